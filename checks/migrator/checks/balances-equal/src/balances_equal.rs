@@ -4,6 +4,7 @@ pub mod test {
 	use anyhow::Context;
 	use mtma_migrator_test_balances_equal_criterion::BalancesEqual;
 	use mtma_migrator_test_types::check::checked_migration;
+	use mtma_migrator_test_types::combinator::Leaf;
 	use mtma_migrator_types::migrator::{movement_migrator::Overlays, MovementMigrator};
 	use mtma_node_null_core::config::Config as MtmaNullConfig;
 	use mtma_node_test_types::prelude::Prelude;
@@ -52,11 +53,11 @@ pub mod test {
 				&mut movement_migrator,
 				&prelude,
 				&migration,
-				vec![balances_equal],
+				vec![Leaf::new(balances_equal)],
 			)
 			.await
 			{
-				Ok(()) => {}
+				Ok(_) => {}
 				Err(e) => {
 					info!("Migration failed: {:?}", e);
 					return Err(anyhow::anyhow!("Migration failed: {:?}", e));
@@ -2,6 +2,7 @@
 pub mod test {
 	use mtma_migrator_test_matching_feature_flags_criterion::GlobalFeatureCheck;
 	use mtma_migrator_test_types::check::checked_migration;
+	use mtma_migrator_test_types::combinator::Leaf;
 	use mtma_migrator_types::migrator::{movement_migrator::Overlays, MovementMigrator};
 	use mtma_node_null_core::config::Config as MtmaNullConfig;
 	use mtma_node_test_types::prelude::Prelude;
@@ -42,7 +43,7 @@ pub mod test {
 			&mut movement_migrator,
 			&prelude,
 			&migration,
-			vec![matching_feature_flags],
+			vec![Leaf::new(matching_feature_flags)],
 		)
 		.await?;
 
@@ -6,6 +6,7 @@ pub mod test {
 	use mtma_migrator_pre_l1_merge_core::config::Config as PreL1MergeConfig;
 	use mtma_migrator_test_accounts_equal_criterion::AccountsEqual;
 	use mtma_migrator_test_types::check::checked_migration;
+	use mtma_migrator_test_types::combinator::Leaf;
 	use mtma_migrator_types::migrator::movement_migrator::{MovementMigrator, Overlays, Runner};
 	use mtma_node_test_types::prelude::Prelude;
 	use mtma_types::movement::aptos_sdk::types::account_config::aptos_test_root_address;
@@ -97,11 +98,11 @@ pub mod test {
 				&mut movement_migrator,
 				&prelude,
 				&migration,
-				Vec::<AccountsEqual>::new(),
+				Vec::<Leaf<AccountsEqual>>::new(),
 			)
 			.await
 			{
-				Ok(()) => {}
+				Ok(_) => {}
 				Err(e) => {
 					info!("Migration failed: {:?}", e);
 					return Err(anyhow::anyhow!("Migration failed: {:?}", e));
@@ -0,0 +1,89 @@
+#[cfg(test)]
+pub mod test {
+
+	use anyhow::Context;
+	use mtma_migrator_test_accounts_equal_criterion::AccountsEqual;
+	use mtma_migrator_test_types::check::checked_migration;
+	use mtma_migrator_test_types::combinator::{Leaf, Verdict};
+	use mtma_migrator_types::migrator::{movement_migrator::Overlays, MovementMigrator};
+	use mtma_node_null_core::config::Config as MtmaNullConfig;
+	use mtma_node_preludes::compose::{Composition, Fund, Transfers};
+	use mtma_node_test_types::criterion::movement_executor::maptos_opt_executor::{
+		aptos_crypto::ed25519::Ed25519PrivateKey, aptos_types::chain_id::ChainId,
+	};
+	use tracing::info;
+
+	#[tokio::test(flavor = "multi_thread")]
+	#[tracing_test::traced_test]
+	async fn test_prelude() -> Result<(), anyhow::Error> {
+		// use a scope to ensure everything is dropped
+		{
+			// Form the migrator.
+			let mut movement_migrator = MovementMigrator::try_temp()?;
+			movement_migrator.set_overlays(Overlays::default());
+
+			// Start the migrator so that it's running in the background.
+			let movement_migrator_for_task = movement_migrator.clone();
+			let movement_migrator_task = kestrel::task(async move {
+				movement_migrator_for_task.run().await?;
+				Ok::<_, anyhow::Error>(())
+			});
+
+			// wait for the rest client to be ready, so the prelude can be signed for the chain
+			let rest_client = movement_migrator
+				.wait_for_rest_client_ready(tokio::time::Duration::from_secs(600))
+				.await
+				.context(
+					"failed to wait for movement migrator rest client while forming the prelude",
+				)?;
+			let chain_id = rest_client
+				.get_index()
+				.await
+				.context("failed to get chain id")?
+				.inner()
+				.chain_id;
+
+			kestrel::end!(movement_migrator_task)?;
+
+			// Form the prelude, funding accounts from the root account and transferring between them.
+			let private_key =
+				Ed25519PrivateKey::try_from(movement_migrator.try_root_private_key()?.as_slice())?;
+			let prelude = Composition::new(private_key, ChainId::new(chain_id))
+				.then(Fund::new(2, 1_000_000_000))
+				.await?
+				.then(Transfers::new(2, 1_000))
+				.await?
+				.into_prelude();
+
+			// Form the migration.
+			let migration_config = MtmaNullConfig::default();
+			let migration = migration_config.build()?;
+
+			// Run the checked migration, which submits the prelude to the restarted node.
+			info!("Running migration");
+			let report = match checked_migration(
+				&mut movement_migrator,
+				&prelude,
+				&migration,
+				vec![Leaf::new(AccountsEqual::new())],
+			)
+			.await
+			{
+				Ok(report) => report,
+				Err(e) => {
+					info!("Migration failed: {:?}", e);
+					return Err(anyhow::anyhow!("Migration failed: {:?}", e));
+				}
+			};
+			let prelude_stage =
+				report.prelude.as_ref().context("the prelude should be reported")?;
+			assert_eq!(prelude_stage.verdict, Verdict::Pass);
+			info!("Migration succeeded");
+		}
+
+		// exit the test is fine when you only have one test per crate because when cargo test is run across a workspace, it actually multi-processes the tests by crate
+		std::process::exit(0);
+
+		// Ok(())
+	}
+}
@@ -3,6 +3,7 @@ pub mod test {
 	use aptos_sdk::types::LocalAccount;
 	use mtma_migrator_test_transacting_criterion::MaptosTransferLifecycle;
 	use mtma_migrator_test_types::check::checked_migration;
+	use mtma_migrator_test_types::combinator::Leaf;
 	use mtma_migrator_types::migrator::{movement_migrator::Overlays, MovementMigrator};
 	use mtma_node_null_core::config::Config as MtmaNullConfig;
 	use mtma_node_test_types::prelude::Prelude;
@@ -40,7 +41,13 @@ pub mod test {
 		// Run the checked migration.
 		let local_account = LocalAccount::generate(&mut rand::rngs::OsRng);
 		let transacting = MaptosTransferLifecycle::new(local_account);
-		checked_migration(&mut movement_migrator, &prelude, &migration, vec![transacting]).await?;
+		checked_migration(
+			&mut movement_migrator,
+			&prelude,
+			&migration,
+			vec![Leaf::new(transacting)],
+		)
+		.await?;
 
 		Ok(())
 	}
@@ -1,29 +1,43 @@
 use anyhow::Context;
 use bcs_ext::conversion::BcsInto;
+use mtma_migrator_test_types::combinator::{BoxedEvaluatish, Leaf, Named};
 use mtma_migrator_test_types::criterion::{
-	Criterion, CriterionError, Criterionish, MovementAptosMigrator, MovementMigrator,
+	Criterion, CriterionError, Criterionish, MovementAptosMigrated, MovementMigrated,
 };
+use mtma_migrator_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_migrator_test_types::registry::{self, Registration};
+use serde::Deserialize;
 use tracing::info;
 
-pub struct BalancesEqual;
+pub struct BalancesEqual {
+	/// The divergences the migration is expected to introduce, e.g. funded or drained accounts.
+	divergences: DivergenceSpec,
+}
 
 impl BalancesEqual {
 	pub fn new() -> Self {
-		Self {}
+		Self { divergences: DivergenceSpec::default() }
 	}
 
 	pub fn criterion() -> Criterion<Self> {
-		Criterion::new(Self {})
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
 	}
 }
 
 impl Criterionish for BalancesEqual {
 	async fn satisfies(
 		&self,
-		movement_migrator: &MovementMigrator,
-		movement_aptos_migrator: &MovementAptosMigrator,
+		movement_migrator: &MovementMigrated,
+		movement_aptos_migrator: &MovementAptosMigrated,
 	) -> Result<(), CriterionError> {
 		let movement_rest_client = movement_migrator
+			.migrator()
 			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(30))
 			.await
 			.context(
@@ -31,13 +45,13 @@ impl Criterionish for BalancesEqual {
 			)
 			.map_err(|e| CriterionError::Internal(e.into()))?;
 		let movement_aptos_rest_client = movement_aptos_migrator
+			.migrator()
 			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(30))
 			.await
 			.context("failed to wait for movement aptos migrator rest client while checking accounts equal")
 			.map_err(|e| CriterionError::Internal(e.into()))?;
 
-		let movement_node =
-			movement_migrator.node().await.map_err(|e| CriterionError::Internal(e.into()))?;
+		let movement_node = movement_migrator.node();
 
 		info!("Iterating over movement node accounts");
 		for account_address_res in movement_node
@@ -78,7 +92,11 @@ impl Criterionish for BalancesEqual {
 				.into_inner();
 
 			info!("Comparing balances");
-			if u64::from(movement_account_balance.coin.value) != movement_aptos_account_balance {
+			if u64::from(movement_account_balance.coin.value) != movement_aptos_account_balance
+				&& !self.divergences.is_explained(
+					&Subject::account(movement_aptos_account_address),
+					Change::Changed,
+				) {
 				return Err(CriterionError::Unsatisfied(
 					format!("movement and movement aptos account balances have different values: {:?} != {:?}", movement_account_balance, movement_aptos_account_balance).into(),
 				));
@@ -88,3 +106,28 @@ impl Criterionish for BalancesEqual {
 		Ok(())
 	}
 }
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "rest-balances-equal";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration {
+	Registration::new(
+		NAME,
+		"Checks that every account balance is equal, read over the REST APIs.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let criterion = BalancesEqual::new().with_divergences(parameters.divergences);
+			Ok(Box::new(Named::new(NAME, Leaf::new(criterion))) as BoxedEvaluatish)
+		},
+	)
+	.with_divergences()
+}
@@ -1,5 +1,5 @@
 use mtma_migrator_test_types::criterion::{
-	Criterion, CriterionError, Criterionish, MovementAptosMigrator, MovementMigrator,
+	Criterion, CriterionError, Criterionish, MovementAptosMigrated, MovementMigrated,
 };
 
 pub struct Empty;
@@ -17,8 +17,8 @@ impl Empty {
 impl Criterionish for Empty {
 	async fn satisfies(
 		&self,
-		_movement_migrator: &MovementMigrator,
-		_movement_aptos_migrator: &MovementAptosMigrator,
+		_movement_migrator: &MovementMigrated,
+		_movement_aptos_migrator: &MovementAptosMigrated,
 	) -> Result<(), CriterionError> {
 		Ok(())
 	}
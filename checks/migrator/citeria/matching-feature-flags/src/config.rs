@@ -0,0 +1,102 @@
+use crate::GlobalFeatureCheck;
+use clap::Parser;
+use mtma_migrator_test_types::divergence::DivergenceSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Errors thrown when working with the [Config].
+#[derive(Debug, thiserror::Error)]
+pub enum GlobalFeatureCheckConfigError {
+	#[error("failed to build from config: {0}")]
+	Build(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The feature-flag contract of a migration target.
+///
+/// All fields should be easily statically encodable to a CLI argument.
+#[derive(Parser, Debug, Serialize, Deserialize, Clone)]
+#[clap(help_expected = true)]
+#[serde(default)]
+pub struct Config {
+	/// Features which must be active on Movement Aptos.
+	#[clap(long, value_delimiter = ',', default_values_t = [73u64])]
+	pub expected_active: Vec<u64>,
+	/// Features which must be inactive on Movement Aptos.
+	#[clap(long, value_delimiter = ',')]
+	pub expected_inactive: Vec<u64>,
+	/// Features which must match between Movement and Movement Aptos.
+	/// If empty, every feature not expected to be active or inactive must match.
+	#[clap(long, value_delimiter = ',')]
+	pub must_match: Vec<u64>,
+	/// A YAML or JSON file of divergences the migration is expected to introduce.
+	/// Features marked expected-changed need not match.
+	#[clap(long, value_parser = |path: &str| DivergenceSpec::load(Path::new(path)))]
+	pub divergences: Option<DivergenceSpec>,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Self {
+			expected_active: vec![73],
+			expected_inactive: vec![],
+			must_match: vec![],
+			divergences: None,
+		}
+	}
+}
+
+impl Config {
+	/// Builds the [GlobalFeatureCheck] from the config.
+	pub fn build(&self) -> Result<GlobalFeatureCheck, GlobalFeatureCheckConfigError> {
+		let expected_active: BTreeSet<u64> = self.expected_active.iter().copied().collect();
+		let expected_inactive: BTreeSet<u64> = self.expected_inactive.iter().copied().collect();
+
+		let contradictions: Vec<u64> =
+			expected_active.intersection(&expected_inactive).copied().collect();
+		if !contradictions.is_empty() {
+			return Err(GlobalFeatureCheckConfigError::Build(
+				format!(
+					"features {:?} are expected to be both active and inactive",
+					contradictions
+				)
+				.into(),
+			));
+		}
+
+		Ok(GlobalFeatureCheck::new()
+			.with_expected_active(expected_active)
+			.with_expected_inactive(expected_inactive)
+			.with_must_match(self.must_match.iter().copied())
+			.with_divergences(self.divergences.clone().unwrap_or_default()))
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	#[test]
+	fn test_build() -> Result<(), GlobalFeatureCheckConfigError> {
+		let config =
+			Config { expected_inactive: vec![5, 5], must_match: vec![1], ..Config::default() };
+		let check = config.build()?;
+		assert_eq!(check.expected_active, BTreeSet::from([73]));
+		assert_eq!(check.expected_inactive, BTreeSet::from([5]));
+		assert_eq!(check.must_match, BTreeSet::from([1]));
+		Ok(())
+	}
+
+	#[test]
+	fn test_build_rejects_contradictions() {
+		let config = Config {
+			expected_active: vec![1, 2],
+			expected_inactive: vec![2, 3],
+			..Config::default()
+		};
+		match config.build() {
+			Ok(_) => panic!("features expected to be both active and inactive should be rejected"),
+			Err(e) => assert!(e.to_string().contains("[2]"), "{}", e),
+		}
+	}
+}
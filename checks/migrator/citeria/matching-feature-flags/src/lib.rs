@@ -1,141 +1,190 @@
+pub mod config;
+
 use aptos_rest_client::aptos_api_types;
 use movement_client::rest_client::aptos_api_types as maptos_api_types;
+use mtma_migrator_test_types::combinator::{BoxedEvaluatish, Leaf, Named};
 use mtma_migrator_test_types::criterion::{
-	Criterion, CriterionError, Criterionish, MovementAptosMigrator, MovementMigrator,
+	Criterion, CriterionError, Criterionish, MovementAptosMigrated, MovementMigrated,
 };
-use serde_json::json;
-use std::collections::HashSet;
+use mtma_migrator_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_migrator_test_types::registry::{self, Registration, RegistryError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::str::FromStr;
 
-pub struct GlobalFeatureCheck;
+pub use config::*;
+
+/// The resource holding the feature bitmap.
+const FEATURES_RESOURCE: &str = "0x1::features::Features";
+
+/// Mirror of `0x1::features::Features`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Features {
+	features: Vec<u8>,
+}
+
+impl Features {
+	/// Whether a feature is enabled in the bitmap.
+	fn is_enabled(&self, feature_id: u64) -> bool {
+		let byte_index = (feature_id / 8) as usize;
+		let bit_mask = 1u8 << (feature_id % 8);
+		self.features.get(byte_index).is_some_and(|byte| byte & bit_mask != 0)
+	}
+
+	/// The highest feature id the bitmap can describe.
+	fn max_feature_id(&self) -> u64 {
+		(self.features.len() as u64 * 8).saturating_sub(1)
+	}
+}
+
+pub struct GlobalFeatureCheck {
+	/// Features which must be active on Movement Aptos.
+	expected_active: BTreeSet<u64>,
+	/// Features which must be inactive on Movement Aptos.
+	expected_inactive: BTreeSet<u64>,
+	/// Features which must match; if empty, all features not otherwise expected must match.
+	must_match: BTreeSet<u64>,
+	/// The divergences the migration is expected to introduce, which excuse features from matching.
+	divergences: DivergenceSpec,
+}
 
 impl GlobalFeatureCheck {
 	pub fn new() -> Self {
-		Self {}
+		Self {
+			expected_active: BTreeSet::from([73]),
+			expected_inactive: BTreeSet::new(),
+			must_match: BTreeSet::new(),
+			divergences: DivergenceSpec::default(),
+		}
 	}
 
 	pub fn criterion() -> Criterion<Self> {
-		Criterion::new(Self {})
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: sets the features which must be active on Movement Aptos.
+	pub fn with_expected_active(mut self, expected_active: impl IntoIterator<Item = u64>) -> Self {
+		self.expected_active = expected_active.into_iter().collect();
+		self
+	}
+
+	/// Builder API: sets the features which must be inactive on Movement Aptos.
+	pub fn with_expected_inactive(
+		mut self,
+		expected_inactive: impl IntoIterator<Item = u64>,
+	) -> Self {
+		self.expected_inactive = expected_inactive.into_iter().collect();
+		self
+	}
+
+	/// Builder API: sets the features which must match.
+	pub fn with_must_match(mut self, must_match: impl IntoIterator<Item = u64>) -> Self {
+		self.must_match = must_match.into_iter().collect();
+		self
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Whether a feature must match between Movement and Movement Aptos.
+	fn must_match(&self, feature_id: u64) -> bool {
+		if self.must_match.is_empty() {
+			!self.expected_active.contains(&feature_id)
+				&& !self.expected_inactive.contains(&feature_id)
+		} else {
+			self.must_match.contains(&feature_id)
+		}
 	}
 }
 
 impl Criterionish for GlobalFeatureCheck {
 	async fn satisfies(
 		&self,
-		movement_migrator: &MovementMigrator,
-		movement_aptos_migrator: &MovementAptosMigrator,
+		movement_migrator: &MovementMigrated,
+		movement_aptos_migrator: &MovementAptosMigrated,
 	) -> Result<(), CriterionError> {
 		let movement_rest_client = movement_migrator
+			.migrator()
 			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(30))
 			.await
 			.map_err(|e| CriterionError::Internal(e.into()))?;
 		let movement_aptos_rest_client = movement_aptos_migrator
+			.migrator()
 			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(30))
 			.await
 			.map_err(|e| CriterionError::Internal(e.into()))?;
 
-		let mut errors = vec![];
-		let expected_active = HashSet::from([73]);
-		let expected_inactive = HashSet::<u64>::new();
-
-		let mut aptos_request = aptos_api_types::ViewRequest {
-			function: aptos_api_types::EntryFunctionId::from_str("0x1::features::is_enabled")
-				.map_err(|e| CriterionError::Internal(e.into()))?,
-			type_arguments: vec![aptos_api_types::MoveType::U64],
-			arguments: vec![],
-		};
-
-		let mut maptos_request = maptos_api_types::ViewRequest {
-			function: maptos_api_types::EntryFunctionId::from_str("0x1::features::is_enabled")
-				.map_err(|e| CriterionError::Internal(e.into()))?,
-			type_arguments: vec![maptos_api_types::MoveType::U64],
-			arguments: vec![],
-		};
-
-		for feature_id in 1u64..=100 {
-			aptos_request.arguments = vec![json!(feature_id)];
-			maptos_request.arguments = vec![json!(feature_id)];
-
-			// Check feature for Aptos executor
-			let aptos_active = movement_aptos_rest_client
-				.view(&aptos_request, None)
-				.await
-				.map_err(|e| {
-					CriterionError::Internal(
-						format!("failed to get Aptos feature flag {}: {:?}", feature_id, e).into(),
-					)
-				})?
-				.into_inner();
-
-			let aptos_active = aptos_active.get(0).ok_or_else(|| {
+		let movement_features = movement_rest_client
+			.get_account_resource_bcs::<Features>(
+				maptos_api_types::Address::from_str("0x1")
+					.map_err(|e| CriterionError::Internal(e.into()))?
+					.into(),
+				FEATURES_RESOURCE,
+			)
+			.await
+			.map_err(|e| {
 				CriterionError::Internal(
-					format!("failed to get Aptos feature flag {}: response is empty", feature_id)
-						.into(),
+					format!("failed to get Movement feature flags: {:?}", e).into(),
 				)
-			})?;
+			})?
+			.into_inner();
 
-			let aptos_active = aptos_active.as_bool().ok_or_else(|| {
-				CriterionError::Internal(
-					format!(
-						"failed to get Aptos feature flag {}: can't convert {:?} into a bool",
-						feature_id, aptos_active
-					)
+		let movement_aptos_features = movement_aptos_rest_client
+			.get_account_resource_bcs::<Features>(
+				aptos_api_types::Address::from_str("0x1")
+					.map_err(|e| CriterionError::Internal(e.into()))?
 					.into(),
-				)
-			})?;
-
-			// Check feature for Maptos executor
-			let maptos_active = movement_rest_client
-				.view(&maptos_request, None)
-				.await
-				.map_err(|e| {
-					CriterionError::Internal(
-						format!("failed to get Movement feature flag {}: {:?}", feature_id, e)
-							.into(),
-					)
-				})?
-				.into_inner();
-
-			let maptos_active = maptos_active.get(0).ok_or_else(|| {
+				FEATURES_RESOURCE,
+			)
+			.await
+			.map_err(|e| {
 				CriterionError::Internal(
-					format!(
-						"failed to get Movement feature flag {}: response is empty",
-						feature_id
-					)
-					.into(),
+					format!("failed to get Movement Aptos feature flags: {:?}", e).into(),
 				)
-			})?;
+			})?
+			.into_inner();
 
-			let maptos_active = maptos_active.as_bool().ok_or_else(|| {
-				CriterionError::Internal(
-					format!(
-						"failed to get Movement feature flag {}: can't convert {:?} into a bool",
-						feature_id, aptos_active
-					)
-					.into(),
-				)
-			})?;
-
-			if !expected_active.contains(&feature_id) {
-				if !maptos_active {
-					errors.push(format!(
-						"Feature {}: Aptos={}, Maptos={} — expected to be active",
-						feature_id, aptos_active, maptos_active
-					));
-				}
-			} else if !expected_inactive.contains(&feature_id) {
-				if maptos_active {
-					errors.push(format!(
-						"Feature {}: Aptos={}, Maptos={} — expected to be inactive",
-						feature_id, aptos_active, maptos_active
-					));
-				}
-			} else if aptos_active != maptos_active {
+		// check every feature either bitmap describes, as well as every feature with an expectation
+		let mut feature_ids: BTreeSet<u64> =
+			(0..=movement_features.max_feature_id().max(movement_aptos_features.max_feature_id()))
+				.collect();
+		feature_ids.extend(&self.expected_active);
+		feature_ids.extend(&self.expected_inactive);
+		feature_ids.extend(&self.must_match);
+
+		let mut errors = vec![];
+		for feature_id in feature_ids {
+			let movement_active = movement_features.is_enabled(feature_id);
+			let movement_aptos_active = movement_aptos_features.is_enabled(feature_id);
+
+			if self.expected_active.contains(&feature_id) && !movement_aptos_active {
 				errors.push(format!(
-					"Feature {}: Aptos={}, Maptos={} — expected to match",
-					feature_id, aptos_active, maptos_active
+					"Feature {}: Movement={}, Movement Aptos={} — expected to be active",
+					feature_id, movement_active, movement_aptos_active
 				));
 			}
+
+			if self.expected_inactive.contains(&feature_id) && movement_aptos_active {
+				errors.push(format!(
+					"Feature {}: Movement={}, Movement Aptos={} — expected to be inactive",
+					feature_id, movement_active, movement_aptos_active
+				));
+			}
+
+			if self.must_match(feature_id) && movement_active != movement_aptos_active {
+				self.divergences.record(
+					&mut errors,
+					&Subject::feature(feature_id),
+					Change::Changed,
+					format!(
+						"Feature {}: Movement={}, Movement Aptos={} — expected to match",
+						feature_id, movement_active, movement_aptos_active
+					),
+				);
+			}
 		}
 
 		if !errors.is_empty() {
@@ -145,3 +194,67 @@ impl Criterionish for GlobalFeatureCheck {
 		Ok(())
 	}
 }
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "matching-feature-flags";
+
+/// Registers the criterion under [NAME], taking the [Config] fields as parameters.
+pub fn registration() -> Registration {
+	Registration::new(
+		NAME,
+		"Checks that the feature flags on Movement Aptos match Movement, up to expected changes.",
+		|parameters| {
+			let config: Config = registry::parameters(NAME, parameters)?;
+			let criterion = config.build().map_err(|e| RegistryError::Build(e.into()))?;
+			Ok(Box::new(Named::new(NAME, Leaf::new(criterion))) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter("expected_active", "Features which must be active on Movement Aptos.", "[73]")
+	.with_parameter("expected_inactive", "Features which must be inactive on Movement Aptos.", "[]")
+	.with_parameter(
+		"must_match",
+		"Features which must match; if empty, every feature not otherwise expected must match.",
+		"[]",
+	)
+	.with_divergences()
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	#[test]
+	fn test_is_enabled() {
+		// features 0, 9 and 15 are enabled
+		let features = Features { features: vec![0b0000_0001, 0b1000_0010] };
+		assert!(features.is_enabled(0));
+		assert!(!features.is_enabled(1));
+		assert!(!features.is_enabled(8));
+		assert!(features.is_enabled(9));
+		assert!(features.is_enabled(15));
+		assert_eq!(features.max_feature_id(), 15);
+	}
+
+	#[test]
+	fn test_is_enabled_past_the_end() {
+		let features = Features { features: vec![0xff] };
+		assert!(features.is_enabled(7));
+		assert!(!features.is_enabled(8));
+		assert!(!features.is_enabled(u64::MAX));
+		assert_eq!(Features { features: vec![] }.max_feature_id(), 0);
+	}
+
+	#[test]
+	fn test_must_match() {
+		// every feature without an expectation must match by default
+		let check = GlobalFeatureCheck::new().with_expected_inactive([5]);
+		assert!(!check.must_match(73));
+		assert!(!check.must_match(5));
+		assert!(check.must_match(1));
+
+		// only the listed features must match otherwise
+		let check = check.with_must_match([1]);
+		assert!(check.must_match(1));
+		assert!(!check.must_match(2));
+	}
+}
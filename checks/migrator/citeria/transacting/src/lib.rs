@@ -10,7 +10,7 @@ use aptos_sdk::types::{
 	LocalAccount,
 };
 use mtma_migrator_test_types::criterion::{
-	Criterion, CriterionError, Criterionish, MovementAptosMigrator, MovementMigrator,
+	Criterion, CriterionError, Criterionish, MovementAptosMigrated, MovementMigrated,
 };
 use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -54,15 +54,17 @@ impl MaptosTransferLifecycle {
 impl Criterionish for MaptosTransferLifecycle {
 	async fn satisfies(
 		&self,
-		_movement_migrator: &MovementMigrator,
-		movement_aptos_migrator: &MovementAptosMigrator,
+		_movement_migrator: &MovementMigrated,
+		movement_aptos_migrator: &MovementAptosMigrated,
 	) -> Result<(), CriterionError> {
 		let movement_aptos_rest_client = movement_aptos_migrator
+			.migrator()
 			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(30))
 			.await
 			.map_err(|e| CriterionError::Internal(e.into()))?;
 
 		let movement_aptos_faucet_client = movement_aptos_migrator
+			.migrator()
 			.wait_for_faucet_client_ready(tokio::time::Duration::from_secs(30))
 			.await
 			.map_err(|e| CriterionError::Internal(e.into()))?;
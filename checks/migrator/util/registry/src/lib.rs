@@ -0,0 +1,77 @@
+use mtma_migrator_test_types::registry::{lift, Registry};
+use mtma_node_test_types::combinator::BoxedEvaluatish as NodeBoxedEvaluatish;
+use mtma_node_test_types::registry::Registry as NodeRegistry;
+
+/// The registry of every node-level criterion, which can be checked against the nodes alone, e.g. a snapshot.
+pub fn node_registry() -> NodeRegistry<NodeBoxedEvaluatish> {
+	NodeRegistry::new()
+		.with(mtma_node_test_global_storage_includes_criterion::registration())
+		.with(mtma_node_test_global_storage_injective_criterion::registration())
+		.with(mtma_node_test_global_storage_not_empty_criterion::registration())
+		.with(mtma_node_test_modules_equal_criterion::registration())
+		.with(mtma_node_test_supply_conserved_criterion::registration())
+		.with(mtma_node_test_balances_equal_criterion::registration())
+		.with(mtma_node_test_on_chain_configs_equal_criterion::registration())
+		.with(mtma_node_test_account_keys_preserved_criterion::registration())
+		.with(mtma_node_test_resources_equal_criterion::registration())
+		.with(mtma_node_test_transactions_equivalent_criterion::registration())
+}
+
+/// The registry of every criterion which can be built by name.
+///
+/// Node-level criteria are lifted, so they are evaluated against the nodes derived from the migrators.
+/// Criteria which need more than parameters to be built, such as `transacting` which needs a funded account, are not registered.
+pub fn registry() -> Registry {
+	node_registry()
+		.into_registrations()
+		.fold(Registry::new(), |registry, registration| registry.with(lift(registration)))
+		// migrator
+		.with(mtma_migrator_test_matching_feature_flags_criterion::registration())
+		.with(mtma_migrator_test_accounts_equal_criterion::registration())
+		.with(mtma_migrator_test_balances_equal_criterion::registration())
+}
+
+#[cfg(test)]
+pub mod test {
+
+	use super::*;
+	use mtma_migrator_test_types::registry::Parameter;
+
+	#[test]
+	fn test_registry_builds_all_with_defaults() -> Result<(), anyhow::Error> {
+		let registry = registry();
+		let criteria = registry.build_all(&[])?;
+		assert_eq!(criteria.len(), registry.names().count());
+
+		// every node-level criterion is also registered at the migrator level
+		let node_registry = node_registry();
+		assert_eq!(node_registry.build_all(&[])?.len(), node_registry.names().count());
+		for name in node_registry.names() {
+			registry.get(name)?;
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_registry_passes_parameters() -> Result<(), anyhow::Error> {
+		let registry = registry();
+		let parameters: Vec<Parameter> = vec![
+			"matching-feature-flags.expected_active=[73,74]".parse()?,
+			"global-storage-includes.versions={\"stride\":100}".parse()?,
+		];
+		let names =
+			vec!["matching-feature-flags".to_string(), "global-storage-includes".to_string()];
+		registry.build(&names, &parameters)?;
+
+		// parameters must name a selected criterion
+		assert!(registry.build(&names[..1], &parameters).is_err());
+
+		// contradicting parameters fail to build
+		let parameters: Vec<Parameter> = vec![
+			"matching-feature-flags.expected_active=[73]".parse()?,
+			"matching-feature-flags.expected_inactive=[73]".parse()?,
+		];
+		assert!(registry.build(&names[..1], &parameters).is_err());
+		Ok(())
+	}
+}
@@ -1,52 +1,153 @@
-use crate::criterion::{Criterionish, MovementMigrator};
+use crate::combinator::Evaluatish;
+use crate::criterion::{Migrated, MovementAptosMigrated, MovementMigrated, MovementMigrator};
+use crate::prelude::RestPreludeRunner;
 use anyhow::Context;
 use mtma_migrator_types::migration::Migrationish;
 use mtma_node_test_types::prelude::Prelude;
+pub use mtma_node_test_types::report::{CheckReport, MigrationReport, StageReport};
+use std::sync::Arc;
+use std::time::Instant;
 use tracing::info;
 
 /// Errors thrown when working with the [Config].
 #[derive(Debug, thiserror::Error)]
 pub enum CheckError {
 	#[error("checked migration encountered an error while running prelude: {0}")]
-	Prelude(#[source] Box<dyn std::error::Error + Send + Sync>),
+	Prelude(#[source] Box<dyn std::error::Error + Send + Sync>, Box<CheckReport>),
 	#[error("checked migration encountered an error while running migration: {0}")]
-	Migration(#[source] Box<dyn std::error::Error + Send + Sync>),
-	#[error("checked migration encountered an error while satisfying criteria: {0}")]
-	Criteria(#[source] Box<dyn std::error::Error + Send + Sync>),
+	Migration(#[source] Box<dyn std::error::Error + Send + Sync>, Box<CheckReport>),
+	#[error("{} of {} criteria were not satisfied:\n{}", .0.failures().count(), .0.criteria.len(), .0)]
+	Unsatisfied(Box<CheckReport>),
 	#[error("checked migration encountered an internal error: {0}")]
 	Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
+impl CheckError {
+	/// The report of the check as far as it got, with the stage which could not complete reported as errored.
+	///
+	/// Only an internal error, which is thrown before any stage is run, carries no report.
+	pub fn report(&self) -> Option<&CheckReport> {
+		match self {
+			CheckError::Prelude(_, report)
+			| CheckError::Migration(_, report)
+			| CheckError::Unsatisfied(report) => Some(report),
+			CheckError::Internal(_) => None,
+		}
+	}
+}
+
 /// Runs a migration where prelude is executed, the migration is run, and then the criteria are checked.
-pub async fn checked_migration<T: Criterionish + Send + Sync>(
+///
+/// The prelude is submitted to the running Movement node with the default [RestPreludeRunner], see [checked_migration_with].
+/// The criteria are trees over the migrators, so a migrator criterion is passed as a [crate::combinator::Leaf].
+/// Every criterion is evaluated, even if an earlier one fails, and the migrator tasks are always ended.
+/// Returns a [CheckReport] of the stages and criteria, which is carried by [CheckError::Unsatisfied] if any criterion failed,
+/// or by [CheckError::Prelude] and [CheckError::Migration] with the stage which could not complete reported as errored.
+pub async fn checked_migration<T>(
+	movement_migrator: &mut MovementMigrator,
+	prelude: &Prelude,
+	migration: &impl Migrationish,
+	criteria: Vec<T>,
+) -> Result<CheckReport, CheckError>
+where
+	T: Evaluatish<MovementMigrated, MovementAptosMigrated> + Send + Sync,
+{
+	checked_migration_with(
+		movement_migrator,
+		prelude,
+		&RestPreludeRunner::default(),
+		migration,
+		criteria,
+	)
+	.await
+}
+
+/// Runs a checked migration, submitting the prelude with the given runner.
+///
+/// The Movement node is run while the prelude is submitted and stopped before migrating,
+/// so the prelude is committed by the node and its DB is not copied mid-write.
+pub async fn checked_migration_with<T>(
 	movement_migrator: &mut MovementMigrator,
 	prelude: &Prelude,
+	prelude_runner: &RestPreludeRunner,
 	migration: &impl Migrationish,
 	criteria: Vec<T>,
-) -> Result<(), CheckError> {
-	// Get the executor
-	info!("Getting movement executor");
-	let mut movement_executor = movement_migrator
-		.node()
-		.await
-		.context("failed to get movement node")
-		.map_err(|e| CheckError::Internal(e.into()))?;
-
-	// Run the prelude
-	info!("Running prelude");
-	prelude
-		.run(&mut movement_executor)
-		.await
-		.context("failed to run prelude")
-		.map_err(|e| CheckError::Prelude(e.into()))?;
-
-	// Run the migration
+) -> Result<CheckReport, CheckError>
+where
+	T: Evaluatish<MovementMigrated, MovementAptosMigrated> + Send + Sync,
+{
+	// Run the prelude, skipping the node entirely when there is nothing to submit
+	let start = Instant::now();
+	if !prelude.is_empty() {
+		info!("Tasking movement migrator for prelude");
+		let movement_migrator_for_task = movement_migrator.clone();
+		let prelude_task = kestrel::task(async move {
+			movement_migrator_for_task.run().await?;
+			Ok::<_, anyhow::Error>(())
+		});
+
+		info!("Running prelude");
+		let prelude_output = prelude_runner.run(movement_migrator, prelude).await;
+
+		// stop the node whatever the outcome
+		let prelude_task_end =
+			kestrel::end!(prelude_task).context("failed to kill movement migrator after prelude");
+		let prelude_output = prelude_output
+			.context("failed to run prelude")
+			.and_then(|prelude_output| prelude_task_end.map(|_| prelude_output));
+		let prelude_output = match prelude_output {
+			Ok(prelude_output) => prelude_output,
+			Err(e) => {
+				let report = CheckReport::prelude_error(format!("{:#}", e), start.elapsed());
+				return Err(CheckError::Prelude(e.into(), Box::new(report)));
+			}
+		};
+
+		info!(
+			"Ran {} prelude transactions using {} gas",
+			prelude_output.transactions.len(),
+			prelude_output.gas_used()
+		);
+	}
+	let prelude_report = StageReport::pass(start.elapsed());
+
+	// Run the migration, reading the versions off the movement node, which now includes the prelude, and the migrated node
 	info!("Running migration");
-	let movement_aptos_migrator = migration
-		.migrate(movement_migrator)
-		.await
-		.context("failed to run migration")
-		.map_err(|e| CheckError::Migration(e.into()))?;
+	let start = Instant::now();
+	let migrated = async {
+		let movement_executor =
+			movement_migrator.node().await.context("failed to get movement node")?;
+		let movement_aptos_migrator =
+			migration.migrate(movement_migrator).await.context("failed to run migration")?;
+		let movement_aptos_executor = movement_aptos_migrator
+			.node()
+			.await
+			.context("failed to get movement aptos node")?;
+		let migration_report = MigrationReport {
+			stage: StageReport::pass(start.elapsed()),
+			output_path: Some(movement_aptos_executor.db_dir_path().clone()),
+			movement_version: Some(
+				movement_executor
+					.latest_ledger_version()
+					.context("failed to get movement ledger version")?,
+			),
+			movement_aptos_version: Some(
+				movement_aptos_executor
+					.latest_ledger_version()
+					.context("failed to get movement aptos ledger version")?,
+			),
+		};
+		Ok::<_, anyhow::Error>((movement_aptos_migrator, migration_report))
+	}
+	.await;
+	let (movement_aptos_migrator, mut migration_report) = match migrated {
+		Ok(migrated) => migrated,
+		Err(e) => {
+			let report =
+				CheckReport::migration_error(prelude_report, format!("{:#}", e), start.elapsed());
+			return Err(CheckError::Migration(e.into(), Box::new(report)));
+		}
+	};
 
 	// start the movement migrator
 	info!("Tasking movement migrator");
@@ -64,47 +165,79 @@ pub async fn checked_migration<T: Criterionish + Send + Sync>(
 		Ok::<_, anyhow::Error>(())
 	});
 
-	// wait for the rest client to be ready
-	// once we have this, there should also be a config, so we can then kill off the migrator and proceed
-	info!("Waiting for movement migrator rest client");
-	movement_migrator
-		.wait_for_rest_client_ready(tokio::time::Duration::from_secs(120))
-		.await
-		.context("failed to wait for movement migrator rest client")
-		.map_err(|e| CheckError::Migration(e.into()))?;
-
-	// wait for movement aptos migrator to be ready
-	info!("Waiting for movement aptos migrator rest client");
-	movement_aptos_migrator
-		.wait_for_rest_client_ready(tokio::time::Duration::from_secs(120))
-		.await
-		.context("failed to wait for movement aptos migrator rest client")
-		.map_err(|e| CheckError::Migration(e.into()))?;
-
-	// Run the criteria
-	info!("Running criteria");
-	for criterion in criteria {
-		match criterion
-			.satisfies(&movement_migrator, &movement_aptos_migrator)
+	// wait for the migrators and run the criteria, so that the tasks are ended whatever the outcome
+	let evaluations = async {
+		// wait for the rest client to be ready
+		// once we have this, there should also be a config, so we can then kill off the migrator and proceed
+		info!("Waiting for movement migrator rest client");
+		movement_migrator
+			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(120))
 			.await
-			.context("failed to satisfy criterion")
-			.map_err(|e| CheckError::Criteria(e.into()))
-		{
-			Ok(()) => {}
-			Err(e) => {
-				info!("Criterion failed: {:?}", e);
-				return Err(e);
-			}
+			.context("failed to wait for movement migrator rest client")?;
+
+		// wait for movement aptos migrator to be ready
+		info!("Waiting for movement aptos migrator rest client");
+		movement_aptos_migrator
+			.wait_for_rest_client_ready(tokio::time::Duration::from_secs(120))
+			.await
+			.context("failed to wait for movement aptos migrator rest client")?;
+
+		// open the nodes once, and pass them to every criterion
+		info!("Opening nodes for the criteria");
+		let movement = Arc::new(Migrated::new(
+			movement_migrator.clone(),
+			Arc::new(movement_migrator.node().await.context("failed to open movement node")?),
+		));
+		let movement_aptos = Arc::new(Migrated::new(
+			movement_aptos_migrator.clone(),
+			Arc::new(
+				movement_aptos_migrator
+					.node()
+					.await
+					.context("failed to open movement aptos node")?,
+			),
+		));
+
+		// Run the criteria
+		info!("Running criteria");
+		let mut evaluations = Vec::with_capacity(criteria.len());
+		for criterion in criteria {
+			let evaluation = criterion.evaluate(&movement, &movement_aptos).await;
+			info!("Evaluated criterion:\n{}", evaluation);
+			evaluations.push(evaluation);
 		}
+
+		Ok::<_, anyhow::Error>(evaluations)
 	}
+	.await;
+
+	// kill the migrators, both of them even if the first fails to end
+	info!("Ending migrator tasks");
+	let movement_migrator_end =
+		kestrel::end!(movement_migrator_task).context("failed to kill movement migrator");
+	let movement_aptos_migrator_end = kestrel::end!(movement_aptos_migrator_task)
+		.context("failed to kill movement aptos migrator");
 
-	// kill the movement migrator
-	kestrel::end!(movement_migrator_task)
-		.context("failed to kill movement migrator")
-		.map_err(|e| CheckError::Migration(e.into()))?;
-	kestrel::end!(movement_aptos_migrator_task)
-		.context("failed to kill movement aptos migrator")
-		.map_err(|e| CheckError::Migration(e.into()))?;
+	// the migrated nodes must have run and stopped cleanly, otherwise the migration is reported as errored
+	let (evaluations, ended) = match evaluations {
+		Ok(evaluations) => (evaluations, movement_migrator_end.and(movement_aptos_migrator_end)),
+		Err(e) => (Vec::new(), Err(e)),
+	};
+	if let Err(e) = &ended {
+		migration_report.stage =
+			StageReport::error(format!("{:#}", e), migration_report.stage.duration);
+	}
+	let report = CheckReport {
+		prelude: Some(prelude_report),
+		migration: Some(migration_report),
+		criteria: evaluations,
+	};
+	if let Err(e) = ended {
+		return Err(CheckError::Migration(e.into(), Box::new(report)));
+	}
+	if !report.passed() {
+		return Err(CheckError::Unsatisfied(Box::new(report)));
+	}
 
-	Ok(())
+	Ok(report)
 }
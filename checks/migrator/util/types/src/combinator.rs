@@ -0,0 +1,83 @@
+//! The combinators of [mtma_node_test_types::combinator], evaluated against the migrators rather than the nodes.
+use crate::criterion::{Criterionish, MovementAptosMigrated, MovementMigrated};
+use mtma_node_test_types::combinator as node;
+pub use mtma_node_test_types::combinator::{EvaluationFuture, Evaluatish, Named, Not, WithTimeout};
+pub use mtma_node_test_types::evaluation::{Evaluation, Verdict};
+use std::sync::Arc;
+
+/// A boxed [Evaluatish] over the migrators, as held by the combinators.
+pub type BoxedEvaluatish = node::BoxedEvaluatish<MovementMigrated, MovementAptosMigrated>;
+
+/// Passes if all children pass, see [node::AllOf].
+pub type AllOf = node::AllOf<MovementMigrated, MovementAptosMigrated>;
+
+/// Passes if any child passes, see [node::AnyOf].
+pub type AnyOf = node::AnyOf<MovementMigrated, MovementAptosMigrated>;
+
+/// A single migrator criterion, as a leaf of the tree.
+pub struct Leaf<T> {
+	criterion: Arc<T>,
+}
+
+impl<T> Leaf<T>
+where
+	T: Criterionish,
+{
+	pub fn new(criterion: T) -> Self {
+		Self { criterion: Arc::new(criterion) }
+	}
+}
+
+impl<T> Evaluatish<MovementMigrated, MovementAptosMigrated> for Leaf<T>
+where
+	T: Criterionish + Send + 'static,
+{
+	fn name(&self) -> String {
+		Criterionish::name(self.criterion.as_ref())
+	}
+
+	fn evaluate(
+		&self,
+		movement_e2e_client: &Arc<MovementMigrated>,
+		movement_aptos_e2e_client: &Arc<MovementAptosMigrated>,
+	) -> EvaluationFuture {
+		let criterion = self.criterion.clone();
+		let movement_e2e_client = movement_e2e_client.clone();
+		let movement_aptos_e2e_client = movement_aptos_e2e_client.clone();
+		Box::pin(async move {
+			criterion.evaluate(&movement_e2e_client, &movement_aptos_e2e_client).await
+		})
+	}
+}
+
+/// Evaluates a node-level tree against the nodes the check opened from the migrators.
+pub struct FromNode<E> {
+	inner: E,
+}
+
+impl<E> FromNode<E>
+where
+	E: Evaluatish,
+{
+	pub fn new(inner: E) -> Self {
+		Self { inner }
+	}
+}
+
+impl<E> Evaluatish<MovementMigrated, MovementAptosMigrated> for FromNode<E>
+where
+	E: Evaluatish,
+{
+	fn name(&self) -> String {
+		self.inner.name()
+	}
+
+	fn evaluate(
+		&self,
+		movement_e2e_client: &Arc<MovementMigrated>,
+		movement_aptos_e2e_client: &Arc<MovementAptosMigrated>,
+	) -> EvaluationFuture {
+		self.inner
+			.evaluate(movement_e2e_client.node(), movement_aptos_e2e_client.node())
+	}
+}
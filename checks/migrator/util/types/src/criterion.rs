@@ -1,6 +1,11 @@
 pub use mtma_migrator_types::migrator::MovementAptosMigrator;
 pub use mtma_migrator_types::migrator::MovementMigrator;
+use mtma_node_test_types::criterion::CriterionError as NodeCriterionError;
+use mtma_node_test_types::criterion::{MovementAptosNode, MovementNode};
+use mtma_node_test_types::evaluation::{short_type_name, Evaluation, Verdict};
 use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Errors thrown when working with the [Config].
 #[derive(Debug, thiserror::Error)]
@@ -11,13 +16,85 @@ pub enum CriterionError {
 	Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
-pub trait Criterionish {
+impl From<NodeCriterionError> for CriterionError {
+	fn from(e: NodeCriterionError) -> Self {
+		match e {
+			NodeCriterionError::Unsatisfied(e) => CriterionError::Unsatisfied(e),
+			NodeCriterionError::Internal(e) => CriterionError::Internal(e),
+		}
+	}
+}
+
+/// A migrator under check, with the node the check opened from it once, so that every criterion shares the node.
+pub struct Migrated<T, N> {
+	/// The migrator.
+	migrator: T,
+	/// The node opened from the migrator.
+	node: Arc<N>,
+}
+
+impl<T, N> Migrated<T, N> {
+	pub fn new(migrator: T, node: Arc<N>) -> Self {
+		Self { migrator, node }
+	}
+
+	/// Borrows the migrator.
+	pub fn migrator(&self) -> &T {
+		&self.migrator
+	}
+
+	/// Borrows the node opened from the migrator.
+	pub fn node(&self) -> &Arc<N> {
+		&self.node
+	}
+}
+
+/// The Movement side of a check, see [Migrated].
+pub type MovementMigrated = Migrated<MovementMigrator, MovementNode>;
+
+/// The Movement Aptos side of a check, see [Migrated].
+pub type MovementAptosMigrated = Migrated<MovementAptosMigrator, MovementAptosNode>;
+
+/// A criterion checked against the migrators, and the nodes opened from them.
+///
+/// Its futures are `Send`, so that it can be a [crate::combinator::Leaf] of a tree like any node criterion.
+/// A node criterion is checked at this level through [crate::combinator::FromNode].
+pub trait Criterionish: Sync {
 	/// Whether the criterion is satisfied by the given movement and movement_aptos executors.
 	fn satisfies(
 		&self,
-		movement_e2e_client: &MovementMigrator,
-		movement_aptos_e2e_client: &MovementAptosMigrator,
-	) -> impl Future<Output = Result<(), CriterionError>>;
+		movement_e2e_client: &MovementMigrated,
+		movement_aptos_e2e_client: &MovementAptosMigrated,
+	) -> impl Future<Output = Result<(), CriterionError>> + Send;
+
+	/// The name under which the criterion is reported.
+	fn name(&self) -> String {
+		short_type_name::<Self>()
+	}
+
+	/// Evaluates the criterion, timing it.
+	///
+	/// Combinators override this to report their whole tree.
+	fn evaluate(
+		&self,
+		movement_e2e_client: &MovementMigrated,
+		movement_aptos_e2e_client: &MovementAptosMigrated,
+	) -> impl Future<Output = Evaluation> + Send {
+		async move {
+			let start = Instant::now();
+			let result = self.satisfies(movement_e2e_client, movement_aptos_e2e_client).await;
+			let duration = start.elapsed();
+			match result {
+				Ok(()) => Evaluation::leaf(self.name(), Verdict::Pass, None, duration),
+				Err(CriterionError::Unsatisfied(e)) => {
+					Evaluation::leaf(self.name(), Verdict::Fail, Some(e.to_string()), duration)
+				}
+				Err(CriterionError::Internal(e)) => {
+					Evaluation::leaf(self.name(), Verdict::Error, Some(e.to_string()), duration)
+				}
+			}
+		}
+	}
 }
 
 /// The criterion type simply
@@ -36,8 +113,8 @@ where
 	/// Whether the criterion is satisfied by the given movement and movement_aptos executors.
 	pub async fn satisfies(
 		&self,
-		movement_e2e_client: &MovementMigrator,
-		movement_aptos_e2e_client: &MovementAptosMigrator,
+		movement_e2e_client: &MovementMigrated,
+		movement_aptos_e2e_client: &MovementAptosMigrated,
 	) -> Result<(), CriterionError> {
 		self.0.satisfies(movement_e2e_client, movement_aptos_e2e_client).await
 	}
@@ -1,2 +1,6 @@
 pub mod check;
+pub mod combinator;
 pub mod criterion;
+pub mod prelude;
+pub use mtma_node_test_types::divergence;
+pub mod registry;
@@ -0,0 +1,175 @@
+use crate::criterion::MovementMigrator;
+use mtma_node_test_types::prelude::{
+	Outcome, Prelude, PreludeError, PreludeOutput, TransactionOutput,
+};
+use mtma_types::movement::aptos_types::transaction::SignedTransaction;
+use mtma_types::movement::movement_client::rest_client::{
+	aptos_api_types::TransactionData, error::RestError, Client as MovementRestClient,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+/// How often the REST API is polled for a submitted transaction.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs a prelude against the running Movement node of a migrator, by submitting its transactions over the REST API.
+///
+/// Unlike [Prelude::run], which executes blocks into a copy of the DB which is then thrown away,
+/// the prelude is committed by the node itself, so its state is part of what gets migrated.
+/// Each block is submitted once the last is committed, though the node chooses its own block boundaries.
+#[derive(Debug, Clone)]
+pub struct RestPreludeRunner {
+	/// How long to wait for the REST API to be ready.
+	ready_timeout: Duration,
+	/// How long to wait for each block to be committed.
+	commit_timeout: Duration,
+	/// Whether transactions which abort are allowed.
+	allow_aborts: bool,
+}
+
+impl Default for RestPreludeRunner {
+	fn default() -> Self {
+		Self {
+			// the nix flake can be slow to come up the first time
+			ready_timeout: Duration::from_secs(600),
+			commit_timeout: Duration::from_secs(120),
+			allow_aborts: false,
+		}
+	}
+}
+
+impl RestPreludeRunner {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets how long to wait for the REST API to be ready.
+	pub fn with_ready_timeout(mut self, ready_timeout: Duration) -> Self {
+		self.ready_timeout = ready_timeout;
+		self
+	}
+
+	/// Builder API: sets how long to wait for each block to be committed.
+	pub fn with_commit_timeout(mut self, commit_timeout: Duration) -> Self {
+		self.commit_timeout = commit_timeout;
+		self
+	}
+
+	/// Builder API: sets whether transactions which abort are allowed, rather than failing the prelude.
+	pub fn with_allow_aborts(mut self, allow_aborts: bool) -> Self {
+		self.allow_aborts = allow_aborts;
+		self
+	}
+
+	/// Submits the prelude to the running node of the migrator, returning the output of each transaction once committed.
+	///
+	/// Fails if any transaction is discarded, i.e. rejected or not committed in time, or aborts unless aborts are allowed.
+	pub async fn run(
+		&self,
+		movement_migrator: &MovementMigrator,
+		prelude: &Prelude,
+	) -> Result<PreludeOutput, PreludeError> {
+		let rest_client = movement_migrator
+			.wait_for_rest_client_ready(self.ready_timeout)
+			.await
+			.map_err(|e| PreludeError::Internal(e.into()))?;
+
+		let mut transactions = Vec::new();
+		for (index, block) in prelude.blocks().iter().enumerate() {
+			debug!("Submitting prelude block {} of {} transactions", index, block.len());
+
+			// submit the whole block before waiting, so the node may commit it together
+			let mut submitted = Vec::with_capacity(block.len());
+			for signed_transaction in block {
+				match rest_client.submit_bcs(signed_transaction).await {
+					Ok(_) => submitted.push(Some(signed_transaction)),
+					Err(e) => {
+						info!(
+							"Prelude transaction {} was rejected: {}",
+							signed_transaction.committed_hash(),
+							e
+						);
+						submitted.push(None);
+					}
+				}
+			}
+
+			let deadline = Instant::now() + self.commit_timeout;
+			for (signed_transaction, submitted) in block.iter().zip(submitted) {
+				let output = match submitted {
+					Some(signed_transaction) => {
+						self.wait_for(&rest_client, signed_transaction, deadline).await?
+					}
+					None => discarded(signed_transaction),
+				};
+				transactions.push(output);
+			}
+		}
+
+		let output = PreludeOutput::new(transactions);
+		let failures: Vec<String> = output
+			.failures(self.allow_aborts)
+			.map(|transaction| transaction.to_string())
+			.collect();
+		if !failures.is_empty() {
+			return Err(PreludeError::Failed(failures.join("\n"), Box::new(output)));
+		}
+
+		Ok(output)
+	}
+
+	/// Waits for a submitted transaction to be committed, treating it as discarded if it is not by the deadline.
+	async fn wait_for(
+		&self,
+		rest_client: &MovementRestClient,
+		signed_transaction: &SignedTransaction,
+		deadline: Instant,
+	) -> Result<TransactionOutput, PreludeError> {
+		let hash = signed_transaction.committed_hash();
+		loop {
+			match rest_client.get_transaction_by_hash_bcs(hash).await {
+				Ok(response) => {
+					if let TransactionData::OnChain(committed) = response.into_inner() {
+						return Ok(TransactionOutput {
+							hash,
+							sender: signed_transaction.sender(),
+							sequence_number: signed_transaction.sequence_number(),
+							outcome: Outcome::Kept(committed.info.status().clone()),
+							version: Some(committed.version),
+							gas_used: committed.info.gas_used(),
+							events: committed.events,
+						});
+					}
+				}
+				// the transaction is not found until it reaches the mempool, so only that is retried
+				Err(e) if is_not_found(&e) => {}
+				Err(e) => return Err(PreludeError::Internal(e.into())),
+			}
+
+			if Instant::now() >= deadline {
+				info!("Prelude transaction {} was not committed in time", hash);
+				return Ok(discarded(signed_transaction));
+			}
+			tokio::time::sleep(POLL_INTERVAL).await;
+		}
+	}
+}
+
+/// Whether an error from the REST API is that what was asked for was not found.
+fn is_not_found(error: &RestError) -> bool {
+	match error {
+		RestError::Api(response) => response.status_code.as_u16() == 404,
+		RestError::Http(status_code, _) => status_code.as_u16() == 404,
+		_ => false,
+	}
+}
+
+/// The output of a transaction which was not committed.
+fn discarded(signed_transaction: &SignedTransaction) -> TransactionOutput {
+	TransactionOutput::discarded(
+		signed_transaction.committed_hash(),
+		signed_transaction.sender(),
+		signed_transaction.sequence_number(),
+	)
+}
@@ -0,0 +1,15 @@
+use crate::combinator::{BoxedEvaluatish, FromNode};
+use mtma_node_test_types::combinator::BoxedEvaluatish as NodeBoxedEvaluatish;
+use mtma_node_test_types::registry as node_registry;
+pub use mtma_node_test_types::registry::{parameters, Parameter, ParameterSpec, RegistryError};
+
+/// A migrator-level criterion as registered under a name.
+pub type Registration = node_registry::Registration<BoxedEvaluatish>;
+
+/// Migrator-level criteria by name.
+pub type Registry = node_registry::Registry<BoxedEvaluatish>;
+
+/// Lifts a node-level registration, so the criterion is evaluated against the nodes derived from the migrators.
+pub fn lift(registration: node_registry::Registration<NodeBoxedEvaluatish>) -> Registration {
+	registration.map(|inner| Box::new(FromNode::new(inner)) as BoxedEvaluatish)
+}
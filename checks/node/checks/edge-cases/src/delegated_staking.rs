@@ -0,0 +1,25 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::check_edge_case;
+	use mtma_node_null_core::config::Config as MtmaNullConfig;
+	use mtma_node_preludes::edge_cases::delegated_staking::DelegatedStakingPrelude;
+	use mtma_node_replay_core::config::Config as MtmaReplayConfig;
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_delegated_staking_null() -> Result<(), anyhow::Error> {
+		let migration = MtmaNullConfig::default().build()?;
+		check_edge_case(DelegatedStakingPrelude::new(), &migration).await?;
+		Ok(())
+	}
+
+	#[ignore]
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_delegated_staking_replay() -> Result<(), anyhow::Error> {
+		let migration = MtmaReplayConfig::default().use_migrated_genesis(true).build()?;
+		check_edge_case(DelegatedStakingPrelude::new(), &migration).await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,25 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::check_edge_case;
+	use mtma_node_null_core::config::Config as MtmaNullConfig;
+	use mtma_node_preludes::edge_cases::large_tables::LargeTablesPrelude;
+	use mtma_node_replay_core::config::Config as MtmaReplayConfig;
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_large_tables_null() -> Result<(), anyhow::Error> {
+		let migration = MtmaNullConfig::default().build()?;
+		check_edge_case(LargeTablesPrelude::new(), &migration).await?;
+		Ok(())
+	}
+
+	#[ignore]
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_large_tables_replay() -> Result<(), anyhow::Error> {
+		let migration = MtmaReplayConfig::default().use_migrated_genesis(true).build()?;
+		check_edge_case(LargeTablesPrelude::new(), &migration).await?;
+		Ok(())
+	}
+}
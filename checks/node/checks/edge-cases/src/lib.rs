@@ -0,0 +1,56 @@
+pub mod delegated_staking;
+pub mod deleted_resources;
+pub mod large_tables;
+pub mod max_size_values;
+pub mod multisig;
+pub mod paired_balances;
+pub mod resource_accounts;
+pub mod rotated_keys;
+
+use mtma_node_preludes::compose::{Composition, Stage};
+use mtma_node_test_account_keys_preserved_criterion::AccountKeysPreserved;
+use mtma_node_test_balances_equal_criterion::BalancesEqual;
+use mtma_node_test_global_storage_includes_criterion::GlobalStorageIncludes;
+use mtma_node_test_modules_equal_criterion::ModulesEqual;
+use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+use mtma_node_test_types::{
+	check::checked_migration,
+	criterion::{
+		movement_executor::{MovementNode, MovementOptExecutor},
+		AsyncCriterionish,
+	},
+	report::CheckReport,
+};
+use mtma_node_types::migration::Migrationish;
+
+/// The criteria every edge case is checked against.
+pub fn criteria() -> Vec<Box<dyn AsyncCriterionish + Send + Sync>> {
+	vec![
+		Box::new(GlobalStorageIncludes::new()),
+		Box::new(AccountKeysPreserved::new()),
+		Box::new(BalancesEqual::new()),
+		Box::new(ModulesEqual::new()),
+		Box::new(ResourcesEqual::new()),
+	]
+}
+
+/// Generates an edge case prelude against a fresh Movement node, and checks it through the migration against [criteria].
+pub async fn check_edge_case(
+	stage: impl Stage,
+	migration: &impl Migrationish,
+) -> Result<CheckReport, anyhow::Error> {
+	// form the executor
+	let (movement_opt_executor, _temp_dir, private_key, _receiver) =
+		MovementOptExecutor::try_generated().await?;
+	let mut movement_node = MovementNode::new(movement_opt_executor);
+
+	// form the prelude
+	let prelude = Composition::new(private_key, movement_node.chain_id())
+		.then(stage)
+		.await?
+		.into_prelude();
+
+	// run the checked migration
+	let report = checked_migration(&mut movement_node, &prelude, migration, criteria()).await?;
+	Ok(report)
+}
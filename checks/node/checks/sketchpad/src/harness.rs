@@ -0,0 +1,306 @@
+//! Shared setup for the sketchpad checks.
+//!
+//! [null_check] runs a prelude and the null migration on a generated node, then checks the criteria.
+//! [Nodes] keeps both sides of a null migration around, so that each side can be given different transactions after it,
+//! e.g. to check that a criterion is not satisfied by nodes which have diverged.
+use anyhow::Context;
+use aptos_crypto::HashValue;
+use aptos_executor_types::BlockExecutorTrait;
+use mtma_node_null_core::config::Config as MtmaNullConfig;
+use mtma_node_preludes::{
+	basic::BasicPrelude,
+	compose::{Fund, Signers, Stage},
+};
+use mtma_node_replay_core::movement_block_to_movement_aptos_block;
+use mtma_node_test_types::{
+	check::{checked_migration, CheckError},
+	criterion::{
+		movement_aptos_executor::aptos_types::{
+			aggregate_signature::AggregateSignature,
+			block_info::BlockInfo,
+			ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+			on_chain_config::{OnChainConfig, OnChainExecutionConfig},
+		},
+		movement_executor::{
+			maptos_opt_executor::{
+				aptos_crypto::ed25519::Ed25519PrivateKey,
+				aptos_types::{
+					block_executor::partitioner::ExecutableBlock as MovementBlock,
+					chain_id::ChainId,
+				},
+			},
+			MovementOptExecutor,
+		},
+		AsyncCriterionish, MovementAptosNode, MovementNode,
+	},
+	evaluation::Evaluation,
+	prelude::{PlannedBlock, Prelude, PreludeError, PreludeGenerator, PreludeRunConfig},
+	report::CheckReport,
+	state::Side,
+};
+use mtma_node_types::migration::Migrationish;
+use std::any::Any;
+use std::future::Future;
+use std::sync::Arc;
+
+/// The number of octas each account funded by [Nodes::try_null] starts with.
+pub const FUNDING: u64 = 1_000_000_000;
+
+/// Generates the [BasicPrelude], the prelude most checks run.
+pub async fn basic(
+	private_key: Ed25519PrivateKey,
+	chain_id: ChainId,
+) -> Result<Prelude, PreludeError> {
+	BasicPrelude { private_key, chain_id }.generate().await
+}
+
+/// Generates a Movement node, runs the prelude generated for it and the null migration, and checks the criteria.
+pub async fn null_check<F, P>(
+	prelude: F,
+	criteria: Vec<Box<dyn AsyncCriterionish + Send + Sync>>,
+) -> Result<CheckReport, CheckError>
+where
+	F: FnOnce(Ed25519PrivateKey, ChainId) -> P,
+	P: Future<Output = Result<Prelude, PreludeError>>,
+{
+	// form the executor
+	let (movement_opt_executor, _temp_dir, private_key, _receiver) =
+		MovementOptExecutor::try_generated()
+			.await
+			.map_err(|e| CheckError::Internal(e.into()))?;
+	let mut movement_executor = MovementNode::new(movement_opt_executor);
+
+	// form the prelude
+	let prelude = prelude(private_key, movement_executor.chain_id())
+		.await
+		.map_err(|e| CheckError::Internal(e.into()))?;
+
+	// form the migration
+	let migration =
+		MtmaNullConfig::default().build().map_err(|e| CheckError::Internal(e.into()))?;
+
+	// run the checked migration
+	checked_migration(&mut movement_executor, &prelude, &migration, criteria).await
+}
+
+/// Both sides of a null migration of a generated Movement node, and the signers which funded it.
+pub struct Nodes {
+	pub movement_node: MovementNode,
+	pub movement_aptos_node: MovementAptosNode,
+	pub signers: Signers,
+	/// Whatever the generated node needs kept alive, e.g. its temp dir.
+	_generated: Box<dyn Any>,
+}
+
+impl Nodes {
+	/// Generates a Movement node, funds accounts on it with [FUNDING] each, and migrates it with the null migration.
+	pub async fn try_null(accounts: usize) -> Result<Self, anyhow::Error> {
+		let (movement_opt_executor, temp_dir, private_key, receiver) =
+			MovementOptExecutor::try_generated().await?;
+		let mut movement_node = MovementNode::new(movement_opt_executor);
+
+		let mut signers = Signers::new(private_key, movement_node.chain_id(), [0u8; 32]);
+		Fund::new(accounts, FUNDING)
+			.generate(&mut signers)
+			.await?
+			.run(&mut movement_node)
+			.await?;
+
+		let migration = MtmaNullConfig::default().build()?;
+		let movement_aptos_node = migration.migrate(&movement_node).await?;
+
+		Ok(Self {
+			movement_node,
+			movement_aptos_node,
+			signers,
+			_generated: Box::new((temp_dir, receiver)),
+		})
+	}
+
+	/// Runs a prelude on each side, generated by `tail` for that side.
+	///
+	/// Both preludes are signed from the signers as they are now, so they may reuse sequence numbers,
+	/// but they must have the same shape, i.e., the same number of transactions in each block, for the ledgers to stay aligned.
+	/// The Movement side runs through the Movement executor and fails on aborts as a prelude would,
+	/// whereas the Movement Aptos side is executed and committed directly by its block executor.
+	pub async fn diverge(
+		&mut self,
+		mut tail: impl FnMut(&mut Signers, Side) -> Result<Prelude, PreludeError>,
+	) -> Result<(), anyhow::Error> {
+		let config = PreludeRunConfig::following(&self.movement_node)?;
+
+		let sequence_numbers = self.sequence_numbers();
+		let movement_tail = tail(&mut self.signers, Side::Movement)?;
+		let diverged_sequence_numbers = self.sequence_numbers();
+		self.set_sequence_numbers(&sequence_numbers);
+		let movement_aptos_tail = tail(&mut self.signers, Side::MovementAptos)?;
+		if self.sequence_numbers() != diverged_sequence_numbers {
+			anyhow::bail!(
+				"the tails of each side must sign the same number of transactions per account"
+			);
+		}
+
+		// plan the Movement Aptos blocks before the Movement side moves on
+		let movement_aptos_blocks =
+			movement_aptos_tail.get_executable_blocks(&self.movement_node, &config)?;
+		let planned = config.plan(movement_aptos_tail.blocks().len());
+
+		movement_tail.run_with(&mut self.movement_node, &config).await?;
+		for (planned, block) in planned.iter().zip(movement_aptos_blocks) {
+			self.execute_movement_aptos_block(planned, block)?;
+		}
+
+		let movement_version = self.movement_node.latest_ledger_version()?;
+		let movement_aptos_version = self.movement_aptos_node.latest_ledger_version()?;
+		if movement_version != movement_aptos_version {
+			anyhow::bail!(
+				"the sides diverged in version: movement is at {}, movement aptos is at {}",
+				movement_version,
+				movement_aptos_version
+			);
+		}
+
+		Ok(())
+	}
+
+	/// Evaluates a criterion against read-only handles to both sides.
+	pub async fn evaluate(&self, criterion: impl AsyncCriterionish) -> Evaluation {
+		Arc::new(criterion)
+			.evaluate(
+				Arc::new(self.movement_node.reader()),
+				Arc::new(self.movement_aptos_node.clone()),
+			)
+			.await
+	}
+
+	/// Executes and commits a Movement block on the Movement Aptos side, with the ledger info planned for it.
+	fn execute_movement_aptos_block(
+		&self,
+		planned: &PlannedBlock,
+		block: MovementBlock,
+	) -> Result<(), anyhow::Error> {
+		let block_executor = self.movement_aptos_node.block_executor();
+
+		let latest_ledger_version = self.movement_aptos_node.latest_ledger_version()?;
+		let state_view = self
+			.movement_aptos_node
+			.state_view_at_version(Some(latest_ledger_version + 1))?;
+		let block_executor_onchain_config = OnChainExecutionConfig::fetch_config(&state_view)
+			.context("onchain execution config not found; it should exist")?
+			.block_executor_onchain_config();
+
+		let block = movement_block_to_movement_aptos_block(block)?;
+		let block_id = block.block_id;
+		let parent_block_id = block_executor.committed_block_id();
+
+		block_executor
+			.execute_and_update_state(block, parent_block_id, block_executor_onchain_config)
+			.context("failed to execute and update state")?;
+		let output = block_executor
+			.ledger_update(block_id, parent_block_id)
+			.context("failed to update ledger")?;
+		block_executor
+			.pre_commit_block(block_id)
+			.context("failed to pre-commit block")?;
+
+		let block_info = BlockInfo::new(
+			planned.epoch,
+			planned.round,
+			block_id,
+			output.root_hash(),
+			output.last_version_or_0(),
+			planned.timestamp_micros,
+			output.epoch_state().clone(),
+		);
+		block_executor
+			.commit_ledger(LedgerInfoWithSignatures::new(
+				LedgerInfo::new(block_info, HashValue::zero()),
+				AggregateSignature::empty(),
+			))
+			.context("failed to commit ledger")?;
+
+		Ok(())
+	}
+
+	/// The sequence numbers of the root account and the funded accounts.
+	fn sequence_numbers(&self) -> Vec<u64> {
+		std::iter::once(self.signers.root_account())
+			.chain(&self.signers.accounts)
+			.map(|account| account.sequence_number())
+			.collect()
+	}
+
+	/// Resets the sequence numbers of the root account and the funded accounts.
+	fn set_sequence_numbers(&self, sequence_numbers: &[u64]) {
+		for (account, sequence_number) in std::iter::once(self.signers.root_account())
+			.chain(&self.signers.accounts)
+			.zip(sequence_numbers)
+		{
+			account.set_sequence_number(*sequence_number);
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+	use mtma_node_test_types::evaluation::Verdict;
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_diverge_identically() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(2).await?;
+
+		// the same transfers on each side keep the sides equal
+		nodes
+			.diverge(|signers, _side| {
+				let mut prelude = Prelude::new_empty();
+				let (from, to) = (&signers.accounts[0], &signers.accounts[1]);
+				prelude
+					.add_transaction(signers.sign(from, payloads::transfer(to.address(), 1_000)?));
+				Ok(prelude)
+			})
+			.await?;
+
+		let evaluation = nodes.evaluate(ResourcesEqual::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Pass, "{}", evaluation);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_null_check_prelude_error() -> Result<(), anyhow::Error> {
+		// the major version cannot be lowered, so the prelude aborts
+		let result = null_check(
+			|private_key, chain_id| async move {
+				let signers = Signers::new(private_key, chain_id, [0u8; 32]);
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(signers.sign(
+					signers.root_account(),
+					payloads::entry_function(
+						"0x1::version",
+						"set_version",
+						vec![],
+						vec![payloads::bcs_arg(&0u64)?],
+					)?,
+				));
+				Ok(prelude)
+			},
+			vec![Box::new(ResourcesEqual::new())],
+		)
+		.await;
+
+		match result {
+			Err(CheckError::Prelude(_, report)) => {
+				let prelude = report.prelude.as_ref().context("the prelude should be reported")?;
+				assert_eq!(prelude.verdict, Verdict::Error);
+				assert!(report.migration.is_none());
+				assert!(report.criteria.is_empty());
+			}
+			result => panic!("expected the prelude to error, got {:?}", result),
+		}
+		Ok(())
+	}
+}
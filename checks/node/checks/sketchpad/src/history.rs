@@ -0,0 +1,84 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{Nodes, FUNDING};
+	use anyhow::Context;
+	use mtma_node_null_core::config::Config as MtmaNullConfig;
+	use mtma_node_preludes::compose::Signers;
+	use mtma_node_preludes::history::{HistoryPrelude, SkipReason};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_modules_equal_criterion::ModulesEqual;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+	use mtma_node_test_types::{
+		check::checked_migration,
+		criterion::movement_executor::{MovementNode, MovementOptExecutor, TStateView},
+		prelude::{Prelude, PreludeRunConfig},
+		state::{self, resources::PackageRegistry, to_movement_state_key},
+	};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_history_checked_migration() -> Result<(), anyhow::Error> {
+		// form the history on a source node
+		let mut nodes = Nodes::try_null(2).await?;
+		let start = nodes.movement_node.latest_ledger_version()? + 1;
+		let (a, b) = (&nodes.signers.accounts[0], &nodes.signers.accounts[1]);
+		let mut prelude = Prelude::new_empty();
+		prelude.add_transaction(nodes.signers.sign(a, payloads::transfer(b.address(), 1_000)?));
+		prelude.add_transaction(nodes.signers.sign(
+			b,
+			payloads::entry_function(
+				"0x1::aptos_account",
+				"batch_transfer",
+				vec![],
+				vec![
+					payloads::bcs_arg(&vec![a.address(), b.address()])?,
+					payloads::bcs_arg(&vec![1u64, 2u64])?,
+				],
+			)?,
+		));
+		prelude.add_transaction(
+			nodes
+				.signers
+				.sign(a, payloads::publish_empty_package(a.address(), "history", vec![])?),
+		);
+		// more than was funded, so it aborts
+		prelude
+			.add_transaction(nodes.signers.sign(b, payloads::transfer(a.address(), FUNDING * 10)?));
+		let (a, b) = (a.address(), b.address());
+		let config = PreludeRunConfig::following(&nodes.movement_node)?.with_allow_aborts(true);
+		prelude.run_with(&mut nodes.movement_node, &config).await?;
+		let end = nodes.movement_node.latest_ledger_version()? + 1;
+
+		// replay it on a fresh node
+		let (movement_opt_executor, _temp_dir, private_key, _receiver) =
+			MovementOptExecutor::try_generated().await?;
+		let mut movement_node = MovementNode::new(movement_opt_executor);
+		let mut signers = Signers::new(private_key, movement_node.chain_id(), [0u8; 32]);
+		let history = HistoryPrelude::new(&nodes.movement_node, start..end).replay(&mut signers)?;
+
+		// the transfer which aborted is skipped rather than failing the prelude
+		let skipped: Vec<_> =
+			history.skipped.iter().map(|skipped| (skipped.sender, skipped.reason)).collect();
+		assert_eq!(skipped, vec![(b, SkipReason::Failed)]);
+
+		checked_migration(
+			&mut movement_node,
+			&history.prelude,
+			&MtmaNullConfig::default().build()?,
+			vec![Box::new(ModulesEqual::new()), Box::new(ResourcesEqual::new())],
+		)
+		.await?;
+
+		// the package is published by the remapped publisher, at its address
+		let publisher = history.addresses.get(&a).context("the publisher should be remapped")?;
+		let key = PackageRegistry::state_key(state::AccountAddress::new(publisher.into_bytes()))?;
+		let bytes = movement_node
+			.state_view_at_version(None)?
+			.get_state_value_bytes(&to_movement_state_key(&key)?)?
+			.context("the remapped publisher should have a package registry")?;
+		let registry: PackageRegistry = bcs::from_bytes(&bytes)?;
+		assert_eq!(registry.packages[0].name, "history");
+		Ok(())
+	}
+}
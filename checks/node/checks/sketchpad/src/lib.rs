@@ -1,2 +1,5 @@
+pub mod harness;
+pub mod history;
 pub mod null;
 pub mod replay;
+pub mod snapshot;
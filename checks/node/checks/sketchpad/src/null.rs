@@ -1,3 +1,13 @@
+pub mod account_keys_preserved;
+pub mod balances_equal;
+pub mod combinators;
 pub mod global_storage_includes;
 pub mod global_storage_injective;
 pub mod global_storage_not_empty;
+pub mod modules_equal;
+pub mod on_chain_configs_equal;
+pub mod packages;
+pub mod random;
+pub mod resources_equal;
+pub mod supply_conserved;
+pub mod transactions_equivalent;
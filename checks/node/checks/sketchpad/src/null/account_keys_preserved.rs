@@ -0,0 +1,40 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check, Nodes};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_account_keys_preserved_criterion::AccountKeysPreserved;
+	use mtma_node_test_types::{evaluation::Verdict, prelude::Prelude, state::Side};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_account_keys_preserved_null() -> Result<(), anyhow::Error> {
+		null_check(basic, vec![Box::new(AccountKeysPreserved::new())]).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_account_keys_preserved_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(2).await?;
+
+		// rotate the key on Movement, while Movement Aptos only sees a transfer
+		nodes
+			.diverge(|signers, side| {
+				let new_key = signers.key();
+				let (account, other) = (&signers.accounts[0], &signers.accounts[1]);
+				let payload = match side {
+					Side::Movement => payloads::rotate_authentication_key(account, &new_key)?,
+					Side::MovementAptos => payloads::transfer(other.address(), 1_000)?,
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(signers.sign(account, payload));
+				Ok(prelude)
+			})
+			.await?;
+
+		let evaluation = nodes.evaluate(AccountKeysPreserved::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check, Nodes};
+	use anyhow::Context;
+	use mtma_node_preludes::{
+		compose::Signers,
+		payloads::{self, entry_function, type_tag},
+	};
+	use mtma_node_test_balances_equal_criterion::BalancesEqual;
+	use mtma_node_test_types::{
+		evaluation::Verdict,
+		prelude::{Prelude, PreludeError},
+		state::{
+			resources::{
+				aptos_coin_metadata_address, primary_store_address, FungibleStore, ObjectGroup,
+				ResourceGroup,
+			},
+			AccountAddress, Side, StateKey, StateViewPair,
+		},
+	};
+
+	/// Migrates the first account to a primary fungible store, and transfers the paired fungible asset from it to the second.
+	fn fungible_asset_transfer(
+		signers: &mut Signers,
+		amount: u64,
+	) -> Result<Prelude, PreludeError> {
+		let (from, to) = (&signers.accounts[0], &signers.accounts[1]);
+
+		let mut prelude = Prelude::new_empty();
+		prelude.add_transaction(signers.sign(
+			from,
+			entry_function(
+				"0x1::coin",
+				"migrate_to_fungible_store",
+				vec![type_tag("0x1::aptos_coin::AptosCoin")?],
+				vec![],
+			)?,
+		));
+		prelude.end_block();
+		prelude.add_transaction(
+			signers.sign(from, payloads::fungible_asset_transfer(to.address(), amount)?),
+		);
+		Ok(prelude)
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_balances_equal_null() -> Result<(), anyhow::Error> {
+		null_check(basic, vec![Box::new(BalancesEqual::new())]).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_primary_store_address() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(2).await?;
+		nodes.diverge(|signers, _side| fungible_asset_transfer(signers, 1_000)).await?;
+
+		// both accounts now have a primary store at the derived address
+		let pair = StateViewPair::latest(&nodes.movement_node, &nodes.movement_aptos_node)?;
+		for account in &nodes.signers.accounts {
+			let owner = AccountAddress::new(account.address().into_bytes());
+			let address = primary_store_address(owner, aptos_coin_metadata_address());
+			let group = pair
+				.side_value::<ResourceGroup>(
+					Side::Movement,
+					&StateKey::resource_group(&address, &ObjectGroup::struct_tag()),
+				)?
+				.with_context(|| format!("no primary store for {} at {}", owner, address))?;
+			let store = ObjectGroup::member::<FungibleStore>(&group, &FungibleStore::struct_tag())?
+				.with_context(|| format!("no fungible store at {}", address))?;
+			assert_eq!(store.metadata.inner, aptos_coin_metadata_address());
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_balances_equal_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(2).await?;
+
+		// transfer a different amount of the fungible asset on each side
+		nodes
+			.diverge(|signers, side| match side {
+				Side::Movement => fungible_asset_transfer(signers, 1_000),
+				Side::MovementAptos => fungible_asset_transfer(signers, 2_000),
+			})
+			.await?;
+
+		let evaluation = nodes.evaluate(BalancesEqual::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
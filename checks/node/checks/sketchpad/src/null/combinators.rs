@@ -0,0 +1,28 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check};
+	use mtma_node_test_global_storage_not_empty_criterion::GlobalStorageNotEmpty;
+	use mtma_node_test_modules_equal_criterion::ModulesEqual;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+	use mtma_node_test_types::combinator::{AllOf, AnyOf, Named, Not, WithTimeout};
+	use std::time::Duration;
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_combinators_null() -> Result<(), anyhow::Error> {
+		// form the tree
+		let tree = AllOf::new()
+			.with(Named::criterion("modules-equal", ModulesEqual::new()))
+			.with(WithTimeout::criterion(ResourcesEqual::new(), Duration::from_secs(600)))
+			.with(
+				AnyOf::new()
+					.with_criterion(GlobalStorageNotEmpty::new())
+					.with(Not::criterion(GlobalStorageNotEmpty::new())),
+			);
+
+		// run the checked migration
+		null_check(basic, vec![Box::new(tree)]).await?;
+		Ok(())
+	}
+}
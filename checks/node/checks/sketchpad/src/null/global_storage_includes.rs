@@ -6,8 +6,10 @@ pub mod test {
 	use mtma_node_test_types::{
 		check::checked_migration,
 		criterion::movement_executor::{MovementNode, MovementOptExecutor},
-		prelude::PreludeGenerator,
+		prelude::{Prelude, PreludeGenerator, PreludeRunConfig},
+		versions::Versions,
 	};
+	use std::collections::BTreeSet;
 	use mtma_node_null_core::config::Config as MtmaNullConfig;
 
 	#[tokio::test]
@@ -37,4 +39,57 @@ pub mod test {
 		.await?;
 		Ok(())
 	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_global_storage_includes_null_historical() -> Result<(), anyhow::Error> {
+		// form the executor
+		let (movement_opt_executor, _temp_dir, private_key, _receiver) =
+			MovementOptExecutor::try_generated().await?;
+		let mut movement_executor = MovementNode::new(movement_opt_executor);
+		let epoch_ending_versions = movement_executor.epoch_ending_versions()?;
+
+		// run the prelude with an epoch per block, so it crosses an epoch boundary
+		let prelude = BasicPrelude { private_key, chain_id: movement_executor.chain_id() }
+			.generate()
+			.await?;
+		let start_version = movement_executor.latest_ledger_version()?;
+		let config = PreludeRunConfig::following(&movement_executor)?.with_blocks_per_epoch(1);
+		prelude.run_with(&mut movement_executor, &config).await?;
+		let latest_version = movement_executor.latest_ledger_version()?;
+
+		// the reconfiguring block ends an epoch within the prelude
+		let prelude_epoch_ending_versions: Vec<u64> = movement_executor
+			.epoch_ending_versions()?
+			.into_iter()
+			.filter(|version| !epoch_ending_versions.contains(version))
+			.collect();
+		assert_eq!(prelude_epoch_ending_versions.len(), 1);
+		assert!(prelude_epoch_ending_versions
+			.iter()
+			.all(|version| *version > start_version && *version < latest_version));
+
+		// the latest version and every epoch boundary are resolved, and nothing else
+		let versions = Versions::latest().with_epoch_boundaries(true);
+		let expected: BTreeSet<u64> = movement_executor
+			.epoch_ending_versions()?
+			.into_iter()
+			.chain(std::iter::once(latest_version))
+			.collect();
+		assert_eq!(versions.resolve(&movement_executor)?, expected);
+
+		// form the migration
+		let migration_config = MtmaNullConfig::default();
+		let migration = migration_config.build()?;
+
+		// run the checked migration, the prelude having already run
+		checked_migration(
+			&mut movement_executor,
+			&Prelude::new_empty(),
+			&migration,
+			vec![Box::new(GlobalStorageIncludes::new().with_versions(versions))],
+		)
+		.await?;
+		Ok(())
+	}
 }
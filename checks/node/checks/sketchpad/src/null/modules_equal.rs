@@ -0,0 +1,42 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check, Nodes};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_modules_equal_criterion::ModulesEqual;
+	use mtma_node_test_types::{evaluation::Verdict, prelude::Prelude, state::Side};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_modules_equal_null() -> Result<(), anyhow::Error> {
+		null_check(basic, vec![Box::new(ModulesEqual::new())]).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_modules_equal_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(1).await?;
+
+		// publish a differently named package on each side
+		nodes
+			.diverge(|signers, side| {
+				let publisher = &signers.accounts[0];
+				let name = match side {
+					Side::Movement => "movement",
+					Side::MovementAptos => "movement_aptos",
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(signers.sign(
+					publisher,
+					payloads::publish_empty_package(publisher.address(), name, vec![])?,
+				));
+				Ok(prelude)
+			})
+			.await?;
+
+		let evaluation = nodes.evaluate(ModulesEqual::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check, Nodes};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_on_chain_configs_equal_criterion::OnChainConfigsEqual;
+	use mtma_node_test_types::{evaluation::Verdict, prelude::Prelude, state::Side};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_on_chain_configs_equal_null() -> Result<(), anyhow::Error> {
+		null_check(basic, vec![Box::new(OnChainConfigsEqual::new())]).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_on_chain_configs_equal_unmet_override() -> Result<(), anyhow::Error> {
+		let nodes = Nodes::try_null(0).await?;
+
+		// the null migration keeps the version, so an override of it is not met
+		let criterion =
+			OnChainConfigsEqual::new().with_override("Version", "/major", serde_json::json!(1_000));
+		let evaluation = nodes.evaluate(criterion).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_on_chain_configs_equal_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(0).await?;
+
+		// the root account may set the version, which it does differently on each side
+		nodes
+			.diverge(|signers, side| {
+				let major: u64 = match side {
+					Side::Movement => 10,
+					Side::MovementAptos => 20,
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(signers.sign(
+					signers.root_account(),
+					payloads::entry_function(
+						"0x1::version",
+						"set_version",
+						vec![],
+						vec![payloads::bcs_arg(&major)?],
+					)?,
+				));
+				Ok(prelude)
+			})
+			.await?;
+
+		let evaluation = nodes.evaluate(OnChainConfigsEqual::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
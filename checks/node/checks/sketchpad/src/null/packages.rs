@@ -0,0 +1,35 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::null_check;
+	use mtma_node_preludes::compose::Composition;
+	use mtma_node_preludes::package::{fixtures, loadable, PackagePrelude, DEFAULT_COMPILER};
+	use mtma_node_test_modules_equal_criterion::ModulesEqual;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+	use std::path::Path;
+
+	#[ignore = "the package fixtures are built on first use, which needs the aptos CLI"]
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_packages_null() -> Result<(), anyhow::Error> {
+		if !loadable(&fixtures(), Path::new(DEFAULT_COMPILER)) {
+			anyhow::bail!(
+				"the fixtures are not built and the {} CLI is not installed",
+				DEFAULT_COMPILER
+			);
+		}
+
+		// run the checked migration
+		null_check(
+			|private_key, chain_id| async move {
+				Ok(Composition::new(private_key, chain_id)
+					.then(PackagePrelude::new(fixtures()))
+					.await?
+					.into_prelude())
+			},
+			vec![Box::new(ModulesEqual::new()), Box::new(ResourcesEqual::new())],
+		)
+		.await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::null_check;
+	use mtma_node_preludes::compose::Composition;
+	use mtma_node_preludes::random::{ActionKind, RandomPrelude};
+	use mtma_node_test_modules_equal_criterion::ModulesEqual;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_random_null() -> Result<(), anyhow::Error> {
+		// the kinds which do not depend on features the local network may not enable, so nothing aborts
+		let kinds = vec![
+			ActionKind::CoinTransfer,
+			ActionKind::ObjectCreation,
+			ActionKind::ResourceAccountCreation,
+			ActionKind::ModulePublishing,
+			ActionKind::KeyRotation,
+		];
+
+		// run the checked migration
+		null_check(
+			|private_key, chain_id| async move {
+				let random =
+					RandomPrelude::new(7).with_accounts(4).with_transactions(32).with_kinds(kinds);
+				Ok(Composition::new(private_key, chain_id).then(random).await?.into_prelude())
+			},
+			vec![Box::new(ModulesEqual::new()), Box::new(ResourcesEqual::new())],
+		)
+		.await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check, Nodes};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+	use mtma_node_test_types::{evaluation::Verdict, prelude::Prelude, state::Side};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_resources_equal_null() -> Result<(), anyhow::Error> {
+		null_check(basic, vec![Box::new(ResourcesEqual::new())]).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_resources_equal_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(1).await?;
+
+		// create a differently named collection object on each side, so their object groups differ
+		nodes
+			.diverge(|signers, side| {
+				let name = match side {
+					Side::Movement => "movement",
+					Side::MovementAptos => "movement_aptos",
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(
+					signers.sign(&signers.accounts[0], payloads::create_collection_object(name)?),
+				);
+				Ok(prelude)
+			})
+			.await?;
+
+		let evaluation = nodes.evaluate(ResourcesEqual::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::{basic, null_check, Nodes};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_supply_conserved_criterion::SupplyConserved;
+	use mtma_node_test_types::{evaluation::Verdict, prelude::Prelude, state::Side};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_supply_conserved_null() -> Result<(), anyhow::Error> {
+		// the generated network holds all of its APT in the tallied stores
+		null_check(basic, vec![Box::new(SupplyConserved::new())]).await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_supply_conserved_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(1).await?;
+
+		// mint a different amount on each side
+		nodes
+			.diverge(|signers, side| {
+				let amount: u64 = match side {
+					Side::Movement => 1_000,
+					Side::MovementAptos => 2_000,
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(signers.sign(
+					signers.root_account(),
+					payloads::entry_function(
+						"0x1::aptos_coin",
+						"mint",
+						vec![],
+						vec![
+							payloads::bcs_arg(&signers.accounts[0].address())?,
+							payloads::bcs_arg(&amount)?,
+						],
+					)?,
+				));
+				Ok(prelude)
+			})
+			.await?;
+
+		// each side is conserved on its own, but the sides no longer agree
+		let evaluation = nodes.evaluate(SupplyConserved::new()).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
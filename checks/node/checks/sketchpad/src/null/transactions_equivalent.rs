@@ -0,0 +1,70 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::Nodes;
+	use mtma_node_preludes::compose::{Stage, Transfers};
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_transactions_equivalent_criterion::{Simulated, TransactionsEquivalent};
+	use mtma_node_test_types::{evaluation::Verdict, prelude::Prelude, state::Side};
+	use mtma_types::movement_aptos::aptos_types::transaction::{
+		ExecutionStatus, SignedTransaction, TransactionStatus,
+	};
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_transactions_equivalent_null() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(4).await?;
+
+		let transactions = Transfers::new(1, 1_000).generate(&mut nodes.signers).await?;
+
+		// the transactions are simulated successfully on both sides, so the criterion compares real outputs
+		let version = nodes.movement_node.latest_ledger_version()?;
+		for transaction in transactions.blocks().iter().flatten() {
+			let transaction: SignedTransaction = bcs::from_bytes(&bcs::to_bytes(transaction)?)?;
+			for simulated in [
+				Simulated::movement(&nodes.movement_node, version, &transaction)?,
+				Simulated::movement_aptos(&nodes.movement_aptos_node, version, &transaction)?,
+			] {
+				assert_eq!(simulated.status, TransactionStatus::Keep(ExecutionStatus::Success));
+				assert!(!simulated.writes.is_empty(), "{:?}", simulated);
+			}
+		}
+
+		let evaluation = nodes.evaluate(TransactionsEquivalent::new(transactions)).await;
+		assert_eq!(evaluation.verdict, Verdict::Pass, "{}", evaluation);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_transactions_equivalent_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(1).await?;
+
+		// only Movement creates the collection named "movement"
+		nodes
+			.diverge(|signers, side| {
+				let name = match side {
+					Side::Movement => "movement",
+					Side::MovementAptos => "movement_aptos",
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(
+					signers.sign(&signers.accounts[0], payloads::create_collection_object(name)?),
+				);
+				Ok(prelude)
+			})
+			.await?;
+
+		// so creating it again aborts on Movement, but succeeds on Movement Aptos
+		let mut transactions = Prelude::new_empty();
+		transactions.add_transaction(
+			nodes
+				.signers
+				.sign(&nodes.signers.accounts[0], payloads::create_collection_object("movement")?),
+		);
+		let evaluation = nodes.evaluate(TransactionsEquivalent::new(transactions)).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		assert!(evaluation.to_string().contains("has status"), "{}", evaluation);
+		Ok(())
+	}
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+pub mod test {
+
+	use crate::harness::Nodes;
+	use anyhow::Context;
+	use mtma_node_preludes::payloads;
+	use mtma_node_test_modules_equal_criterion::ModulesEqual;
+	use mtma_node_test_resources_equal_criterion::ResourcesEqual;
+	use mtma_node_test_types::{
+		check::{checked_snapshot, CheckError},
+		criterion::{AsyncCriterionish, MovementAptosNode, MovementNode},
+		prelude::Prelude,
+		state::Side,
+	};
+
+	/// Opens both sides of the nodes read-only, as a snapshot check would.
+	fn read_only(nodes: &Nodes) -> Result<(MovementNode, MovementAptosNode), anyhow::Error> {
+		let movement_db_path = nodes
+			.movement_node
+			.opt_executor()?
+			.config()
+			.chain
+			.maptos_db_path
+			.clone()
+			.context("generated movement node has no db path")?;
+		let movement_node =
+			MovementNode::try_read_only_db(movement_db_path, nodes.movement_node.chain_id())?;
+		let movement_aptos_node =
+			MovementAptosNode::try_read_only(nodes.movement_aptos_node.db_dir_path().clone())?;
+		Ok((movement_node, movement_aptos_node))
+	}
+
+	fn criteria() -> Vec<Box<dyn AsyncCriterionish + Send + Sync>> {
+		vec![Box::new(ModulesEqual::new()), Box::new(ResourcesEqual::new())]
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_checked_snapshot() -> Result<(), anyhow::Error> {
+		let nodes = Nodes::try_null(1).await?;
+		let (movement_node, movement_aptos_node) = read_only(&nodes)?;
+
+		// the read-only nodes see the same ledger as the nodes they were opened from
+		assert_eq!(
+			movement_node.latest_ledger_version()?,
+			nodes.movement_node.latest_ledger_version()?
+		);
+		assert!(movement_node.opt_executor().is_err());
+
+		let report = checked_snapshot(&movement_node, &movement_aptos_node, criteria()).await?;
+		assert!(report.prelude.is_none());
+		assert!(report.migration.is_none());
+		assert_eq!(report.criteria.len(), 2);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_checked_snapshot_diverged() -> Result<(), anyhow::Error> {
+		let mut nodes = Nodes::try_null(1).await?;
+
+		// create a differently named collection object on each side
+		nodes
+			.diverge(|signers, side| {
+				let name = match side {
+					Side::Movement => "movement",
+					Side::MovementAptos => "movement_aptos",
+				};
+				let mut prelude = Prelude::new_empty();
+				prelude.add_transaction(
+					signers.sign(&signers.accounts[0], payloads::create_collection_object(name)?),
+				);
+				Ok(prelude)
+			})
+			.await?;
+		let (movement_node, movement_aptos_node) = read_only(&nodes)?;
+
+		match checked_snapshot(&movement_node, &movement_aptos_node, criteria()).await {
+			Err(CheckError::Unsatisfied(report)) => {
+				assert_eq!(report.failures().count(), 1, "{}", report);
+			}
+			result => panic!("expected the snapshot to be unsatisfied, got {:?}", result),
+		}
+		Ok(())
+	}
+}
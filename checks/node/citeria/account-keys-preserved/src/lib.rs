@@ -0,0 +1,202 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration};
+use mtma_node_test_types::state::resources::{Account, OriginatingAddress};
+use mtma_node_test_types::state::{
+	self, AccountAddress, Side, StateKey, StateKeyKind, StateViewPair,
+};
+use serde::Deserialize;
+use tracing::debug;
+
+/// Whether a state key holds an account resource.
+fn is_account_key(key: &StateKey) -> bool {
+	matches!(
+		StateKeyKind::of(key),
+		StateKeyKind::Resource(_, struct_tag) if struct_tag == Account::struct_tag()
+	)
+}
+
+pub struct AccountKeysPreserved {
+	/// The divergences the migration is expected to introduce, e.g. reset governance accounts.
+	divergences: DivergenceSpec,
+}
+
+impl AccountKeysPreserved {
+	pub fn new() -> Self {
+		Self { divergences: DivergenceSpec::default() }
+	}
+
+	pub fn criterion() -> Criterion<Self> {
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Looks up the account an authentication key was rotated from, on one side.
+	fn originating_address(
+		pair: &StateViewPair,
+		side: Side,
+		authentication_key: AccountAddress,
+	) -> Result<Option<AccountAddress>, anyhow::Error> {
+		let Some(originating_address) =
+			pair.side_value::<OriginatingAddress>(side, &OriginatingAddress::state_key()?)?
+		else {
+			return Ok(None);
+		};
+		let item_key = originating_address.address_map.item_state_key(&authentication_key)?;
+		pair.side_value::<AccountAddress>(side, &item_key)
+	}
+
+	/// Compares an account across both sides.
+	///
+	/// Violations which would lock the account out are pushed to `locked_out`, the rest to `errors`.
+	fn check_account(
+		&self,
+		pair: &StateViewPair,
+		key: &StateKey,
+		address: AccountAddress,
+		locked_out: &mut Vec<String>,
+		errors: &mut Vec<String>,
+	) -> Result<(), anyhow::Error> {
+		let Some(movement_account) = pair.movement_value::<Account>(key)? else {
+			return Ok(());
+		};
+		let subject = Subject::key(key);
+		let Some(movement_aptos_account) = pair.movement_aptos_value::<Account>(key)? else {
+			self.divergences.record(
+				locked_out,
+				&subject,
+				Change::Removed,
+				format!("Account {} is missing on Movement Aptos", address),
+			);
+			return Ok(());
+		};
+
+		// the account is on both sides, so any difference is explained by an expected change
+		if self.divergences.is_explained(&subject, Change::Changed) {
+			return Ok(());
+		}
+
+		if movement_account.authentication_key != movement_aptos_account.authentication_key {
+			locked_out.push(format!(
+				"Account {} has authentication_key 0x{} on Movement, while Movement Aptos has 0x{}",
+				address,
+				hex::encode(&movement_account.authentication_key),
+				hex::encode(&movement_aptos_account.authentication_key)
+			));
+		}
+
+		if movement_account.sequence_number != movement_aptos_account.sequence_number {
+			locked_out.push(format!(
+				"Account {} has sequence_number {} on Movement, while Movement Aptos has {}",
+				address, movement_account.sequence_number, movement_aptos_account.sequence_number
+			));
+		}
+
+		if movement_account.rotation_capability_offer
+			!= movement_aptos_account.rotation_capability_offer
+		{
+			errors.push(format!(
+				"Account {} has rotation_capability_offer {:?} on Movement, while Movement Aptos has {:?}",
+				address,
+				movement_account.rotation_capability_offer.for_address,
+				movement_aptos_account.rotation_capability_offer.for_address
+			));
+		}
+
+		if movement_account.signer_capability_offer
+			!= movement_aptos_account.signer_capability_offer
+		{
+			errors.push(format!(
+				"Account {} has signer_capability_offer {:?} on Movement, while Movement Aptos has {:?}",
+				address,
+				movement_account.signer_capability_offer.for_address,
+				movement_aptos_account.signer_capability_offer.for_address
+			));
+		}
+
+		// a rotated key must still resolve to the account
+		let authentication_key = AccountAddress::from_bytes(&movement_account.authentication_key)?;
+		if authentication_key != address {
+			let movement_originating_address =
+				Self::originating_address(pair, Side::Movement, authentication_key)?;
+			let movement_aptos_originating_address =
+				Self::originating_address(pair, Side::MovementAptos, authentication_key)?;
+			if movement_originating_address != movement_aptos_originating_address {
+				errors.push(format!(
+					"Account {} has originating address {:?} for its rotated key on Movement, while Movement Aptos has {:?}",
+					address, movement_originating_address, movement_aptos_originating_address
+				));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Criterionish for AccountKeysPreserved {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let keys = state::movement_state_keys(movement_executor, is_account_key)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		debug!("checking {} accounts at version {}", keys.len(), pair.version());
+
+		let mut locked_out = Vec::new();
+		let mut errors = Vec::new();
+		for key in keys {
+			let StateKeyKind::Resource(address, _) = StateKeyKind::of(&key) else {
+				continue;
+			};
+			self.check_account(&pair, &key, address, &mut locked_out, &mut errors)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+		}
+
+		if !locked_out.is_empty() {
+			errors.insert(0, format!("Accounts would be locked out:\n{}", locked_out.join("\n")));
+		}
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "account-keys-preserved";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that every account keeps its authentication key and sequence number.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let criterion = AccountKeysPreserved::new().with_divergences(parameters.divergences);
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_divergences()
+}
@@ -0,0 +1,271 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration};
+use mtma_node_test_types::state::resources::{
+	aptos_coin_metadata_address, aptos_coin_type_tag, primary_store_address, CoinConversionMap,
+	CoinStore, ConcurrentFungibleBalance, FungibleStore, ObjectCore, ObjectGroup, ResourceGroup,
+	TypeInfo,
+};
+use mtma_node_test_types::state::{
+	self, AccountAddress, Side, StateKey, StateKeyKind, StateViewPair,
+};
+use mtma_types::movement_aptos::aptos_sdk::move_types::language_storage::TypeTag;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::debug;
+
+/// An asset an account can hold a balance of.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Asset {
+	/// A coin, identified by its coin type.
+	Coin(TypeTag),
+	/// A fungible asset, identified by its metadata address.
+	FungibleAsset(AccountAddress),
+}
+
+impl std::fmt::Display for Asset {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Asset::Coin(coin_type) => write!(f, "coin {}", coin_type.to_canonical_string()),
+			Asset::FungibleAsset(metadata) => write!(f, "fungible asset {}", metadata),
+		}
+	}
+}
+
+/// Balances keyed by owner and asset.
+type Balances = BTreeMap<(AccountAddress, Asset), u128>;
+
+/// Whether a state key holds a coin store or may hold a fungible store.
+fn is_balance_key(key: &StateKey) -> bool {
+	match StateKeyKind::of(key) {
+		StateKeyKind::Resource(_, struct_tag) => CoinStore::coin_type(&struct_tag).is_some(),
+		StateKeyKind::ResourceGroup(_, struct_tag) => struct_tag == ObjectGroup::struct_tag(),
+		_ => false,
+	}
+}
+
+pub struct BalancesEqual {
+	/// The divergences the migration is expected to introduce, e.g. funded or drained accounts.
+	divergences: DivergenceSpec,
+}
+
+impl BalancesEqual {
+	pub fn new() -> Self {
+		Self { divergences: DivergenceSpec::default() }
+	}
+
+	pub fn criterion() -> Criterion<Self> {
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Reads the coin and primary fungible store balances on one side.
+	fn balances(
+		pair: &StateViewPair,
+		side: Side,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<Balances, anyhow::Error> {
+		let keys = state::state_keys(side, movement_executor, maptos_executor, is_balance_key)?;
+		debug!("reading {} balance keys on {}", keys.len(), side);
+
+		let mut balances = Balances::new();
+		for key in keys {
+			match StateKeyKind::of(&key) {
+				StateKeyKind::Resource(owner, struct_tag) => {
+					let Some(coin_type) = CoinStore::coin_type(&struct_tag) else {
+						continue;
+					};
+					let Some(coin_store) = pair.side_value::<CoinStore>(side, &key)? else {
+						continue;
+					};
+					*balances.entry((owner, Asset::Coin(coin_type.clone()))).or_default() +=
+						u128::from(coin_store.coin.value);
+				}
+				StateKeyKind::ResourceGroup(address, _) => {
+					let Some(group) = pair.side_value::<ResourceGroup>(side, &key)? else {
+						continue;
+					};
+					let Some(store) =
+						ObjectGroup::member::<FungibleStore>(&group, &FungibleStore::struct_tag())?
+					else {
+						continue;
+					};
+					let Some(object_core) =
+						ObjectGroup::member::<ObjectCore>(&group, &ObjectCore::struct_tag())?
+					else {
+						continue;
+					};
+
+					// only primary stores are attributed to their owner
+					let metadata = store.metadata.inner;
+					if primary_store_address(object_core.owner, metadata) != address {
+						continue;
+					}
+
+					let concurrent_balance = ObjectGroup::member::<ConcurrentFungibleBalance>(
+						&group,
+						&ConcurrentFungibleBalance::struct_tag(),
+					)?
+					.map(|concurrent_balance| concurrent_balance.balance.value)
+					.unwrap_or(0);
+					*balances
+						.entry((object_core.owner, Asset::FungibleAsset(metadata)))
+						.or_default() += u128::from(store.balance) + u128::from(concurrent_balance);
+				}
+				_ => {}
+			}
+		}
+
+		Ok(balances)
+	}
+
+	/// Reads the fungible asset paired with each coin type on one side.
+	fn pairings<'a>(
+		pair: &StateViewPair,
+		side: Side,
+		coin_types: impl IntoIterator<Item = &'a TypeTag>,
+	) -> Result<BTreeMap<TypeTag, AccountAddress>, anyhow::Error> {
+		let mut pairings = BTreeMap::new();
+		pairings.insert(aptos_coin_type_tag(), aptos_coin_metadata_address());
+
+		let Some(conversion_map) =
+			pair.side_value::<CoinConversionMap>(side, &CoinConversionMap::state_key()?)?
+		else {
+			return Ok(pairings);
+		};
+
+		for coin_type in coin_types {
+			let Some(type_info) = TypeInfo::of(coin_type) else {
+				continue;
+			};
+			let item_key = conversion_map.coin_to_fungible_asset_map.item_state_key(&type_info)?;
+			if let Some(metadata) = pair.side_value::<AccountAddress>(side, &item_key)? {
+				pairings.insert(coin_type.clone(), metadata);
+			}
+		}
+
+		Ok(pairings)
+	}
+
+	/// Folds paired coin balances into their fungible asset balances.
+	fn combine_paired(
+		balances: Balances,
+		pairings: &BTreeMap<TypeTag, AccountAddress>,
+	) -> Balances {
+		let mut combined = Balances::new();
+		for ((owner, asset), balance) in balances {
+			let asset = match asset {
+				Asset::Coin(coin_type) => match pairings.get(&coin_type) {
+					Some(metadata) => Asset::FungibleAsset(*metadata),
+					None => Asset::Coin(coin_type),
+				},
+				asset => asset,
+			};
+			*combined.entry((owner, asset)).or_default() += balance;
+		}
+		combined
+	}
+}
+
+impl Criterionish for BalancesEqual {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let movement_balances =
+			Self::balances(&pair, Side::Movement, movement_executor, maptos_executor)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+		let movement_aptos_balances =
+			Self::balances(&pair, Side::MovementAptos, movement_executor, maptos_executor)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		// a migration may pair coins which were not paired before, so pairings from both sides apply
+		let coin_types: BTreeSet<TypeTag> = movement_balances
+			.keys()
+			.chain(movement_aptos_balances.keys())
+			.filter_map(|(_, asset)| match asset {
+				Asset::Coin(coin_type) => Some(coin_type.clone()),
+				Asset::FungibleAsset(_) => None,
+			})
+			.collect();
+		let mut pairings = BTreeMap::new();
+		for side in Side::BOTH {
+			pairings.extend(
+				Self::pairings(&pair, side, &coin_types)
+					.map_err(|e| CriterionError::Internal(e.into()))?,
+			);
+		}
+
+		let movement_balances = Self::combine_paired(movement_balances, &pairings);
+		let movement_aptos_balances = Self::combine_paired(movement_aptos_balances, &pairings);
+
+		let mut errors = Vec::new();
+		let holdings: BTreeSet<&(AccountAddress, Asset)> =
+			movement_balances.keys().chain(movement_aptos_balances.keys()).collect();
+		for holding in holdings {
+			let movement_balance = movement_balances.get(holding).copied().unwrap_or(0);
+			let movement_aptos_balance = movement_aptos_balances.get(holding).copied().unwrap_or(0);
+			if movement_balance != movement_aptos_balance {
+				let (owner, asset) = holding;
+				let change = match (movement_balance, movement_aptos_balance) {
+					(0, _) => Change::Added,
+					(_, 0) => Change::Removed,
+					_ => Change::Changed,
+				};
+				self.divergences.record(
+					&mut errors,
+					&Subject::account(*owner),
+					change,
+					format!(
+						"Account {} holds {} of {} on Movement, while Movement Aptos holds {}",
+						owner, movement_balance, asset, movement_aptos_balance
+					),
+				);
+			}
+		}
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "balances-equal";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that every account holds the same coin and fungible store balances.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let criterion = BalancesEqual::new().with_divergences(parameters.divergences);
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_divergences()
+}
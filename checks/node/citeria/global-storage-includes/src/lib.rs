@@ -1,44 +1,67 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
 use mtma_node_test_types::criterion::movement_aptos_executor::TStateView as _;
 use mtma_node_test_types::criterion::movement_executor::TStateView as _;
 use mtma_node_test_types::criterion::{
 	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
 };
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration};
+use mtma_node_test_types::versions::Versions;
 use mtma_types::movement_aptos::aptos_types::state_store::state_key::StateKey as MovementAptosStateKey;
+use serde::Deserialize;
 use tracing::debug;
-pub struct GlobalStorageIncludes;
+pub struct GlobalStorageIncludes {
+	/// The versions at which global storage is compared.
+	versions: Versions,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
 
 impl GlobalStorageIncludes {
 	pub fn new() -> Self {
-		Self
+		Self { versions: Versions::default(), divergences: DivergenceSpec::default() }
 	}
 
 	pub fn criterion() -> Criterion<Self> {
-		Criterion::new(Self)
+		Criterion::new(Self::new())
 	}
-}
 
-impl Criterionish for GlobalStorageIncludes {
-	fn satisfies(
+	/// Builder API: sets the versions at which global storage is compared.
+	pub fn with_versions(mut self, versions: Versions) -> Self {
+		self.versions = versions;
+		self
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Checks that the Movement global storage is included in the Movement Aptos global storage at the given versions.
+	///
+	/// The keys are iterated once, and each key is checked at every version, so the cost does not grow with the versions times the history.
+	fn satisfies_at_versions(
 		&self,
 		movement_executor: &MovementNode,
 		maptos_executor: &MovementAptosNode,
+		versions: &[u64],
 	) -> Result<(), CriterionError> {
-		// get the latest ledger version from the movement executor
-		let movement_ledger_version = movement_executor
-			.latest_ledger_version()
-			.map_err(|e| CriterionError::Internal(e.into()))?;
-
-		debug!("movement_ledger_version: {:?}", movement_ledger_version);
+		debug!("checking global storage includes at {} versions", versions.len());
 
-		// get the latest state view from the movement executor
-		let movement_state_view = movement_executor
-			.state_view_at_version(Some(movement_ledger_version))
-			.map_err(|e| CriterionError::Internal(e.into()))?;
-
-		// get the latest state view from the maptos executor
-		let maptos_state_view = maptos_executor
-			.state_view_at_version(Some(movement_ledger_version))
-			.map_err(|e| CriterionError::Internal(e.into()))?;
+		// get the state views at each version from the movement and maptos executors
+		let state_views = versions
+			.iter()
+			.map(|version| {
+				let movement_state_view = movement_executor
+					.state_view_at_version(Some(*version))
+					.map_err(|e| CriterionError::Internal(e.into()))?;
+				let maptos_state_view = maptos_executor
+					.state_view_at_version(Some(*version))
+					.map_err(|e| CriterionError::Internal(e.into()))?;
+				Ok((*version, movement_state_view, maptos_state_view))
+			})
+			.collect::<Result<Vec<_>, CriterionError>>()?;
 
 		// the movement state view is the domain, so the maptos state view is the codomain
 		let movement_global_state_keys_iterator =
@@ -58,52 +81,68 @@ impl Criterionish for GlobalStorageIncludes {
 				MovementAptosStateKey::decode(movement_state_key_bytes.to_vec().as_slice())
 					.map_err(|e| CriterionError::Internal(e.into()))?;
 
-			let movement_value = movement_state_view
-				.get_state_value_bytes(&movement_state_key)
-				.map_err(|e| CriterionError::Internal(e.into()))?;
-
-			match movement_value {
-				Some(movement_value) => {
-					let maptos_state_value = maptos_state_view
-						.get_state_value_bytes(&movement_aptos_state_key)
-						.map_err(|e| CriterionError::Internal(e.into()))?
-						.ok_or(CriterionError::Unsatisfied(
-							format!(
-								"Movement Aptos is missing a value for {:?}",
-								movement_state_key
-							)
-							.into(),
-						))?;
-
-					if movement_value != maptos_state_value {
-						return Err(CriterionError::Unsatisfied(
-							format!(
-								"Movement state value for {:?} is {:?}, while Movement Aptos state value is {:?}",
-								movement_state_key,
-								movement_value,
-								maptos_state_value
-							)
-							.into(),
-						));
+			let subject = Subject::key(&movement_aptos_state_key);
+
+			for (version, movement_state_view, maptos_state_view) in state_views.iter() {
+				let movement_value = movement_state_view
+					.get_state_value_bytes(&movement_state_key)
+					.map_err(|e| CriterionError::Internal(e.into()))?;
+
+				match movement_value {
+					Some(movement_value) => {
+						match maptos_state_view
+							.get_state_value_bytes(&movement_aptos_state_key)
+							.map_err(|e| CriterionError::Internal(e.into()))?
+						{
+							None => {
+								if !self.divergences.is_explained(&subject, Change::Removed) {
+									return Err(CriterionError::Unsatisfied(
+										format!(
+											"Movement Aptos is missing a value for {:?} at version {}",
+											movement_state_key, version
+										)
+										.into(),
+									));
+								}
+							}
+							Some(maptos_state_value) => {
+								if movement_value != maptos_state_value
+									&& !self.divergences.is_explained(&subject, Change::Changed)
+								{
+									return Err(CriterionError::Unsatisfied(
+										format!(
+											"Movement state value for {:?} is {:?}, while Movement Aptos state value is {:?} at version {}",
+											movement_state_key,
+											movement_value,
+											maptos_state_value,
+											version
+										)
+										.into(),
+									));
+								}
+							}
+						}
 					}
-				}
-				None => {
-					debug!("Value from a previous version has been removed at the latest ledger version");
-
-					match maptos_state_view
-						.get_state_value(&movement_aptos_state_key)
-						.map_err(|e| CriterionError::Internal(e.into()))?
-					{
-						Some(_) => {
-							return Err(CriterionError::Unsatisfied(
-								format!(
-									"Movement Aptos is unexpectedly not missing a value for {:?}",
-									movement_state_key
-								)
-								.into(),
-							));
+					None => {
+						debug!("Value from another version is not present at version {}", version);
+
+						match maptos_state_view
+							.get_state_value(&movement_aptos_state_key)
+							.map_err(|e| CriterionError::Internal(e.into()))?
+						{
+							Some(_) => {
+								if !self.divergences.is_explained(&subject, Change::Added) {
+									return Err(CriterionError::Unsatisfied(
+										format!(
+											"Movement Aptos is unexpectedly not missing a value for {:?} at version {}",
+											movement_state_key, version
+										)
+										.into(),
+									));
+								}
+							}
+							None => {}
 						}
-						None => {}
 					}
 				}
 			}
@@ -113,3 +152,53 @@ impl Criterionish for GlobalStorageIncludes {
 		Ok(())
 	}
 }
+
+impl Criterionish for GlobalStorageIncludes {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		// resolve the versions against the movement executor
+		let versions = self
+			.versions
+			.resolve(movement_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		self.satisfies_at_versions(movement_executor, maptos_executor, &versions)
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "global-storage-includes";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// The versions at which global storage is compared.
+	versions: Versions,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks whether the Movement global storage is included in Movement Aptos.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let criterion = GlobalStorageIncludes::new()
+				.with_versions(parameters.versions)
+				.with_divergences(parameters.divergences);
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter(
+		"versions",
+		"The versions at which global storage is compared.",
+		r#"{"latest":true}"#,
+	)
+	.with_divergences()
+}
@@ -1,6 +1,8 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
 use mtma_node_test_types::criterion::{
 	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
 };
+use mtma_node_test_types::registry::Registration;
 pub struct GlobalStorageNotEmpty;
 
 impl GlobalStorageNotEmpty {
@@ -49,3 +51,13 @@ impl Criterionish for GlobalStorageNotEmpty {
 		Ok(())
 	}
 }
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "global-storage-not-empty";
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(NAME, "Checks that neither global storage is empty.", |_| {
+		Ok(Box::new(Named::criterion(NAME, GlobalStorageNotEmpty::new())) as BoxedEvaluatish)
+	})
+}
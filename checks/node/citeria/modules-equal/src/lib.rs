@@ -0,0 +1,303 @@
+use move_binary_format::deserializer::DeserializerConfig;
+use move_binary_format::file_format_common::{IDENTIFIER_SIZE_MAX, VERSION_MAX};
+use move_binary_format::CompiledModule;
+use move_bytecode_verifier::VerifierConfig;
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration};
+use mtma_node_test_types::state::resources::{PackageMetadata, PackageRegistry};
+use mtma_node_test_types::state::{self, StateKey, StateKeyKind, StateViewPair};
+use mtma_types::movement_aptos::aptos_crypto::HashValue;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+/// Whether a state key holds module bytecode or a package registry.
+fn is_code_key(key: &StateKey) -> bool {
+	match StateKeyKind::of(key) {
+		StateKeyKind::Module(_) => true,
+		StateKeyKind::Resource(_, struct_tag) => struct_tag == PackageRegistry::struct_tag(),
+		_ => false,
+	}
+}
+
+pub struct ModulesEqual {
+	/// The maximum bytecode version Movement Aptos accepts.
+	max_binary_format_version: u32,
+	/// The verifier config Movement Aptos runs published modules through.
+	verifier_config: VerifierConfig,
+	/// The divergences the migration is expected to introduce, e.g. rewritten framework modules.
+	divergences: DivergenceSpec,
+}
+
+impl ModulesEqual {
+	pub fn new() -> Self {
+		Self {
+			max_binary_format_version: VERSION_MAX,
+			verifier_config: VerifierConfig::production(),
+			divergences: DivergenceSpec::default(),
+		}
+	}
+
+	pub fn criterion() -> Criterion<Self> {
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: sets the maximum bytecode version Movement Aptos accepts.
+	pub fn with_max_binary_format_version(mut self, max_binary_format_version: u32) -> Self {
+		self.max_binary_format_version = max_binary_format_version;
+		self
+	}
+
+	/// Builder API: sets the verifier config.
+	pub fn with_verifier_config(mut self, verifier_config: VerifierConfig) -> Self {
+		self.verifier_config = verifier_config;
+		self
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Checks that Movement Aptos would accept a module.
+	fn verify(&self, bytecode: &[u8]) -> Result<(), String> {
+		let config = DeserializerConfig::new(self.max_binary_format_version, IDENTIFIER_SIZE_MAX);
+		let module = CompiledModule::deserialize_with_config(bytecode, &config)
+			.map_err(|e| format!("fails to deserialize: {:?}", e))?;
+		move_bytecode_verifier::verify_module_with_config(&self.verifier_config, &module)
+			.map_err(|e| format!("fails verification: {:?}", e))
+	}
+
+	/// Compares a module across both sides.
+	fn check_module(
+		&self,
+		pair: &StateViewPair,
+		key: &StateKey,
+		errors: &mut Vec<String>,
+	) -> Result<(), CriterionError> {
+		let (movement_bytecode, movement_aptos_bytecode) =
+			pair.bytes(key).map_err(|e| CriterionError::Internal(e.into()))?;
+		let subject = Subject::key(key);
+
+		match (&movement_bytecode, &movement_aptos_bytecode) {
+			(Some(movement_bytecode), Some(movement_aptos_bytecode)) => {
+				let movement_hash = HashValue::sha3_256_of(movement_bytecode);
+				let movement_aptos_hash = HashValue::sha3_256_of(movement_aptos_bytecode);
+				if movement_hash != movement_aptos_hash {
+					self.divergences.record(
+						errors,
+						&subject,
+						Change::Changed,
+						format!(
+							"Module {:?} has bytecode hash {} on Movement, while Movement Aptos has {}",
+							key, movement_hash, movement_aptos_hash
+						),
+					);
+				}
+			}
+			(Some(_), None) => {
+				self.divergences.record(
+					errors,
+					&subject,
+					Change::Removed,
+					format!("Module {:?} is missing on Movement Aptos", key),
+				);
+			}
+			(None, Some(_)) => {
+				self.divergences.record(
+					errors,
+					&subject,
+					Change::Added,
+					format!("Module {:?} is unexpectedly published on Movement Aptos", key),
+				);
+			}
+			(None, None) => {}
+		}
+
+		if let Some(movement_aptos_bytecode) = movement_aptos_bytecode {
+			if let Err(reason) = self.verify(&movement_aptos_bytecode) {
+				errors.push(format!(
+					"Module {:?} would be rejected by Movement Aptos: {}",
+					key, reason
+				));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Compares a package registry across both sides.
+	fn check_package_registry(
+		&self,
+		pair: &StateViewPair,
+		key: &StateKey,
+		errors: &mut Vec<String>,
+	) -> Result<(), CriterionError> {
+		let movement_registry = pair
+			.movement_value::<PackageRegistry>(key)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		let movement_aptos_registry = pair
+			.movement_aptos_value::<PackageRegistry>(key)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let packages = |registry: Option<PackageRegistry>| -> BTreeMap<String, PackageMetadata> {
+			registry
+				.map(|registry| registry.packages)
+				.unwrap_or_default()
+				.into_iter()
+				.map(|package| (package.name.clone(), package))
+				.collect()
+		};
+		let subject = Subject::key(key);
+		let movement_packages = packages(movement_registry);
+		let mut movement_aptos_packages = packages(movement_aptos_registry);
+
+		for (name, movement_package) in movement_packages {
+			let Some(movement_aptos_package) = movement_aptos_packages.remove(&name) else {
+				self.divergences.record(
+					errors,
+					&subject,
+					Change::Removed,
+					format!("Package {} at {:?} is missing on Movement Aptos", name, key),
+				);
+				continue;
+			};
+			let mut differences = Vec::new();
+
+			if movement_package.upgrade_policy != movement_aptos_package.upgrade_policy {
+				differences.push(format!(
+					"Package {} at {:?} has upgrade policy {} on Movement, while Movement Aptos has {}",
+					name, key, movement_package.upgrade_policy, movement_aptos_package.upgrade_policy
+				));
+			}
+
+			if movement_package.upgrade_number != movement_aptos_package.upgrade_number {
+				differences.push(format!(
+					"Package {} at {:?} has upgrade number {} on Movement, while Movement Aptos has {}",
+					name, key, movement_package.upgrade_number, movement_aptos_package.upgrade_number
+				));
+			}
+
+			if movement_package.source_digest != movement_aptos_package.source_digest {
+				differences.push(format!(
+					"Package {} at {:?} has source digest {} on Movement, while Movement Aptos has {}",
+					name, key, movement_package.source_digest, movement_aptos_package.source_digest
+				));
+			}
+
+			let module_names = |package: &PackageMetadata| -> Vec<String> {
+				package.modules.iter().map(|module| module.name.clone()).collect()
+			};
+			if module_names(&movement_package) != module_names(&movement_aptos_package) {
+				differences.push(format!(
+					"Package {} at {:?} has modules {:?} on Movement, while Movement Aptos has {:?}",
+					name,
+					key,
+					module_names(&movement_package),
+					module_names(&movement_aptos_package)
+				));
+			}
+
+			if movement_package.deps != movement_aptos_package.deps {
+				differences.push(format!(
+					"Package {} at {:?} has dependencies {:?} on Movement, while Movement Aptos has {:?}",
+					name, key, movement_package.deps, movement_aptos_package.deps
+				));
+			}
+
+			if !differences.is_empty() && !self.divergences.is_explained(&subject, Change::Changed)
+			{
+				errors.extend(differences);
+			}
+		}
+
+		for name in movement_aptos_packages.keys() {
+			self.divergences.record(
+				errors,
+				&subject,
+				Change::Added,
+				format!(
+					"Package {} at {:?} is unexpectedly published on Movement Aptos",
+					name, key
+				),
+			);
+		}
+
+		Ok(())
+	}
+}
+
+impl Criterionish for ModulesEqual {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		// modules can be published on either side, so we check the union of keys
+		let mut keys = state::movement_state_keys(movement_executor, is_code_key)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		keys.extend(
+			state::movement_aptos_state_keys(maptos_executor, is_code_key)
+				.map_err(|e| CriterionError::Internal(e.into()))?,
+		);
+
+		debug!("checking {} code keys at version {}", keys.len(), pair.version());
+
+		let mut errors = Vec::new();
+		for key in keys {
+			match StateKeyKind::of(&key) {
+				StateKeyKind::Module(_) => self.check_module(&pair, &key, &mut errors)?,
+				_ => self.check_package_registry(&pair, &key, &mut errors)?,
+			}
+		}
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "modules-equal";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// The maximum bytecode version Movement Aptos accepts.
+	max_binary_format_version: Option<u32>,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that published modules are preserved and pass the Movement Aptos verifier.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let mut criterion = ModulesEqual::new().with_divergences(parameters.divergences);
+			if let Some(max_binary_format_version) = parameters.max_binary_format_version {
+				criterion = criterion.with_max_binary_format_version(max_binary_format_version);
+			}
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter(
+		"max_binary_format_version",
+		"The maximum bytecode version Movement Aptos accepts.",
+		VERSION_MAX.to_string(),
+	)
+	.with_divergences()
+}
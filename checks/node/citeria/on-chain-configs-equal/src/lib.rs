@@ -0,0 +1,320 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration, RegistryError};
+use mtma_node_test_types::state::{StateKey, StateViewPair};
+use mtma_types::movement_aptos::aptos_types::chain_id::ChainId;
+use mtma_types::movement_aptos::aptos_types::on_chain_config::{
+	Features, GasScheduleV2, OnChainConfig, OnChainConsensusConfig, OnChainExecutionConfig,
+	ValidatorSet, Version,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+/// The type identifiers of the compared configs, which the overrides are keyed by.
+const CONFIGS: [&str; 7] = [
+	GasScheduleV2::TYPE_IDENTIFIER,
+	OnChainConsensusConfig::TYPE_IDENTIFIER,
+	OnChainExecutionConfig::TYPE_IDENTIFIER,
+	Version::TYPE_IDENTIFIER,
+	ChainId::TYPE_IDENTIFIER,
+	ValidatorSet::TYPE_IDENTIFIER,
+	Features::TYPE_IDENTIFIER,
+];
+
+/// Escapes a token for use in a JSON pointer.
+fn escape_pointer_token(token: &str) -> String {
+	token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Collects the JSON pointers at which two values differ.
+fn diff(pointer: String, movement: &Value, movement_aptos: &Value, diffs: &mut Vec<String>) {
+	match (movement, movement_aptos) {
+		(Value::Object(movement), Value::Object(movement_aptos)) => {
+			let mut keys: Vec<&String> = movement.keys().chain(movement_aptos.keys()).collect();
+			keys.sort();
+			keys.dedup();
+			for key in keys {
+				diff(
+					format!("{}/{}", pointer, escape_pointer_token(key)),
+					movement.get(key).unwrap_or(&Value::Null),
+					movement_aptos.get(key).unwrap_or(&Value::Null),
+					diffs,
+				);
+			}
+		}
+		(Value::Array(movement), Value::Array(movement_aptos)) => {
+			for index in 0..movement.len().max(movement_aptos.len()) {
+				diff(
+					format!("{}/{}", pointer, index),
+					movement.get(index).unwrap_or(&Value::Null),
+					movement_aptos.get(index).unwrap_or(&Value::Null),
+					diffs,
+				);
+			}
+		}
+		(movement, movement_aptos) => {
+			if movement != movement_aptos {
+				diffs.push(pointer);
+			}
+		}
+	}
+}
+
+pub struct OnChainConfigsEqual {
+	/// Expected Movement Aptos values, keyed by config name and then JSON pointer.
+	overrides: BTreeMap<String, BTreeMap<String, Value>>,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+impl OnChainConfigsEqual {
+	pub fn new() -> Self {
+		Self { overrides: BTreeMap::new(), divergences: DivergenceSpec::default() }
+	}
+
+	pub fn criterion() -> Criterion<Self> {
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: expects Movement Aptos to hold `value` at `pointer` in the named config.
+	///
+	/// The config name is the on-chain type name, e.g. `ExecutionConfig` or `GasScheduleV2`,
+	/// and must be one of the compared configs, see [OnChainConfigsEqual::check_overrides].
+	/// An empty pointer overrides the whole config.
+	pub fn with_override(
+		mut self,
+		config: impl Into<String>,
+		pointer: impl Into<String>,
+		value: Value,
+	) -> Self {
+		self.overrides.entry(config.into()).or_default().insert(pointer.into(), value);
+		self
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	///
+	/// Unlike overrides, a divergence explains a whole config, matched by its resource type.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Checks that every override names a compared config, so that a misspelled one is not silently ignored.
+	pub fn check_overrides(&self) -> Result<(), anyhow::Error> {
+		let unknown: Vec<&String> = self
+			.overrides
+			.keys()
+			.filter(|config| !CONFIGS.contains(&config.as_str()))
+			.collect();
+		if !unknown.is_empty() {
+			anyhow::bail!(
+				"overrides for {:?} do not name a compared config, expected one of {:?}",
+				unknown,
+				CONFIGS
+			);
+		}
+		Ok(())
+	}
+
+	/// Whether a pointer is at or beneath an overridden pointer.
+	fn is_overridden(overrides: Option<&BTreeMap<String, Value>>, pointer: &str) -> bool {
+		overrides.is_some_and(|overrides| {
+			overrides.keys().any(|overridden| {
+				pointer == overridden || pointer.starts_with(&format!("{}/", overridden))
+			})
+		})
+	}
+
+	/// Reads and decodes an on-chain config on both sides, as JSON.
+	fn read<T: OnChainConfig + Serialize>(
+		pair: &StateViewPair,
+	) -> Result<(Option<Value>, Option<Value>), anyhow::Error> {
+		let key = StateKey::on_chain_config::<T>()?;
+		let decode = |bytes: Option<Vec<u8>>| -> Result<Option<Value>, anyhow::Error> {
+			bytes
+				.map(|bytes| {
+					let config = T::deserialize_into_config(&bytes)?;
+					Ok(serde_json::to_value(config)?)
+				})
+				.transpose()
+		};
+		let (movement_bytes, movement_aptos_bytes) = pair.bytes(&key)?;
+		Ok((decode(movement_bytes)?, decode(movement_aptos_bytes)?))
+	}
+
+	/// Compares an on-chain config across both sides.
+	fn compare<T: OnChainConfig + Serialize>(
+		&self,
+		pair: &StateViewPair,
+		errors: &mut Vec<String>,
+	) -> Result<(), anyhow::Error> {
+		let name = T::TYPE_IDENTIFIER;
+		debug!("comparing on-chain config {}", name);
+
+		let (movement, movement_aptos) = Self::read::<T>(pair)?;
+		let overrides = self.overrides.get(name);
+		let subject = Subject::key(&StateKey::on_chain_config::<T>()?);
+
+		// overridden fields must hold the expected value on Movement Aptos
+		for (pointer, expected) in overrides.into_iter().flatten() {
+			let actual = movement_aptos.as_ref().and_then(|config| config.pointer(pointer));
+			if actual != Some(expected) {
+				errors.push(format!(
+					"On-chain config {} has {:?} at {:?} on Movement Aptos, while {} is expected",
+					name, actual, pointer, expected
+				));
+			}
+		}
+		if Self::is_overridden(overrides, "") {
+			return Ok(());
+		}
+
+		let (movement, movement_aptos) = match (movement, movement_aptos) {
+			(Some(movement), Some(movement_aptos)) => (movement, movement_aptos),
+			(Some(_), None) => {
+				self.divergences.record(
+					errors,
+					&subject,
+					Change::Removed,
+					format!("On-chain config {} is missing on Movement Aptos", name),
+				);
+				return Ok(());
+			}
+			(None, Some(_)) => {
+				self.divergences.record(
+					errors,
+					&subject,
+					Change::Added,
+					format!("On-chain config {} is unexpectedly present on Movement Aptos", name),
+				);
+				return Ok(());
+			}
+			(None, None) => return Ok(()),
+		};
+
+		let mut diffs = Vec::new();
+		diff(String::new(), &movement, &movement_aptos, &mut diffs);
+		diffs.retain(|pointer| !Self::is_overridden(overrides, pointer));
+		if diffs.is_empty() || self.divergences.is_explained(&subject, Change::Changed) {
+			return Ok(());
+		}
+		for pointer in diffs {
+			errors.push(format!(
+				"On-chain config {} has {} at {:?} on Movement, while Movement Aptos has {}",
+				name,
+				movement.pointer(&pointer).unwrap_or(&Value::Null),
+				pointer,
+				movement_aptos.pointer(&pointer).unwrap_or(&Value::Null)
+			));
+		}
+
+		Ok(())
+	}
+}
+
+impl Criterionish for OnChainConfigsEqual {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		self.check_overrides().map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let mut errors = Vec::new();
+		self.compare::<GasScheduleV2>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		self.compare::<OnChainConsensusConfig>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		self.compare::<OnChainExecutionConfig>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		self.compare::<Version>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		self.compare::<ChainId>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		self.compare::<ValidatorSet>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		self.compare::<Features>(&pair, &mut errors)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "on-chain-configs-equal";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// Expected Movement Aptos values, keyed by config name and then JSON pointer.
+	overrides: BTreeMap<String, BTreeMap<String, Value>>,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that the on-chain configs are equal, up to declared overrides.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let mut criterion = OnChainConfigsEqual::new().with_divergences(parameters.divergences);
+			for (config, overrides) in parameters.overrides {
+				for (pointer, value) in overrides {
+					criterion = criterion.with_override(config.clone(), pointer, value);
+				}
+			}
+			criterion
+				.check_overrides()
+				.map_err(|e| RegistryError::Parameters(NAME.to_string(), e.into()))?;
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter(
+		"overrides",
+		"Expected Movement Aptos values, keyed by config name and then JSON pointer.",
+		"{}",
+	)
+	.with_divergences()
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	#[test]
+	fn test_check_overrides() {
+		let criterion = OnChainConfigsEqual::new()
+			.with_override(Version::TYPE_IDENTIFIER, "/major", Value::from(2))
+			.with_override(Features::TYPE_IDENTIFIER, "", Value::Null);
+		assert!(criterion.check_overrides().is_ok());
+
+		// a misspelled config is rejected rather than ignored
+		let criterion = criterion.with_override("GasSchedule", "/entries", Value::Null);
+		let error = criterion.check_overrides().expect_err("misspelled config is accepted");
+		assert!(error.to_string().contains(r#"["GasSchedule"]"#), "{}", error);
+	}
+
+	#[test]
+	fn test_is_overridden() {
+		let overrides = BTreeMap::from([("/entries".to_string(), Value::Null)]);
+		assert!(OnChainConfigsEqual::is_overridden(Some(&overrides), "/entries"));
+		assert!(OnChainConfigsEqual::is_overridden(Some(&overrides), "/entries/0"));
+		assert!(!OnChainConfigsEqual::is_overridden(Some(&overrides), "/entries_v2"));
+		assert!(!OnChainConfigsEqual::is_overridden(None, "/entries"));
+	}
+}
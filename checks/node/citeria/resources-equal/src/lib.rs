@@ -0,0 +1,241 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration, RegistryError};
+use mtma_node_test_types::state::resources::{ObjectGroup, ResourceGroup};
+use mtma_node_test_types::state::{
+	self, AccountAddress, Side, StateKey, StateKeyKind, StateViewPair, StructTag,
+};
+use serde::Deserialize;
+use std::str::FromStr;
+use tracing::debug;
+
+/// Whether a state key holds a resource or a resource group.
+fn is_resource_key(key: &StateKey) -> bool {
+	matches!(StateKeyKind::of(key), StateKeyKind::Resource(..) | StateKeyKind::ResourceGroup(..))
+}
+
+/// Where a resource is stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Location {
+	/// Under its own state key.
+	Standalone,
+	/// As a member of a resource group.
+	Grouped(StructTag),
+}
+
+pub struct ResourcesEqual {
+	/// The groups a resource which is not found standalone may have been moved into.
+	groups: Vec<StructTag>,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+impl ResourcesEqual {
+	pub fn new() -> Self {
+		Self { groups: vec![ObjectGroup::struct_tag()], divergences: DivergenceSpec::default() }
+	}
+
+	pub fn criterion() -> Criterion<Self> {
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: adds groups a resource may have been moved into.
+	pub fn with_groups(mut self, groups: impl IntoIterator<Item = StructTag>) -> Self {
+		self.groups.extend(groups);
+		self
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Expands a state key into the resources it holds on one side.
+	fn members(
+		pair: &StateViewPair,
+		side: Side,
+		key: &StateKey,
+	) -> Result<Vec<(StructTag, Vec<u8>)>, anyhow::Error> {
+		match StateKeyKind::of(key) {
+			StateKeyKind::Resource(_, struct_tag) => Ok(pair
+				.side_bytes(side, key)?
+				.map(|bytes| (struct_tag, bytes))
+				.into_iter()
+				.collect()),
+			StateKeyKind::ResourceGroup(..) => Ok(pair
+				.side_value::<ResourceGroup>(side, key)?
+				.map(|group| group.into_iter().collect())
+				.unwrap_or_default()),
+			_ => Ok(vec![]),
+		}
+	}
+
+	/// Locates a resource on one side, standalone or in a group.
+	fn locate(
+		&self,
+		pair: &StateViewPair,
+		side: Side,
+		address: AccountAddress,
+		resource: &StructTag,
+		group_hint: Option<&StructTag>,
+	) -> Result<Option<(Location, Vec<u8>)>, anyhow::Error> {
+		if let Some(bytes) = pair.side_bytes(side, &StateKey::resource(&address, resource)?)? {
+			return Ok(Some((Location::Standalone, bytes)));
+		}
+
+		for group in group_hint.into_iter().chain(self.groups.iter()) {
+			let group_key = StateKey::resource_group(&address, group);
+			let Some(members) = pair.side_value::<ResourceGroup>(side, &group_key)? else {
+				continue;
+			};
+			if let Some(bytes) = members.get(resource) {
+				return Ok(Some((Location::Grouped(group.clone()), bytes.clone())));
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Checks every resource on one side against the other side.
+	///
+	/// Values are only compared from the Movement side, the Movement Aptos side only looks for extra resources.
+	fn check_side(
+		&self,
+		pair: &StateViewPair,
+		side: Side,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+		errors: &mut Vec<String>,
+	) -> Result<(), anyhow::Error> {
+		let other_side = side.other();
+
+		let keys = state::state_keys(side, movement_executor, maptos_executor, is_resource_key)?;
+		debug!("checking {} resource keys on {}", keys.len(), side);
+
+		for key in keys {
+			let (address, group_hint) = match StateKeyKind::of(&key) {
+				StateKeyKind::Resource(address, _) => (address, None),
+				StateKeyKind::ResourceGroup(address, group) => (address, Some(group)),
+				_ => continue,
+			};
+			let location = match &group_hint {
+				Some(group) => Location::Grouped(group.clone()),
+				None => Location::Standalone,
+			};
+
+			for (resource, bytes) in Self::members(pair, side, &key)? {
+				// a group member is described by its own type rather than the group's
+				let subject = Subject::key(&key).with_resource(resource.clone());
+				match self.locate(pair, other_side, address, &resource, group_hint.as_ref())? {
+					None => match side {
+						Side::Movement => self.divergences.record(
+							errors,
+							&subject,
+							Change::Removed,
+							format!(
+								"Resource {} at {} is missing on Movement Aptos",
+								resource, address
+							),
+						),
+						Side::MovementAptos => self.divergences.record(
+							errors,
+							&subject,
+							Change::Added,
+							format!(
+								"Resource {} at {} is unexpectedly present on Movement Aptos",
+								resource, address
+							),
+						),
+					},
+					Some((other_location, other_bytes)) => {
+						if other_location != location {
+							debug!(
+								"Resource {} at {} moved from {:?} on {} to {:?} on {}",
+								resource, address, location, side, other_location, other_side
+							);
+						}
+						if side == Side::Movement && bytes != other_bytes {
+							self.divergences.record(
+								errors,
+								&subject,
+								Change::Changed,
+								format!(
+									"Resource {} at {} is 0x{} on Movement, while Movement Aptos has 0x{}",
+									resource,
+									address,
+									hex::encode(&bytes),
+									hex::encode(&other_bytes)
+								),
+							);
+						}
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl Criterionish for ResourcesEqual {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let mut errors = Vec::new();
+		for side in Side::BOTH {
+			self.check_side(&pair, side, movement_executor, maptos_executor, &mut errors)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+		}
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "resources-equal";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// Additional groups a resource may have been moved into.
+	groups: Vec<String>,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that every resource is equal, comparing resource groups member by member.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let groups = parameters
+				.groups
+				.iter()
+				.map(|group| StructTag::from_str(group))
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(|e| RegistryError::Parameters(NAME.to_string(), e.into()))?;
+			let criterion = ResourcesEqual::new()
+				.with_groups(groups)
+				.with_divergences(parameters.divergences);
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter("groups", "Additional groups a resource may have been moved into.", "[]")
+	.with_divergences()
+}
@@ -0,0 +1,327 @@
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::registry::{self, Registration};
+use mtma_node_test_types::state::resources::{
+	aptos_coin_metadata_address, aptos_coin_type_tag, CoinInfo, CoinStore,
+	ConcurrentFungibleBalance, ConcurrentSupply, FungibleStore, ObjectGroup, OptionalAggregator,
+	ResourceGroup, StakePool, Supply,
+};
+use mtma_node_test_types::state::{
+	self, AccountAddress, Side, StateKey, StateKeyKind, StateViewPair,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+/// An asset whose supply is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Asset {
+	/// `0x1::aptos_coin::AptosCoin` held as a coin.
+	AptosCoin,
+	/// A fungible asset, identified by its metadata address.
+	FungibleAsset(AccountAddress),
+}
+
+impl Asset {
+	/// Describes the asset for matching against divergences, by its `CoinInfo` or metadata object.
+	fn subject(&self) -> Subject {
+		match self {
+			Asset::AptosCoin => Subject::account(AccountAddress::ONE)
+				.with_resource(CoinInfo::struct_tag(aptos_coin_type_tag())),
+			Asset::FungibleAsset(metadata) => Subject::account(*metadata),
+		}
+	}
+}
+
+impl std::fmt::Display for Asset {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Asset::AptosCoin => write!(f, "coin 0x1::aptos_coin::AptosCoin"),
+			Asset::FungibleAsset(metadata) => write!(f, "fungible asset {}", metadata),
+		}
+	}
+}
+
+/// The supply and summed balances of an asset on one side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Tally {
+	/// The recorded supply, if tracked.
+	supply: Option<u128>,
+	/// The sum of all balances.
+	balances: u128,
+}
+
+impl Tally {
+	/// Combines two tallies, such as a coin and its paired fungible asset.
+	fn combine(self, other: Self) -> Self {
+		let supply = match (self.supply, other.supply) {
+			(None, None) => None,
+			(supply, other_supply) => Some(supply.unwrap_or(0) + other_supply.unwrap_or(0)),
+		};
+		Self { supply, balances: self.balances + other.balances }
+	}
+}
+
+/// Whether a state key holds a supply, a balance, or staked coins.
+fn is_supply_or_balance_key(key: &StateKey) -> bool {
+	match StateKeyKind::of(key) {
+		StateKeyKind::Resource(_, struct_tag) => {
+			struct_tag == CoinInfo::struct_tag(aptos_coin_type_tag())
+				|| struct_tag == CoinStore::struct_tag(aptos_coin_type_tag())
+				|| struct_tag == StakePool::struct_tag()
+		}
+		StateKeyKind::ResourceGroup(_, struct_tag) => struct_tag == ObjectGroup::struct_tag(),
+		_ => false,
+	}
+}
+
+pub struct SupplyConserved {
+	/// The divergences the migration is expected to introduce, e.g. minted or burned assets.
+	divergences: DivergenceSpec,
+	/// How far the balances may fall short of the supply on each side.
+	///
+	/// The tally misses value held outside of the tallied stores, e.g. coins embedded in vesting or delegation pools,
+	/// or fees collected but not yet distributed, so a network with such sinks needs a tolerance.
+	per_side_tolerance: u128,
+}
+
+impl SupplyConserved {
+	pub fn new() -> Self {
+		Self { divergences: DivergenceSpec::default(), per_side_tolerance: 0 }
+	}
+
+	pub fn criterion() -> Criterion<Self> {
+		Criterion::new(Self::new())
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Builder API: sets how far the balances on each side may fall short of the supply, for known sinks.
+	pub fn with_per_side_tolerance(mut self, tolerance: u128) -> Self {
+		self.per_side_tolerance = tolerance;
+		self
+	}
+
+	/// Reads the supply recorded in a `CoinInfo`.
+	fn coin_supply(
+		pair: &StateViewPair,
+		side: Side,
+		supply: Option<OptionalAggregator>,
+	) -> Result<Option<u128>, anyhow::Error> {
+		let Some(supply) = supply else {
+			return Ok(None);
+		};
+
+		match (supply.aggregator, supply.integer) {
+			(Some(aggregator), _) => {
+				let value = pair.side_value::<u128>(side, &aggregator.state_key()?)?;
+				Ok(Some(value.unwrap_or(0)))
+			}
+			(None, Some(integer)) => Ok(Some(integer.value)),
+			(None, None) => Err(anyhow::anyhow!("optional aggregator has neither value")),
+		}
+	}
+
+	/// Tallies the supplies and balances of every asset on one side.
+	fn tally(
+		pair: &StateViewPair,
+		side: Side,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<BTreeMap<Asset, Tally>, anyhow::Error> {
+		let keys =
+			state::state_keys(side, movement_executor, maptos_executor, is_supply_or_balance_key)?;
+		debug!("tallying {} supply and balance keys on {}", keys.len(), side);
+
+		let mut tallies: BTreeMap<Asset, Tally> = BTreeMap::new();
+		for key in keys {
+			match StateKeyKind::of(&key) {
+				StateKeyKind::Resource(_, struct_tag)
+					if struct_tag == CoinInfo::struct_tag(aptos_coin_type_tag()) =>
+				{
+					let Some(coin_info) = pair.side_value::<CoinInfo>(side, &key)? else {
+						continue;
+					};
+					tallies.entry(Asset::AptosCoin).or_default().supply =
+						Self::coin_supply(pair, side, coin_info.supply)?;
+				}
+				StateKeyKind::Resource(_, struct_tag) if struct_tag == StakePool::struct_tag() => {
+					let Some(stake_pool) = pair.side_value::<StakePool>(side, &key)? else {
+						continue;
+					};
+					tallies.entry(Asset::AptosCoin).or_default().balances += stake_pool.total();
+				}
+				StateKeyKind::Resource(..) => {
+					let Some(coin_store) = pair.side_value::<CoinStore>(side, &key)? else {
+						continue;
+					};
+					tallies.entry(Asset::AptosCoin).or_default().balances +=
+						u128::from(coin_store.coin.value);
+				}
+				StateKeyKind::ResourceGroup(address, _) => {
+					let Some(group) = pair.side_value::<ResourceGroup>(side, &key)? else {
+						continue;
+					};
+
+					// an object may be a metadata object, a store, or neither
+					let supply = match ObjectGroup::member::<ConcurrentSupply>(
+						&group,
+						&ConcurrentSupply::struct_tag(),
+					)? {
+						Some(concurrent_supply) => Some(concurrent_supply.current.value),
+						None => ObjectGroup::member::<Supply>(&group, &Supply::struct_tag())?
+							.map(|supply| supply.current),
+					};
+					if supply.is_some() {
+						tallies.entry(Asset::FungibleAsset(address)).or_default().supply = supply;
+					}
+
+					if let Some(store) =
+						ObjectGroup::member::<FungibleStore>(&group, &FungibleStore::struct_tag())?
+					{
+						let concurrent_balance = ObjectGroup::member::<ConcurrentFungibleBalance>(
+							&group,
+							&ConcurrentFungibleBalance::struct_tag(),
+						)?
+						.map(|concurrent_balance| concurrent_balance.balance.value)
+						.unwrap_or(0);
+						tallies
+							.entry(Asset::FungibleAsset(store.metadata.inner))
+							.or_default()
+							.balances += u128::from(store.balance) + u128::from(concurrent_balance);
+					}
+				}
+				_ => {}
+			}
+		}
+
+		Ok(tallies)
+	}
+
+	/// Combines the APT coin and its paired fungible asset, since either may hold APT after the migration.
+	fn combine_apt(mut tallies: BTreeMap<Asset, Tally>) -> BTreeMap<Asset, Tally> {
+		let paired = tallies
+			.remove(&Asset::FungibleAsset(aptos_coin_metadata_address()))
+			.unwrap_or_default();
+		let coin = tallies.remove(&Asset::AptosCoin).unwrap_or_default();
+		tallies.insert(Asset::AptosCoin, coin.combine(paired));
+		tallies
+	}
+}
+
+impl Criterionish for SupplyConserved {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let mut errors = Vec::new();
+		let mut side_tallies = Vec::new();
+		for side in Side::BOTH {
+			let tallies = Self::tally(&pair, side, movement_executor, maptos_executor)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+
+			// the sum of balances must equal the supply on each side, short of the tolerance
+			for (asset, tally) in &tallies {
+				match tally.supply {
+					Some(supply)
+						if tally.balances > supply
+							|| supply - tally.balances > self.per_side_tolerance =>
+					{
+						errors.push(format!(
+							"{} has supply {} on {}, while its balances sum to {}",
+							asset, supply, side, tally.balances
+						));
+					}
+					Some(_) => {}
+					None => debug!("{} has no tracked supply on {}", asset, side),
+				}
+			}
+
+			side_tallies.push(Self::combine_apt(tallies));
+		}
+
+		// the two sides must match
+		let movement_aptos_tallies = side_tallies.pop().unwrap_or_default();
+		let movement_tallies = side_tallies.pop().unwrap_or_default();
+		for (asset, movement_tally) in &movement_tallies {
+			let movement_aptos_tally =
+				movement_aptos_tallies.get(asset).copied().unwrap_or_default();
+			if *movement_tally != movement_aptos_tally {
+				self.divergences.record(
+					&mut errors,
+					&asset.subject(),
+					Change::Changed,
+					format!(
+						"{} has supply {:?} and balances {} on Movement, while Movement Aptos has supply {:?} and balances {}",
+						asset,
+						movement_tally.supply,
+						movement_tally.balances,
+						movement_aptos_tally.supply,
+						movement_aptos_tally.balances
+					),
+				);
+			}
+		}
+		for asset in movement_aptos_tallies.keys() {
+			if !movement_tallies.contains_key(asset) {
+				self.divergences.record(
+					&mut errors,
+					&asset.subject(),
+					Change::Added,
+					format!("{} unexpectedly exists on Movement Aptos", asset),
+				);
+			}
+		}
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "supply-conserved";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+	/// How far the balances may fall short of the supply on each side.
+	per_side_tolerance: u128,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that the summed balances equal the supply on each side, and that supplies and balances match across the migration.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let criterion = SupplyConserved::new()
+				.with_divergences(parameters.divergences)
+				.with_per_side_tolerance(parameters.per_side_tolerance);
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter(
+		"per_side_tolerance",
+		"How far the balances may fall short of the supply on each side, for known sinks.",
+		"0",
+	)
+	.with_divergences()
+}
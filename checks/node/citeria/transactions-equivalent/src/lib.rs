@@ -0,0 +1,414 @@
+use anyhow::Context;
+use mtma_node_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_node_test_types::criterion::{
+	Criterion, CriterionError, Criterionish, MovementAptosNode, MovementNode,
+};
+use mtma_node_test_types::divergence::{Change, DivergenceSpec, Subject};
+use mtma_node_test_types::prelude::Prelude;
+use mtma_node_test_types::registry::{self, Registration, RegistryError};
+use mtma_node_test_types::state::resources::{
+	aptos_coin_metadata_address, aptos_coin_type_tag, CoinInfo, CoinStore, FungibleStore,
+	ObjectCore, ObjectGroup, ResourceGroup,
+};
+use mtma_node_test_types::state::{self, AccountAddress, StateKey, StateKeyKind, StateViewPair};
+use mtma_types::movement::aptos_types::transaction::SignedTransaction as MovementSignedTransaction;
+use mtma_types::movement::aptos_vm::aptos_vm::AptosSimulationVM as MovementSimulationVM;
+use mtma_types::movement_aptos::aptos_crypto::ed25519::Ed25519Signature;
+use mtma_types::movement_aptos::aptos_sdk::move_types::language_storage::TypeTag;
+use mtma_types::movement_aptos::aptos_types::contract_event::ContractEvent;
+use mtma_types::movement_aptos::aptos_types::transaction::{
+	authenticator::TransactionAuthenticator, SignedTransaction, TransactionStatus,
+};
+use mtma_types::movement_aptos::aptos_vm::aptos_vm::AptosSimulationVM;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::debug;
+
+/// Replaces the signature of a transaction with a dummy signature over the same public key.
+///
+/// The simulation rejects transactions which are validly signed, e.g. those in a prelude.
+fn unsigned(transaction: &SignedTransaction) -> Result<SignedTransaction, anyhow::Error> {
+	match transaction.authenticator_ref() {
+		TransactionAuthenticator::Ed25519 { public_key, .. } => Ok(SignedTransaction::new(
+			transaction.raw_transaction_ref().clone(),
+			public_key.clone(),
+			Ed25519Signature::dummy_signature(),
+		)),
+		authenticator => Err(anyhow::anyhow!(
+			"only transactions signed by a single Ed25519 key can be simulated, not {:?}",
+			authenticator
+		)),
+	}
+}
+
+/// The output of a simulated transaction, in Movement Aptos types so both sides compare directly.
+#[derive(Debug)]
+pub struct Simulated {
+	/// The transaction status.
+	pub status: TransactionStatus,
+	/// The gas used.
+	pub gas_used: u64,
+	/// The value written for each state key, in write set order, or none if deleted.
+	pub writes: Vec<(StateKey, Option<Vec<u8>>)>,
+	/// The events emitted.
+	pub events: Vec<ContractEvent>,
+}
+
+impl Simulated {
+	/// Simulates a transaction against the Movement state at a version.
+	pub fn movement(
+		movement_executor: &MovementNode,
+		version: u64,
+		transaction: &SignedTransaction,
+	) -> Result<Self, anyhow::Error> {
+		let state_view = movement_executor
+			.state_view_at_version(Some(version))
+			.context("failed to get movement state view")?;
+		// transactions are encoded identically on both sides, so we go through the encoding
+		let transaction: MovementSignedTransaction =
+			bcs::from_bytes(&bcs::to_bytes(&unsigned(transaction)?)?)
+				.context("failed to decode movement aptos transaction as a movement transaction")?;
+		let (_, output) = MovementSimulationVM::create_vm_and_simulate_signed_transaction(
+			&transaction,
+			&state_view,
+		);
+
+		let writes = output
+			.write_set()
+			.iter()
+			.map(|(key, write_op)| {
+				Ok((
+					state::to_movement_aptos_state_key(key)?,
+					write_op.bytes().map(|bytes| bytes.to_vec()),
+				))
+			})
+			.collect::<Result<_, anyhow::Error>>()?;
+		// events are encoded identically on both sides, so we go through the encoding
+		let events = output
+			.events()
+			.iter()
+			.map(|event| Ok(bcs::from_bytes(&bcs::to_bytes(event)?)?))
+			.collect::<Result<_, anyhow::Error>>()
+			.context("failed to decode movement event as a movement aptos event")?;
+		let status = bcs::from_bytes(&bcs::to_bytes(output.status())?)
+			.context("failed to decode movement transaction status as a movement aptos status")?;
+
+		Ok(Self { status, gas_used: output.gas_used(), writes, events })
+	}
+
+	/// Simulates a transaction against the Movement Aptos state at a version.
+	pub fn movement_aptos(
+		maptos_executor: &MovementAptosNode,
+		version: u64,
+		transaction: &SignedTransaction,
+	) -> Result<Self, anyhow::Error> {
+		let state_view = maptos_executor
+			.state_view_at_version(Some(version))
+			.context("failed to get movement aptos state view")?;
+		let (_, output) = AptosSimulationVM::create_vm_and_simulate_signed_transaction(
+			&unsigned(transaction)?,
+			&state_view,
+		);
+
+		let writes = output
+			.write_set()
+			.iter()
+			.map(|(key, write_op)| (key.clone(), write_op.bytes().map(|bytes| bytes.to_vec())))
+			.collect();
+
+		Ok(Self {
+			status: output.status().clone(),
+			gas_used: output.gas_used(),
+			writes,
+			events: output.events().to_vec(),
+		})
+	}
+}
+
+/// Whether an event is the `0x1::transaction_fee::FeeStatement` emitted with the gas charged.
+fn is_fee_statement(type_tag: &TypeTag) -> bool {
+	matches!(
+		type_tag,
+		TypeTag::Struct(struct_tag)
+			if struct_tag.address == AccountAddress::ONE
+				&& struct_tag.module.as_str() == "transaction_fee"
+				&& struct_tag.name.as_str() == "FeeStatement"
+	)
+}
+
+/// The state keys which hold APT balances and the APT supply, which change with the gas charged.
+struct FeeKeys {
+	/// The account paying for gas.
+	payer: AccountAddress,
+	/// The table item holding the APT coin supply, if tracked by an aggregator.
+	supply_aggregator: Option<StateKey>,
+}
+
+impl FeeKeys {
+	/// Whether a write to the key may differ only because of the gas charged.
+	fn contains(&self, key: &StateKey, values: [Option<&Vec<u8>>; 2]) -> bool {
+		match StateKeyKind::of(key) {
+			StateKeyKind::Resource(address, struct_tag) => {
+				(address == self.payer
+					&& struct_tag == CoinStore::struct_tag(aptos_coin_type_tag()))
+					|| (address == AccountAddress::ONE
+						&& struct_tag == CoinInfo::struct_tag(aptos_coin_type_tag()))
+			}
+			StateKeyKind::ResourceGroup(address, struct_tag)
+				if struct_tag == ObjectGroup::struct_tag() =>
+			{
+				// the supply of the paired fungible asset, or a primary store of APT owned by the payer
+				address == aptos_coin_metadata_address()
+					|| values.into_iter().flatten().any(|bytes| self.is_payer_apt_store(bytes))
+			}
+			StateKeyKind::Other => self.supply_aggregator.as_ref() == Some(key),
+			_ => false,
+		}
+	}
+
+	/// Whether an object group holds an APT store owned by the payer.
+	fn is_payer_apt_store(&self, bytes: &[u8]) -> bool {
+		let Ok(group) = bcs::from_bytes::<ResourceGroup>(bytes) else {
+			return false;
+		};
+		let owner = ObjectGroup::member::<ObjectCore>(&group, &ObjectCore::struct_tag());
+		let store = ObjectGroup::member::<FungibleStore>(&group, &FungibleStore::struct_tag());
+		matches!(
+			(owner, store),
+			(Ok(Some(owner)), Ok(Some(store)))
+				if owner.owner == self.payer && store.metadata.inner == aptos_coin_metadata_address()
+		)
+	}
+}
+
+/// Executes new transactions speculatively on both sides, and checks that they have the same outputs.
+///
+/// Each transaction is simulated alone against the state at the latest Movement version, so none is committed
+/// and the transactions should not depend on each other.
+pub struct TransactionsEquivalent {
+	/// The transactions, signed for Movement.
+	transactions: Prelude,
+	/// Whether the gas schedule is expected to change, so gas and the fees charged for it may differ.
+	gas_schedule_changed: bool,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+impl TransactionsEquivalent {
+	pub fn new(transactions: Prelude) -> Self {
+		Self { transactions, gas_schedule_changed: false, divergences: DivergenceSpec::default() }
+	}
+
+	pub fn criterion(transactions: Prelude) -> Criterion<Self> {
+		Criterion::new(Self::new(transactions))
+	}
+
+	/// Builder API: sets whether the gas schedule is expected to change.
+	///
+	/// Gas used may then differ, along with the fee statement event and the writes to the payer's APT balance
+	/// and the APT supply which the fee changes.
+	pub fn with_gas_schedule_changed(mut self, gas_schedule_changed: bool) -> Self {
+		self.gas_schedule_changed = gas_schedule_changed;
+		self
+	}
+
+	/// Builder API: sets the divergences the migration is expected to introduce.
+	pub fn with_divergences(mut self, divergences: DivergenceSpec) -> Self {
+		self.divergences = divergences;
+		self
+	}
+
+	/// Compares the outputs of a transaction on both sides.
+	fn compare(
+		&self,
+		transaction: &SignedTransaction,
+		fee_keys: &FeeKeys,
+		movement: Simulated,
+		movement_aptos: Simulated,
+		errors: &mut Vec<String>,
+	) {
+		let hash = transaction.committed_hash();
+
+		if movement.status != movement_aptos.status {
+			errors.push(format!(
+				"Transaction {} has status {:?} on Movement, while Movement Aptos has {:?}",
+				hash, movement.status, movement_aptos.status
+			));
+		}
+
+		if movement.gas_used != movement_aptos.gas_used && !self.gas_schedule_changed {
+			errors.push(format!(
+				"Transaction {} uses {} gas on Movement, while Movement Aptos uses {}",
+				hash, movement.gas_used, movement_aptos.gas_used
+			));
+		}
+
+		let is_fee_statement = |event: &&ContractEvent| {
+			self.gas_schedule_changed && is_fee_statement(event.type_tag())
+		};
+		let movement_events: Vec<_> =
+			movement.events.iter().filter(|event| !is_fee_statement(event)).collect();
+		let movement_aptos_events: Vec<_> =
+			movement_aptos.events.iter().filter(|event| !is_fee_statement(event)).collect();
+		if movement_events != movement_aptos_events {
+			errors.push(format!(
+				"Transaction {} emits {:?} on Movement, while Movement Aptos emits {:?}",
+				hash, movement_events, movement_aptos_events
+			));
+		}
+
+		let movement_aptos_writes: HashMap<_, _> = movement_aptos.writes.iter().cloned().collect();
+		for (key, movement_value) in &movement.writes {
+			let movement_aptos_value = movement_aptos_writes.get(key);
+			if movement_aptos_value == Some(movement_value) {
+				continue;
+			}
+			if self.gas_schedule_changed
+				&& fee_keys.contains(
+					key,
+					[movement_value.as_ref(), movement_aptos_value.and_then(Option::as_ref)],
+				) {
+				continue;
+			}
+			let change =
+				if movement_aptos_value.is_some() { Change::Changed } else { Change::Removed };
+			self.divergences.record(
+				errors,
+				&Subject::key(key),
+				change,
+				format!(
+					"Transaction {} writes {:?} to {:?} on Movement, while Movement Aptos writes {:?}",
+					hash, movement_value, key, movement_aptos_value
+				),
+			);
+		}
+
+		let movement_writes: HashMap<_, _> = movement.writes.iter().cloned().collect();
+		for (key, movement_aptos_value) in &movement_aptos.writes {
+			if movement_writes.contains_key(key) {
+				continue;
+			}
+			if self.gas_schedule_changed
+				&& fee_keys.contains(key, [None, movement_aptos_value.as_ref()])
+			{
+				continue;
+			}
+			self.divergences.record(
+				errors,
+				&Subject::key(key),
+				Change::Added,
+				format!(
+					"Transaction {} writes {:?} to {:?} only on Movement Aptos",
+					hash, movement_aptos_value, key
+				),
+			);
+		}
+	}
+}
+
+impl Criterionish for TransactionsEquivalent {
+	fn satisfies(
+		&self,
+		movement_executor: &MovementNode,
+		maptos_executor: &MovementAptosNode,
+	) -> Result<(), CriterionError> {
+		let pair = StateViewPair::latest(movement_executor, maptos_executor)
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+		let version = pair.version();
+
+		// the supply aggregator is read from Movement, where the supply was last written
+		let supply_aggregator = pair
+			.movement_value::<CoinInfo>(
+				&StateKey::resource(
+					&AccountAddress::ONE,
+					&CoinInfo::struct_tag(aptos_coin_type_tag()),
+				)
+				.map_err(|e| CriterionError::Internal(e.into()))?,
+			)
+			.map_err(|e| CriterionError::Internal(e.into()))?
+			.and_then(|coin_info| coin_info.supply)
+			.and_then(|supply| supply.aggregator)
+			.map(|aggregator| aggregator.state_key())
+			.transpose()
+			.map_err(|e| CriterionError::Internal(e.into()))?;
+
+		let mut errors = Vec::new();
+		for movement_transaction in self.transactions.blocks().iter().flatten() {
+			// transactions are encoded identically on both sides, so we go through the encoding
+			let transaction: SignedTransaction = bcs::to_bytes(movement_transaction)
+				.and_then(|bytes| bcs::from_bytes(&bytes))
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+			debug!(
+				"simulating transaction {} at version {}",
+				transaction.committed_hash(),
+				version
+			);
+
+			let movement = Simulated::movement(movement_executor, version, &transaction)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+			let movement_aptos = Simulated::movement_aptos(maptos_executor, version, &transaction)
+				.map_err(|e| CriterionError::Internal(e.into()))?;
+
+			let fee_keys = FeeKeys {
+				payer: transaction
+					.authenticator_ref()
+					.fee_payer_address()
+					.unwrap_or_else(|| transaction.sender()),
+				supply_aggregator: supply_aggregator.clone(),
+			};
+			self.compare(&transaction, &fee_keys, movement, movement_aptos, &mut errors);
+		}
+
+		if !errors.is_empty() {
+			return Err(CriterionError::Unsatisfied(errors.join("\n").into()));
+		}
+
+		Ok(())
+	}
+}
+
+/// The name the criterion is registered under.
+pub const NAME: &str = "transactions-equivalent";
+
+/// The parameters the criterion is registered with.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Parameters {
+	/// A saved prelude of the transactions to simulate, or none to simulate nothing.
+	transactions: Option<PathBuf>,
+	/// Whether the gas schedule is expected to change.
+	gas_schedule_changed: bool,
+	/// The divergences the migration is expected to introduce.
+	divergences: DivergenceSpec,
+}
+
+/// Registers the criterion under [NAME].
+pub fn registration() -> Registration<BoxedEvaluatish> {
+	Registration::new(
+		NAME,
+		"Checks that new transactions have the same outputs when simulated on both sides.",
+		|parameters| {
+			let parameters: Parameters = registry::parameters(NAME, parameters)?;
+			let transactions = match parameters.transactions {
+				Some(path) => Prelude::load(&path).map_err(|e| RegistryError::Build(e.into()))?,
+				None => Prelude::new_empty(),
+			};
+			let criterion = TransactionsEquivalent::new(transactions)
+				.with_gas_schedule_changed(parameters.gas_schedule_changed)
+				.with_divergences(parameters.divergences);
+			Ok(Box::new(Named::criterion(NAME, criterion)) as BoxedEvaluatish)
+		},
+	)
+	.with_parameter(
+		"transactions",
+		"A prelude of the transactions to simulate, saved as BCS or JSON lines.",
+		"null",
+	)
+	.with_parameter(
+		"gas_schedule_changed",
+		"Whether gas, and the fees charged for it, may differ due to a gas schedule change.",
+		"false",
+	)
+	.with_divergences()
+}
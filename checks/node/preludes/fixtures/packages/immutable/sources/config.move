@@ -0,0 +1,20 @@
+/// A package published as immutable, which the migration must keep immutable.
+module publisher::config {
+    use std::signer;
+
+    struct Config has key {
+        value: u64,
+        updates: u64,
+    }
+
+    public entry fun set(account: &signer, value: u64) acquires Config {
+        let address = signer::address_of(account);
+        if (!exists<Config>(address)) {
+            move_to(account, Config { value, updates: 0 });
+        } else {
+            let config = borrow_global_mut<Config>(address);
+            config.value = value;
+            config.updates = config.updates + 1;
+        }
+    }
+}
@@ -0,0 +1,33 @@
+/// Stores the results of framework natives, so the migration is checked against their outputs.
+module publisher::natives {
+    use std::bcs;
+    use std::hash;
+    use std::signer;
+    use std::string::String;
+    use aptos_std::aptos_hash;
+    use aptos_std::string_utils;
+    use aptos_std::table::{Self, Table};
+    use aptos_std::type_info;
+
+    struct Digests has key {
+        by_input: Table<vector<u8>, vector<u8>>,
+        keccak: vector<u8>,
+        type_name: String,
+        formatted: String,
+    }
+
+    public entry fun record(account: &signer, input: vector<u8>) acquires Digests {
+        let address = signer::address_of(account);
+        if (!exists<Digests>(address)) {
+            move_to(account, Digests {
+                by_input: table::new(),
+                keccak: vector[],
+                type_name: type_info::type_name<Digests>(),
+                formatted: string_utils::to_string(&address),
+            });
+        };
+        let digests = borrow_global_mut<Digests>(address);
+        table::upsert(&mut digests.by_input, input, hash::sha3_256(bcs::to_bytes(&input)));
+        digests.keccak = aptos_hash::keccak256(input);
+    }
+}
@@ -0,0 +1,36 @@
+/// A user-defined resource group, whose members are stored together under a single state key.
+module publisher::resource_groups {
+    use std::signer;
+    use std::string::String;
+
+    #[resource_group(scope = global)]
+    struct Group {}
+
+    #[resource_group_member(group = publisher::resource_groups::Group)]
+    struct Counter has key {
+        value: u64,
+    }
+
+    #[resource_group_member(group = publisher::resource_groups::Group)]
+    struct Label has key {
+        label: String,
+    }
+
+    public entry fun increment(account: &signer) acquires Counter {
+        let address = signer::address_of(account);
+        if (!exists<Counter>(address)) {
+            move_to(account, Counter { value: 0 });
+        };
+        let counter = borrow_global_mut<Counter>(address);
+        counter.value = counter.value + 1;
+    }
+
+    public entry fun label(account: &signer, label: String) acquires Label {
+        let address = signer::address_of(account);
+        if (!exists<Label>(address)) {
+            move_to(account, Label { label });
+        } else {
+            borrow_global_mut<Label>(address).label = label;
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Composition of preludes from stages which share the accounts they sign with.
+//!
+//! Each stage is generated after the stages before it, so it may sign with the accounts they funded,
+//! and its transactions follow theirs in sequence number.
+use crate::payloads;
+use crate::EXPIRATION_TIMESTAMP_SECS;
+use mtma_node_test_types::{
+	criterion::movement_executor::maptos_opt_executor::{
+		aptos_crypto::ed25519::Ed25519PrivateKey,
+		aptos_sdk::{
+			transaction_builder::TransactionFactory,
+			types::{AccountKey, LocalAccount},
+		},
+		aptos_types::{
+			account_config::aptos_test_root_address,
+			chain_id::ChainId,
+			transaction::{SignedTransaction, TransactionPayload},
+		},
+	},
+	prelude::{Prelude, PreludeError, PreludeGenerator},
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::future::Future;
+
+/// The root account and transaction factory a prelude signs with, the rng it generates accounts from,
+/// and the accounts funded so far.
+pub struct Signers {
+	root_account: LocalAccount,
+	tx_factory: TransactionFactory,
+	rng: StdRng,
+	/// The accounts funded by earlier stages, which later stages sign with.
+	pub accounts: Vec<LocalAccount>,
+}
+
+impl Signers {
+	pub fn new(private_key: Ed25519PrivateKey, chain_id: ChainId, seed: [u8; 32]) -> Self {
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(private_key),
+			0,
+		);
+		let tx_factory = TransactionFactory::new(chain_id)
+			.with_transaction_expiration_time(EXPIRATION_TIMESTAMP_SECS);
+		Self { root_account, tx_factory, rng: StdRng::from_seed(seed), accounts: Vec::new() }
+	}
+
+	/// The root account, which funds the generated accounts.
+	pub fn root_account(&self) -> &LocalAccount {
+		&self.root_account
+	}
+
+	/// Generates accounts, creating and funding them in the current block.
+	///
+	/// The accounts are returned rather than added to [Signers::accounts], see [Fund] for that.
+	pub fn generate_accounts(
+		&mut self,
+		prelude: &mut Prelude,
+		count: usize,
+		funding: u64,
+	) -> Vec<LocalAccount> {
+		let accounts: Vec<LocalAccount> =
+			(0..count).map(|_| LocalAccount::generate(&mut self.rng)).collect();
+		for account in &accounts {
+			prelude.add_transaction(self.root_account.sign_with_transaction_builder(
+				self.tx_factory.create_user_account(account.public_key()),
+			));
+			prelude.add_transaction(
+				self.root_account.sign_with_transaction_builder(
+					self.tx_factory.mint(account.address(), funding),
+				),
+			);
+		}
+		accounts
+	}
+
+	/// Generates a key, e.g. to rotate to.
+	pub fn key(&mut self) -> AccountKey {
+		AccountKey::generate(&mut self.rng)
+	}
+
+	/// The transaction factory the signers sign with, e.g. to sign a payload with the gas of another transaction.
+	pub fn tx_factory(&self) -> &TransactionFactory {
+		&self.tx_factory
+	}
+
+	/// Signs a payload by an account.
+	pub fn sign(&self, account: &LocalAccount, payload: TransactionPayload) -> SignedTransaction {
+		account.sign_with_transaction_builder(self.tx_factory.payload(payload))
+	}
+}
+
+/// A part of a composed prelude, which depends on the signers as left by the stages before it.
+pub trait Stage {
+	/// Generates the transactions of the stage, signed by the signers.
+	fn generate(self, signers: &mut Signers)
+		-> impl Future<Output = Result<Prelude, PreludeError>>;
+}
+
+/// A prelude is a stage as it is, e.g. one loaded from a file.
+///
+/// Its transactions are already signed, so it should not share senders with the other stages.
+impl Stage for Prelude {
+	async fn generate(self, _signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		Ok(self)
+	}
+}
+
+/// A stage generated by a function of the signers, see [from_fn].
+pub struct FromFn<F>(F);
+
+/// A stage generated by a function of the signers.
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+	F: FnOnce(&mut Signers) -> Result<Prelude, PreludeError>,
+{
+	FromFn(f)
+}
+
+impl<F> Stage for FromFn<F>
+where
+	F: FnOnce(&mut Signers) -> Result<Prelude, PreludeError>,
+{
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		(self.0)(signers)
+	}
+}
+
+/// A stage funding new accounts in a single block, which are added to the signers for later stages.
+pub struct Fund {
+	/// The number of accounts.
+	pub accounts: usize,
+	/// The number of octas each account is funded with.
+	pub funding: u64,
+}
+
+impl Fund {
+	pub fn new(accounts: usize, funding: u64) -> Self {
+		Self { accounts, funding }
+	}
+}
+
+impl Stage for Fund {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let accounts = signers.generate_accounts(&mut prelude, self.accounts, self.funding);
+		signers.accounts.extend(accounts);
+		Ok(prelude)
+	}
+}
+
+/// A stage of rounds of transfers, each a block in which every funded account transfers to the next.
+pub struct Transfers {
+	/// The number of rounds.
+	pub rounds: usize,
+	/// The number of octas each transfer sends.
+	pub amount: u64,
+}
+
+impl Transfers {
+	pub fn new(rounds: usize, amount: u64) -> Self {
+		Self { rounds, amount }
+	}
+}
+
+impl Stage for Transfers {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		if signers.accounts.len() < 2 && self.rounds > 0 {
+			return Err(PreludeError::Internal(
+				"transfers need at least two funded accounts".into(),
+			));
+		}
+
+		let mut prelude = Prelude::new_empty();
+		for _ in 0..self.rounds {
+			prelude.end_block();
+			let accounts = &signers.accounts;
+			for (account, next) in accounts.iter().zip(accounts.iter().cycle().skip(1)) {
+				prelude.add_transaction(
+					signers.sign(account, payloads::transfer(next.address(), self.amount)?),
+				);
+			}
+		}
+		Ok(prelude)
+	}
+}
+
+/// A prelude composed of stages, generated in order against shared [Signers].
+///
+/// ```ignore
+/// let prelude = Composition::new(private_key, chain_id)
+/// 	.then(Fund::new(4, 1_000_000_000))
+/// 	.await?
+/// 	.then(Transfers::new(10, 1_000))
+/// 	.await?
+/// 	.into_prelude();
+/// ```
+pub struct Composition {
+	signers: Signers,
+	prelude: Prelude,
+}
+
+impl Composition {
+	pub fn new(private_key: Ed25519PrivateKey, chain_id: ChainId) -> Self {
+		Self::with_signers(Signers::new(private_key, chain_id, [0u8; 32]))
+	}
+
+	/// Composes against existing signers, e.g. ones generated from another seed.
+	pub fn with_signers(signers: Signers) -> Self {
+		Self { signers, prelude: Prelude::new_empty() }
+	}
+
+	/// Generates a stage and appends its blocks to the composed prelude.
+	pub async fn then(mut self, stage: impl Stage) -> Result<Self, PreludeError> {
+		let prelude = stage.generate(&mut self.signers).await?;
+		self.prelude.extend(prelude);
+		Ok(self)
+	}
+
+	/// The signers, as left by the stages so far.
+	pub fn signers(&self) -> &Signers {
+		&self.signers
+	}
+
+	/// The composed prelude.
+	pub fn into_prelude(self) -> Prelude {
+		self.prelude
+	}
+}
+
+impl PreludeGenerator for Composition {
+	async fn generate(self) -> Result<Prelude, PreludeError> {
+		Ok(self.into_prelude())
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_stages_share_signers() -> Result<(), anyhow::Error> {
+		let private_key = Ed25519PrivateKey::try_from([7u8; 32].as_slice())?;
+		let composition = Composition::new(private_key, ChainId::test())
+			.then(Fund::new(3, 1_000))
+			.await?
+			.then(Transfers::new(2, 10))
+			.await?;
+		assert_eq!(composition.signers().accounts.len(), 3);
+
+		// the funding block, then a block of transfers per round
+		let prelude = composition.into_prelude();
+		let sizes: Vec<usize> = prelude.blocks().iter().map(Vec::len).collect();
+		assert_eq!(sizes, vec![6, 3, 3]);
+
+		// the second round continues the sequence numbers of the first
+		let transfers = &prelude.blocks()[2];
+		assert!(transfers.iter().all(|transaction| transaction.sequence_number() == 1));
+
+		Ok(())
+	}
+}
@@ -0,0 +1,12 @@
+//! Preludes targeting framework edge cases which a migration must preserve.
+pub mod delegated_staking;
+pub mod deleted_resources;
+pub mod large_tables;
+pub mod max_size_values;
+pub mod multisig;
+pub mod paired_balances;
+pub mod resource_accounts;
+pub mod rotated_keys;
+
+/// The default number of octas each generated account is funded with.
+pub(crate) const DEFAULT_FUNDING: u64 = 100_000_000_000;
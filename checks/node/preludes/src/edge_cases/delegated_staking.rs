@@ -0,0 +1,116 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads::{bcs_arg, entry_function};
+use mtma_node_test_types::{
+	criterion::movement_executor::maptos_opt_executor::aptos_types::account_address::create_resource_address,
+	prelude::{Prelude, PreludeError},
+};
+
+/// The salt delegation pool addresses are derived with.
+const DELEGATION_POOL_SALT: &[u8] = b"aptos_framework::delegation_pool";
+
+/// The seed the pool is created with.
+const DELEGATION_POOL_SEED: &[u8] = b"delegated-staking-prelude";
+
+/// A prelude of a delegation pool, which delegators add stake to and then partially unlock.
+///
+/// Delegation pools must be enabled by feature flag on the local network.
+pub struct DelegatedStakingPrelude {
+	/// The number of delegators.
+	pub delegators: usize,
+	/// The number of octas each delegator stakes.
+	pub stake: u64,
+	/// The commission of the operator, in hundredths of a percent.
+	pub operator_commission_percentage: u64,
+}
+
+impl Default for DelegatedStakingPrelude {
+	fn default() -> Self {
+		Self { delegators: 3, stake: 1_000_000_000, operator_commission_percentage: 1_000 }
+	}
+}
+
+impl DelegatedStakingPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of delegators.
+	pub fn with_delegators(mut self, delegators: usize) -> Self {
+		self.delegators = delegators;
+		self
+	}
+
+	/// Builder API: sets the number of octas each delegator stakes.
+	pub fn with_stake(mut self, stake: u64) -> Self {
+		self.stake = stake;
+		self
+	}
+
+	/// Builder API: sets the commission of the operator, in hundredths of a percent.
+	pub fn with_operator_commission_percentage(
+		mut self,
+		operator_commission_percentage: u64,
+	) -> Self {
+		self.operator_commission_percentage = operator_commission_percentage;
+		self
+	}
+}
+
+impl Stage for DelegatedStakingPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let owner = signers.generate_accounts(&mut prelude, 1, DEFAULT_FUNDING).remove(0);
+		let delegators =
+			signers.generate_accounts(&mut prelude, self.delegators, self.stake.saturating_mul(2));
+
+		// the pool is a resource account of the owner
+		let pool_address = create_resource_address(
+			owner.address(),
+			&[DELEGATION_POOL_SEED, DELEGATION_POOL_SALT].concat(),
+		);
+
+		prelude.end_block();
+		prelude.add_transaction(signers.sign(
+			&owner,
+			entry_function(
+				"0x1::delegation_pool",
+				"initialize_delegation_pool",
+				vec![],
+				vec![
+					bcs_arg(&self.operator_commission_percentage)?,
+					bcs_arg(DELEGATION_POOL_SEED)?,
+				],
+			)?,
+		));
+
+		prelude.end_block();
+		for delegator in &delegators {
+			prelude.add_transaction(signers.sign(
+				delegator,
+				entry_function(
+					"0x1::delegation_pool",
+					"add_stake",
+					vec![],
+					vec![bcs_arg(&pool_address)?, bcs_arg(&self.stake)?],
+				)?,
+			));
+		}
+
+		// unlocking leaves stake pending inactive, which is tracked apart from the active shares
+		prelude.end_block();
+		for delegator in &delegators {
+			prelude.add_transaction(signers.sign(
+				delegator,
+				entry_function(
+					"0x1::delegation_pool",
+					"unlock",
+					vec![],
+					vec![bcs_arg(&pool_address)?, bcs_arg(&(self.stake / 2))?],
+				)?,
+			));
+		}
+
+		Ok(prelude)
+	}
+}
@@ -0,0 +1,125 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads::{self, bcs_arg, entry_function};
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+
+/// The collection the tokens are created in.
+const COLLECTION: &str = "deleted-resources";
+
+/// A prelude of state which is written and then deleted, so it is only in the history of the Movement DB.
+///
+/// A creator offers tokens to a receiver and cancels the offers, deleting the pending claims,
+/// and burns tokens it holds, deleting them from its token store.
+pub struct DeletedResourcesPrelude {
+	/// The number of tokens created, and then offered or burned.
+	pub tokens: usize,
+}
+
+impl Default for DeletedResourcesPrelude {
+	fn default() -> Self {
+		Self { tokens: 4 }
+	}
+}
+
+impl DeletedResourcesPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of tokens created.
+	pub fn with_tokens(mut self, tokens: usize) -> Self {
+		self.tokens = tokens;
+		self
+	}
+}
+
+impl Stage for DeletedResourcesPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let mut accounts = signers.generate_accounts(&mut prelude, 2, DEFAULT_FUNDING);
+		let receiver = accounts.remove(1);
+		let creator = accounts.remove(0);
+		let token = |name: &str| -> Result<Vec<Vec<u8>>, PreludeError> {
+			Ok(vec![
+				bcs_arg(&creator.address())?,
+				bcs_arg(COLLECTION)?,
+				bcs_arg(name)?,
+				// the property version
+				bcs_arg(&0u64)?,
+			])
+		};
+
+		prelude.end_block();
+		prelude.add_transaction(
+			signers.sign(&creator, payloads::create_token_collection(COLLECTION)?),
+		);
+		for index in 0..self.tokens {
+			prelude.add_transaction(signers.sign(
+				&creator,
+				entry_function(
+					"0x3::token",
+					"create_token_script",
+					vec![],
+					vec![
+						bcs_arg(COLLECTION)?,
+						bcs_arg(&format!("token-{}", index))?,
+						bcs_arg("token")?,
+						// the balance and maximum
+						bcs_arg(&1u64)?,
+						bcs_arg(&1u64)?,
+						bcs_arg("https://movementlabs.xyz")?,
+						// the royalty
+						bcs_arg(&creator.address())?,
+						bcs_arg(&100u64)?,
+						bcs_arg(&0u64)?,
+						bcs_arg(&vec![false; 5])?,
+						// the tokens must be burnable for the owner to burn them
+						bcs_arg(&vec![String::from("TOKEN_BURNABLE_BY_OWNER")])?,
+						bcs_arg(&vec![bcs_arg(&true)?])?,
+						bcs_arg(&vec![String::from("bool")])?,
+					],
+				)?,
+			));
+		}
+
+		// offer the even tokens, and burn the odd ones
+		prelude.end_block();
+		for index in 0..self.tokens {
+			let name = format!("token-{}", index);
+			let payload = if index % 2 == 0 {
+				entry_function(
+					"0x3::token_transfers",
+					"offer_script",
+					vec![],
+					[vec![bcs_arg(&receiver.address())?], token(&name)?, vec![bcs_arg(&1u64)?]]
+						.concat(),
+				)?
+			} else {
+				entry_function(
+					"0x3::token",
+					"burn",
+					vec![],
+					[token(&name)?, vec![bcs_arg(&1u64)?]].concat(),
+				)?
+			};
+			prelude.add_transaction(signers.sign(&creator, payload));
+		}
+
+		// cancel the offers
+		prelude.end_block();
+		for index in (0..self.tokens).step_by(2) {
+			let name = format!("token-{}", index);
+			prelude.add_transaction(signers.sign(
+				&creator,
+				entry_function(
+					"0x3::token_transfers",
+					"cancel_offer_script",
+					vec![],
+					[vec![bcs_arg(&receiver.address())?], token(&name)?].concat(),
+				)?,
+			));
+		}
+
+		Ok(prelude)
+	}
+}
@@ -0,0 +1,55 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads;
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+
+/// A prelude of a table with a large number of entries, i.e. the token collections of a single creator.
+pub struct LargeTablesPrelude {
+	/// The number of entries written to the table.
+	pub entries: usize,
+	/// The number of entries written per block.
+	pub block_size: usize,
+}
+
+impl Default for LargeTablesPrelude {
+	fn default() -> Self {
+		Self { entries: 1_000, block_size: 100 }
+	}
+}
+
+impl LargeTablesPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of entries written to the table.
+	pub fn with_entries(mut self, entries: usize) -> Self {
+		self.entries = entries;
+		self
+	}
+
+	/// Builder API: sets the number of entries written per block.
+	pub fn with_block_size(mut self, block_size: usize) -> Self {
+		self.block_size = block_size;
+		self
+	}
+}
+
+impl Stage for LargeTablesPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let creator = signers.generate_accounts(&mut prelude, 1, DEFAULT_FUNDING).remove(0);
+
+		// each collection is an entry in the creator's collection data table
+		for entry in 0..self.entries {
+			if entry % self.block_size.max(1) == 0 {
+				prelude.end_block();
+			}
+			let name = format!("large-table-{}", entry);
+			prelude
+				.add_transaction(signers.sign(&creator, payloads::create_token_collection(&name)?));
+		}
+
+		Ok(prelude)
+	}
+}
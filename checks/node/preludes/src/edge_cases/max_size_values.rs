@@ -0,0 +1,68 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads;
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+
+/// The number of bytes each transaction adds to the value, which keeps it under the transaction size limit.
+const DEFAULT_CHUNK: usize = 48 * 1024;
+
+/// A prelude growing a single state value to close to the size limit of a write.
+///
+/// The value is the package registry of a publisher, which holds the metadata of every package it publishes.
+/// Each transaction publishes an empty package whose manifest is a chunk of the target size.
+pub struct MaxSizeValuesPrelude {
+	/// The size the value is grown to, in bytes.
+	pub size: usize,
+	/// The number of bytes each transaction adds.
+	pub chunk: usize,
+}
+
+impl Default for MaxSizeValuesPrelude {
+	fn default() -> Self {
+		Self {
+			// just under the 1 MiB a write may be, leaving room for the rest of the registry
+			size: 1_000 * 1024,
+			chunk: DEFAULT_CHUNK,
+		}
+	}
+}
+
+impl MaxSizeValuesPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the size the value is grown to.
+	pub fn with_size(mut self, size: usize) -> Self {
+		self.size = size;
+		self
+	}
+
+	/// Builder API: sets the number of bytes each transaction adds.
+	pub fn with_chunk(mut self, chunk: usize) -> Self {
+		self.chunk = chunk;
+		self
+	}
+}
+
+impl Stage for MaxSizeValuesPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let publisher = signers.generate_accounts(&mut prelude, 1, DEFAULT_FUNDING).remove(0);
+
+		// each package is a block of its own, as each grows the same value
+		let chunk = self.chunk.max(1);
+		for (index, start) in (0..self.size).step_by(chunk).enumerate() {
+			let manifest: Vec<u8> =
+				(start..(start + chunk).min(self.size)).map(|byte| byte as u8).collect();
+			let name = format!("max_size_{}", index);
+			prelude.end_block();
+			prelude.add_transaction(signers.sign(
+				&publisher,
+				payloads::publish_empty_package(publisher.address(), &name, manifest)?,
+			));
+		}
+
+		Ok(prelude)
+	}
+}
@@ -0,0 +1,127 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads::{self, bcs_arg, entry_function};
+use mtma_node_test_types::{
+	criterion::movement_executor::maptos_opt_executor::aptos_types::{
+		account_address::{create_resource_address, AccountAddress},
+		transaction::{Multisig, MultisigTransactionPayload, TransactionPayload},
+	},
+	prelude::{Prelude, PreludeError},
+};
+
+/// The domain separator multisig account addresses are derived with.
+const MULTISIG_DOMAIN_SEPARATOR: &[u8] = b"aptos_framework::multisig_account";
+
+/// A prelude of a multisig account, which is funded and then executes a transfer its owners have approved.
+pub struct MultisigPrelude {
+	/// The number of owners.
+	pub owners: usize,
+	/// The number of approvals a transaction needs.
+	pub signatures_required: u64,
+}
+
+impl Default for MultisigPrelude {
+	fn default() -> Self {
+		Self { owners: 3, signatures_required: 2 }
+	}
+}
+
+impl MultisigPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of owners.
+	pub fn with_owners(mut self, owners: usize) -> Self {
+		self.owners = owners;
+		self
+	}
+
+	/// Builder API: sets the number of approvals a transaction needs.
+	pub fn with_signatures_required(mut self, signatures_required: u64) -> Self {
+		self.signatures_required = signatures_required;
+		self
+	}
+}
+
+impl Stage for MultisigPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		if (self.owners as u64) < self.signatures_required.max(1) {
+			return Err(PreludeError::Internal(
+				"a multisig account needs at least as many owners as signatures required".into(),
+			));
+		}
+
+		let mut prelude = Prelude::new_empty();
+		let owners = signers.generate_accounts(&mut prelude, self.owners, DEFAULT_FUNDING);
+		let (creator, others) = owners.split_first().expect("there is at least one owner");
+
+		// the multisig account is derived from the creator's sequence number when it is created
+		let seed = [MULTISIG_DOMAIN_SEPARATOR, &bcs_arg(&creator.sequence_number())?].concat();
+		let multisig_address = create_resource_address(creator.address(), &seed);
+		let other_addresses: Vec<AccountAddress> =
+			others.iter().map(|owner| owner.address()).collect();
+
+		prelude.end_block();
+		prelude.add_transaction(signers.sign(
+			creator,
+			entry_function(
+				"0x1::multisig_account",
+				"create_with_owners",
+				vec![],
+				vec![
+					bcs_arg(&other_addresses)?,
+					bcs_arg(&self.signatures_required)?,
+					bcs_arg(&Vec::<String>::new())?,
+					bcs_arg(&Vec::<Vec<u8>>::new())?,
+				],
+			)?,
+		));
+		prelude.add_transaction(
+			signers.sign(creator, payloads::transfer(multisig_address, DEFAULT_FUNDING / 2)?),
+		);
+
+		// propose a transfer back to the creator, which the creator's proposal approves
+		prelude.end_block();
+		let proposal = match payloads::transfer(creator.address(), DEFAULT_FUNDING / 4)? {
+			TransactionPayload::EntryFunction(entry_function) => {
+				MultisigTransactionPayload::EntryFunction(entry_function)
+			}
+			_ => unreachable!("transfers are entry functions"),
+		};
+		prelude.add_transaction(signers.sign(
+			creator,
+			entry_function(
+				"0x1::multisig_account",
+				"create_transaction",
+				vec![],
+				vec![bcs_arg(&multisig_address)?, bcs_arg(&bcs_arg(&proposal)?)?],
+			)?,
+		));
+
+		// approve it by the remaining owners, then execute it
+		prelude.end_block();
+		for owner in others.iter().take(self.signatures_required.saturating_sub(1) as usize) {
+			prelude.add_transaction(signers.sign(
+				owner,
+				entry_function(
+					"0x1::multisig_account",
+					"approve_transaction",
+					vec![],
+					vec![bcs_arg(&multisig_address)?, bcs_arg(&1u64)?],
+				)?,
+			));
+		}
+		prelude.end_block();
+		prelude.add_transaction(signers.sign(
+			creator,
+			TransactionPayload::Multisig(Multisig {
+				multisig_address,
+				// the payload is stored on chain with the proposal
+				transaction_payload: None,
+			}),
+		));
+
+		Ok(prelude)
+	}
+}
@@ -0,0 +1,75 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads::{self, entry_function, type_tag};
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+
+/// A prelude of coin balances paired with fungible asset balances.
+///
+/// Half of the accounts migrate their coin store to a primary fungible store, and the rest keep their coin store
+/// while receiving the paired fungible asset too, so their balance is split across both.
+/// The coin to fungible asset migration must be enabled by feature flag on the local network.
+pub struct PairedBalancesPrelude {
+	/// The number of accounts.
+	pub accounts: usize,
+	/// The number of octas each transfer sends.
+	pub amount: u64,
+}
+
+impl Default for PairedBalancesPrelude {
+	fn default() -> Self {
+		Self { accounts: 4, amount: 1_000_000 }
+	}
+}
+
+impl PairedBalancesPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of accounts.
+	pub fn with_accounts(mut self, accounts: usize) -> Self {
+		self.accounts = accounts;
+		self
+	}
+
+	/// Builder API: sets the number of octas each transfer sends.
+	pub fn with_amount(mut self, amount: u64) -> Self {
+		self.amount = amount;
+		self
+	}
+}
+
+impl Stage for PairedBalancesPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let accounts = signers.generate_accounts(&mut prelude, self.accounts, DEFAULT_FUNDING);
+
+		// migrate every other account to a primary fungible store
+		prelude.end_block();
+		for account in accounts.iter().step_by(2) {
+			prelude.add_transaction(signers.sign(
+				account,
+				entry_function(
+					"0x1::coin",
+					"migrate_to_fungible_store",
+					vec![type_tag("0x1::aptos_coin::AptosCoin")?],
+					vec![],
+				)?,
+			));
+		}
+
+		// transfer both the coin and the fungible asset around the accounts
+		prelude.end_block();
+		for (account, next) in accounts.iter().zip(accounts.iter().cycle().skip(1)) {
+			prelude.add_transaction(
+				signers.sign(account, payloads::transfer(next.address(), self.amount)?),
+			);
+			prelude.add_transaction(
+				signers
+					.sign(account, payloads::fungible_asset_transfer(next.address(), self.amount)?),
+			);
+		}
+
+		Ok(prelude)
+	}
+}
@@ -0,0 +1,85 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads::{self, bcs_arg, entry_function};
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+
+/// A prelude of resource accounts, some funded by their origin and some with an authentication key handed to another account.
+pub struct ResourceAccountsPrelude {
+	/// The number of accounts which create resource accounts.
+	pub origins: usize,
+	/// The number of resource accounts each origin creates.
+	pub resource_accounts: usize,
+	/// The number of octas funded resource accounts are funded with.
+	pub funding: u64,
+}
+
+impl Default for ResourceAccountsPrelude {
+	fn default() -> Self {
+		Self { origins: 2, resource_accounts: 3, funding: 1_000_000 }
+	}
+}
+
+impl ResourceAccountsPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of accounts which create resource accounts.
+	pub fn with_origins(mut self, origins: usize) -> Self {
+		self.origins = origins;
+		self
+	}
+
+	/// Builder API: sets the number of resource accounts each origin creates.
+	pub fn with_resource_accounts(mut self, resource_accounts: usize) -> Self {
+		self.resource_accounts = resource_accounts;
+		self
+	}
+
+	/// Builder API: sets the number of octas funded resource accounts are funded with.
+	pub fn with_funding(mut self, funding: u64) -> Self {
+		self.funding = funding;
+		self
+	}
+}
+
+impl Stage for ResourceAccountsPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let origins = signers.generate_accounts(&mut prelude, self.origins, DEFAULT_FUNDING);
+
+		prelude.end_block();
+		for (index, origin) in origins.iter().enumerate() {
+			// the next origin controls the resource accounts which are handed an authentication key
+			let controller = &origins[(index + 1) % origins.len()];
+			for resource_account in 0..self.resource_accounts {
+				let seed = format!("resource-account-{}", resource_account);
+				let payload = match resource_account % 3 {
+					0 => payloads::create_resource_account(seed.as_bytes())?,
+					1 => entry_function(
+						"0x1::resource_account",
+						"create_resource_account_and_fund",
+						vec![],
+						vec![
+							bcs_arg(seed.as_bytes())?,
+							bcs_arg(&Vec::<u8>::new())?,
+							bcs_arg(&self.funding)?,
+						],
+					)?,
+					_ => entry_function(
+						"0x1::resource_account",
+						"create_resource_account",
+						vec![],
+						vec![
+							bcs_arg(seed.as_bytes())?,
+							bcs_arg(&controller.authentication_key().to_vec())?,
+						],
+					)?,
+				};
+				prelude.add_transaction(signers.sign(origin, payload));
+			}
+		}
+
+		Ok(prelude)
+	}
+}
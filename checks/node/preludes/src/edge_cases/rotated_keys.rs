@@ -0,0 +1,63 @@
+use super::DEFAULT_FUNDING;
+use crate::compose::{Signers, Stage};
+use crate::payloads;
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+
+/// A prelude of accounts whose authentication keys are rotated, so their addresses no longer derive from their keys.
+///
+/// After rotating, each account transfers to the next, so the rotated keys are known to authenticate.
+pub struct RotatedKeysPrelude {
+	/// The number of accounts whose keys are rotated.
+	pub accounts: usize,
+	/// The number of times each key is rotated.
+	pub rotations: usize,
+}
+
+impl Default for RotatedKeysPrelude {
+	fn default() -> Self {
+		Self { accounts: 4, rotations: 2 }
+	}
+}
+
+impl RotatedKeysPrelude {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: sets the number of accounts whose keys are rotated.
+	pub fn with_accounts(mut self, accounts: usize) -> Self {
+		self.accounts = accounts;
+		self
+	}
+
+	/// Builder API: sets the number of times each key is rotated.
+	pub fn with_rotations(mut self, rotations: usize) -> Self {
+		self.rotations = rotations;
+		self
+	}
+}
+
+impl Stage for RotatedKeysPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let mut prelude = Prelude::new_empty();
+		let mut accounts = signers.generate_accounts(&mut prelude, self.accounts, DEFAULT_FUNDING);
+
+		// each rotation is a block of its own, so every account rotates from the key set by the last one
+		for _ in 0..self.rotations {
+			prelude.end_block();
+			for account in accounts.iter_mut() {
+				let new_key = signers.key();
+				let payload = payloads::rotate_authentication_key(account, &new_key)?;
+				prelude.add_transaction(signers.sign(account, payload));
+				account.rotate_key(new_key);
+			}
+		}
+
+		prelude.end_block();
+		for (account, next) in accounts.iter().zip(accounts.iter().cycle().skip(1)) {
+			prelude.add_transaction(signers.sign(account, payloads::transfer(next.address(), 1)?));
+		}
+
+		Ok(prelude)
+	}
+}
@@ -0,0 +1,346 @@
+use crate::compose::{Signers, Stage};
+use crate::package::rewrite_addresses;
+use mtma_node_test_types::{
+	criterion::movement_executor::{
+		maptos_opt_executor::{
+			aptos_sdk::{
+				move_types::language_storage::{ModuleId, StructTag, TypeTag},
+				types::LocalAccount,
+			},
+			aptos_types::{
+				account_address::AccountAddress,
+				transaction::{EntryFunction, SignedTransaction, Transaction, TransactionPayload},
+			},
+		},
+		MovementNode,
+	},
+	prelude::{Prelude, PreludeError},
+	state::{self, resources::PackageMetadata},
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use tracing::{debug, info};
+
+/// The default number of octas each generated account is funded with.
+const DEFAULT_FUNDING: u64 = 100_000_000_000;
+
+/// Why a transaction of the history is not replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+	/// The transaction did not succeed on the source, so it would abort the prelude.
+	Failed,
+	/// The transaction has secondary signers or a fee payer, who cannot be re-signed for.
+	OtherSigners,
+	/// The payload is a script, a module bundle, a multisig transaction or an object code deployment,
+	/// whose addresses cannot be remapped.
+	Unsupported,
+}
+
+impl std::fmt::Display for SkipReason {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SkipReason::Failed => write!(f, "failed on the source"),
+			SkipReason::OtherSigners => write!(f, "has other signers"),
+			SkipReason::Unsupported => write!(f, "has an unsupported payload"),
+		}
+	}
+}
+
+/// A transaction of the history which is not replayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+	/// The version of the transaction on the source.
+	pub version: u64,
+	/// The sender on the source.
+	pub sender: AccountAddress,
+	pub reason: SkipReason,
+}
+
+/// A history prelude, along with how it was remapped and what was skipped.
+#[derive(Debug, Clone)]
+pub struct History {
+	pub prelude: Prelude,
+	/// The generated local account of each sender on the source.
+	pub addresses: BTreeMap<AccountAddress, AccountAddress>,
+	/// The transactions which are not replayed, in the order of the history.
+	pub skipped: Vec<Skipped>,
+}
+
+/// A prelude replaying the user transactions of a Movement DB over a version range, e.g. from mainnet.
+///
+/// Each sender is remapped onto a generated local account, which is created and funded by the root account in the first block.
+/// The transactions are re-signed by their remapped senders, so they execute on a fresh local genesis.
+/// Where a sender's address appears in an entry function payload, i.e. in the module called, a type argument,
+/// or an `address` or `vector<address>` argument, it is remapped too, as it is in the modules and dependencies of packages the sender publishes.
+///
+/// Transactions which would not replay are skipped and reported, see [SkipReason].
+/// Transactions which succeeded on the source but depend on state which is not replayed, e.g. modules published at object addresses, still fail the prelude.
+pub struct HistoryPrelude<'a> {
+	/// The Movement node whose history is replayed.
+	pub source: &'a MovementNode,
+	/// The versions whose user transactions are replayed.
+	pub versions: Range<u64>,
+	/// The number of octas each generated account is funded with.
+	pub funding: u64,
+}
+
+impl<'a> HistoryPrelude<'a> {
+	pub fn new(source: &'a MovementNode, versions: Range<u64>) -> Self {
+		Self { source, versions, funding: DEFAULT_FUNDING }
+	}
+
+	/// Builder API: sets the number of octas each generated account is funded with.
+	pub fn with_funding(mut self, funding: u64) -> Self {
+		self.funding = funding;
+		self
+	}
+
+	/// Reads the user transactions in the version range, keeping their blocks and versions.
+	///
+	/// Transactions which did not succeed on the source are skipped.
+	fn history(
+		&self,
+		skipped: &mut Vec<Skipped>,
+	) -> Result<Vec<Vec<(u64, SignedTransaction)>>, PreludeError> {
+		let db_reader = self.source.db_reader();
+		let latest_version = self
+			.source
+			.latest_ledger_version()
+			.map_err(|e| PreludeError::Internal(e.into()))?;
+
+		let mut blocks = Vec::new();
+		for block in self
+			.source
+			.iter_blocks(self.versions.start)
+			.map_err(|e| PreludeError::Internal(e.into()))?
+		{
+			let (start_version, _end_version, block) =
+				block.map_err(|e| PreludeError::Internal(e.into()))?;
+			if start_version >= self.versions.end {
+				break;
+			}
+
+			let mut transactions = Vec::new();
+			for (transaction, version) in
+				block.transactions.into_txns().into_iter().zip(start_version..)
+			{
+				if !self.versions.contains(&version) {
+					continue;
+				}
+				let Transaction::UserTransaction(signed_transaction) = transaction.into_inner()
+				else {
+					continue;
+				};
+
+				let info = db_reader
+					.get_transaction_by_version(version, latest_version, false)
+					.map_err(|e| PreludeError::Internal(e.into()))?
+					.proof
+					.transaction_info;
+				if !info.status().is_success() {
+					skipped.push(Skipped {
+						version,
+						sender: signed_transaction.sender(),
+						reason: SkipReason::Failed,
+					});
+					continue;
+				}
+				transactions.push((version, signed_transaction));
+			}
+			if !transactions.is_empty() {
+				blocks.push(transactions);
+			}
+		}
+		Ok(blocks)
+	}
+
+	/// Generates the prelude, reporting how senders were remapped and which transactions were skipped.
+	pub fn replay(self, signers: &mut Signers) -> Result<History, PreludeError> {
+		let mut skipped = Vec::new();
+		let history = self.history(&mut skipped)?;
+
+		// remap every sender onto a generated local account, which is created and funded first
+		let senders: BTreeSet<AccountAddress> =
+			history.iter().flatten().map(|(_, transaction)| transaction.sender()).collect();
+		let mut prelude = Prelude::new_empty();
+		let generated = signers.generate_accounts(&mut prelude, senders.len(), self.funding);
+		let accounts: BTreeMap<AccountAddress, LocalAccount> =
+			senders.into_iter().zip(generated).collect();
+		let addresses: BTreeMap<AccountAddress, AccountAddress> =
+			accounts.iter().map(|(sender, account)| (*sender, account.address())).collect();
+		info!("Replaying {} blocks from {} senders", history.len(), accounts.len());
+
+		// re-sign the history, block by block
+		for block in history {
+			prelude.end_block();
+			for (version, transaction) in block {
+				let sender = transaction.sender();
+				let authenticator = transaction.authenticator();
+				let remapped = if !authenticator.secondary_signer_addresses().is_empty()
+					|| authenticator.fee_payer_address().is_some()
+				{
+					Err(SkipReason::OtherSigners)
+				} else {
+					remap_payload(transaction.payload(), &addresses)?
+				};
+
+				match remapped {
+					Ok(payload) => {
+						// every sender in the history has an account
+						let builder = signers
+							.tx_factory()
+							.payload(payload)
+							.max_gas_amount(transaction.max_gas_amount())
+							.gas_unit_price(transaction.gas_unit_price());
+						prelude.add_transaction(
+							accounts[&sender].sign_with_transaction_builder(builder),
+						);
+					}
+					Err(reason) => {
+						debug!("Skipping transaction {} from {}: {}", version, sender, reason);
+						skipped.push(Skipped { version, sender, reason });
+					}
+				}
+			}
+		}
+
+		skipped.sort_by_key(|skipped| skipped.version);
+		Ok(History { prelude, addresses, skipped })
+	}
+}
+
+/// Remaps an address if it is a remapped sender.
+fn remap_address(
+	address: &AccountAddress,
+	addresses: &BTreeMap<AccountAddress, AccountAddress>,
+) -> AccountAddress {
+	*addresses.get(address).unwrap_or(address)
+}
+
+/// Remaps the addresses of the structs in a type tag.
+fn remap_type_tag(
+	type_tag: &TypeTag,
+	addresses: &BTreeMap<AccountAddress, AccountAddress>,
+) -> TypeTag {
+	match type_tag {
+		TypeTag::Vector(inner) => TypeTag::Vector(Box::new(remap_type_tag(inner, addresses))),
+		TypeTag::Struct(struct_tag) => TypeTag::Struct(Box::new(StructTag {
+			address: remap_address(&struct_tag.address, addresses),
+			module: struct_tag.module.clone(),
+			name: struct_tag.name.clone(),
+			type_args: struct_tag
+				.type_args
+				.iter()
+				.map(|type_arg| remap_type_tag(type_arg, addresses))
+				.collect(),
+		})),
+		type_tag => type_tag.clone(),
+	}
+}
+
+/// Remaps an entry function argument which is an `address` or a `vector<address>` containing a remapped sender.
+///
+/// Arguments are untyped bytes, so any other argument which decodes as such is remapped too,
+/// but it is unlikely that one does and also contains a remapped sender.
+fn remap_arg(
+	arg: &[u8],
+	addresses: &BTreeMap<AccountAddress, AccountAddress>,
+) -> Result<Vec<u8>, PreludeError> {
+	if let Ok(address) = bcs::from_bytes::<AccountAddress>(arg) {
+		if addresses.contains_key(&address) {
+			return Ok(remap_address(&address, addresses).to_vec());
+		}
+	}
+	if let Ok(vector) = bcs::from_bytes::<Vec<AccountAddress>>(arg) {
+		if vector.iter().any(|address| addresses.contains_key(address)) {
+			let remapped: Vec<AccountAddress> =
+				vector.iter().map(|address| remap_address(address, addresses)).collect();
+			return bcs::to_bytes(&remapped).map_err(|e| PreludeError::Internal(e.into()));
+		}
+	}
+	Ok(arg.to_vec())
+}
+
+/// Remaps the arguments of `0x1::code::publish_package_txn`, i.e. the package metadata and the modules.
+fn remap_publish_args(
+	args: &[Vec<u8>],
+	addresses: &BTreeMap<AccountAddress, AccountAddress>,
+) -> Result<Vec<Vec<u8>>, PreludeError> {
+	let [metadata, modules] = args else {
+		return Err(PreludeError::Internal(
+			format!("expected 2 arguments to publish_package_txn, got {}", args.len()).into(),
+		));
+	};
+	let decode_error = |e: bcs::Error| PreludeError::Internal(e.into());
+
+	let metadata: Vec<u8> = bcs::from_bytes(metadata).map_err(decode_error)?;
+	let mut metadata: PackageMetadata = bcs::from_bytes(&metadata).map_err(decode_error)?;
+	for dep in metadata.deps.iter_mut() {
+		let account = remap_address(&AccountAddress::new(dep.account.into_bytes()), addresses);
+		dep.account = state::AccountAddress::new(account.into_bytes());
+	}
+	let metadata = bcs::to_bytes(&metadata).map_err(decode_error)?;
+
+	let modules: Vec<Vec<u8>> = bcs::from_bytes(modules).map_err(decode_error)?;
+	let modules = modules
+		.iter()
+		.map(|module| rewrite_addresses(module, addresses))
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(vec![
+		bcs::to_bytes(&metadata).map_err(decode_error)?,
+		bcs::to_bytes(&modules).map_err(decode_error)?,
+	])
+}
+
+/// Remaps the addresses of remapped senders in an entry function payload, or gives why the payload cannot be remapped.
+fn remap_payload(
+	payload: &TransactionPayload,
+	addresses: &BTreeMap<AccountAddress, AccountAddress>,
+) -> Result<Result<TransactionPayload, SkipReason>, PreludeError> {
+	let TransactionPayload::EntryFunction(entry_function) = payload else {
+		return Ok(Err(SkipReason::Unsupported));
+	};
+
+	let module = entry_function.module();
+	let function = entry_function.function().as_str();
+	let framework = module.address() == &AccountAddress::ONE;
+	let args = match (framework, module.name().as_str(), function) {
+		(true, "object_code_deployment", _) => return Ok(Err(SkipReason::Unsupported)),
+		(true, "code", "publish_package_txn") => {
+			remap_publish_args(entry_function.args(), addresses)?
+		}
+		_ => entry_function
+			.args()
+			.iter()
+			.map(|arg| remap_arg(arg, addresses))
+			.collect::<Result<Vec<_>, _>>()?,
+	};
+
+	Ok(Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+		ModuleId::new(remap_address(module.address(), addresses), module.name().to_owned()),
+		entry_function.function().to_owned(),
+		entry_function
+			.ty_args()
+			.iter()
+			.map(|type_arg| remap_type_tag(type_arg, addresses))
+			.collect(),
+		args,
+	))))
+}
+
+impl Stage for HistoryPrelude<'_> {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let history = self.replay(signers)?;
+
+		let mut reasons: BTreeMap<SkipReason, usize> = BTreeMap::new();
+		for skipped in &history.skipped {
+			*reasons.entry(skipped.reason).or_default() += 1;
+		}
+		for (reason, count) in reasons {
+			info!("Skipped {} transactions which {}", count, reason);
+		}
+
+		Ok(history.prelude)
+	}
+}
@@ -1 +1,12 @@
 pub mod basic;
+pub mod compose;
+pub mod edge_cases;
+pub mod history;
+pub mod package;
+pub mod payloads;
+pub mod random;
+
+/// The expiration time the preludes sign transactions with, in seconds.
+///
+/// It is fixed far in the future rather than taken from the clock, so the same prelude signs to the same bytes whenever it is generated.
+pub const EXPIRATION_TIMESTAMP_SECS: u64 = 4_000_000_000;
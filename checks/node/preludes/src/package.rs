@@ -0,0 +1,526 @@
+use crate::compose::{Signers, Stage};
+use mtma_node_test_types::{
+	criterion::movement_executor::maptos_opt_executor::{
+		aptos_sdk::move_types::{
+			identifier::Identifier,
+			language_storage::{ModuleId, TypeTag},
+		},
+		aptos_types::{
+			account_address::AccountAddress,
+			transaction::{EntryFunction, TransactionPayload},
+		},
+	},
+	prelude::{Prelude, PreludeError},
+	state::{self, resources::PackageMetadata},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use tracing::info;
+
+/// The default number of octas each publishing account is funded with.
+const DEFAULT_FUNDING: u64 = 100_000_000_000;
+
+/// The named address packages publish their modules under.
+pub const NAMED_ADDRESS: &str = "publisher";
+
+/// The address packages are compiled with, which is rewritten to the address of the account publishing them.
+pub const PLACEHOLDER_ADDRESS: &str = "0xcafe";
+
+/// The file, at the root of a package, listing the entry functions to call once it is published.
+pub const CALLS_FILE: &str = "prelude.yaml";
+
+/// The CLI packages are compiled with by default.
+pub const DEFAULT_COMPILER: &str = "aptos";
+
+/// The directory of the Move packages bundled as fixtures.
+pub fn fixtures() -> PathBuf {
+	PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join("packages")
+}
+
+/// Whether the packages in a directory can be loaded, i.e. each is already built or the compiler can be run to build it.
+///
+/// The bundled [fixtures] are not committed built, so e.g. tests publishing them need the CLI unless they have been built before.
+pub fn loadable(packages: &Path, compiler: &Path) -> bool {
+	let built = std::fs::read_dir(packages).is_ok_and(|entries| {
+		entries
+			.filter_map(Result::ok)
+			.map(|entry| entry.path())
+			.filter(|path| path.is_dir())
+			.all(|path| PackagePrelude::build(&path).is_some())
+	});
+	built
+		|| Command::new(compiler)
+			.arg("--version")
+			.output()
+			.is_ok_and(|output| output.status.success())
+}
+
+/// A package compiled with `aptos move compile --save-metadata`, with [NAMED_ADDRESS] set to [PLACEHOLDER_ADDRESS].
+#[derive(Debug, Clone)]
+pub struct CompiledPackage {
+	/// The metadata the package is registered with.
+	pub metadata: PackageMetadata,
+	/// The bytecode of the modules, in the order of the metadata.
+	pub modules: Vec<Vec<u8>>,
+}
+
+impl CompiledPackage {
+	/// Loads a package from its build directory, i.e. `build/<package name>`.
+	pub fn load(build: &Path) -> Result<Self, PreludeError> {
+		let read = |path: PathBuf| {
+			std::fs::read(&path).map_err(|e| PreludeError::File(path.clone(), e.into()))
+		};
+
+		let metadata_path = build.join("package-metadata.bcs");
+		let metadata: PackageMetadata = bcs::from_bytes(&read(metadata_path.clone())?)
+			.map_err(|e| PreludeError::File(metadata_path, e.into()))?;
+		let modules = metadata
+			.modules
+			.iter()
+			.map(|module| read(build.join("bytecode_modules").join(format!("{}.mv", module.name))))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Self { metadata, modules })
+	}
+
+	/// The package as published by an account, with the placeholder address rewritten to the account's address.
+	pub fn at(&self, address: AccountAddress) -> Result<Self, PreludeError> {
+		let placeholder = placeholder_address()?;
+
+		let mut metadata = self.metadata.clone();
+		for dep in metadata.deps.iter_mut() {
+			// packages in the same directory may depend on each other
+			if dep.account.into_bytes() == placeholder.into_bytes() {
+				dep.account = state::AccountAddress::new(address.into_bytes());
+			}
+		}
+		let addresses = BTreeMap::from([(placeholder, address)]);
+		let modules = self
+			.modules
+			.iter()
+			.map(|module| rewrite_addresses(module, &addresses))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Self { metadata, modules })
+	}
+
+	/// The payload publishing the package.
+	pub fn publish_payload(&self) -> Result<TransactionPayload, PreludeError> {
+		let metadata =
+			bcs::to_bytes(&self.metadata).map_err(|e| PreludeError::Internal(e.into()))?;
+		Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+			ModuleId::new(
+				AccountAddress::ONE,
+				Identifier::new("code").expect("framework module names are valid identifiers"),
+			),
+			Identifier::new("publish_package_txn")
+				.expect("framework function names are valid identifiers"),
+			vec![],
+			vec![
+				bcs::to_bytes(&metadata).map_err(|e| PreludeError::Internal(e.into()))?,
+				bcs::to_bytes(&self.modules).map_err(|e| PreludeError::Internal(e.into()))?,
+			],
+		)))
+	}
+}
+
+/// Parses [PLACEHOLDER_ADDRESS].
+fn placeholder_address() -> Result<AccountAddress, PreludeError> {
+	AccountAddress::from_hex_literal(PLACEHOLDER_ADDRESS)
+		.map_err(|e| PreludeError::Internal(e.into()))
+}
+
+/// The key of the module metadata holding the attributes the Aptos VM reads, e.g. resource group membership.
+const METADATA_V1_KEY: &[u8] = b"aptos::metadata_v1";
+
+/// Mirror of `aptos_types::vm::module_metadata::RuntimeModuleMetadataV1`.
+#[derive(Debug, Serialize, Deserialize)]
+struct RuntimeModuleMetadataV1 {
+	error_map: BTreeMap<u64, ErrorDescription>,
+	struct_attributes: BTreeMap<String, Vec<KnownAttribute>>,
+	fun_attributes: BTreeMap<String, Vec<KnownAttribute>>,
+}
+
+/// Mirror of `aptos_types::vm::module_metadata::ErrorDescription`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ErrorDescription {
+	code_name: String,
+	code_description: String,
+}
+
+/// Mirror of `aptos_types::vm::module_metadata::KnownAttribute`, whose arguments may name structs by address,
+/// e.g. `0xcafe::resource_groups::Group` for a resource group member.
+#[derive(Debug, Serialize, Deserialize)]
+struct KnownAttribute {
+	kind: u8,
+	args: Vec<String>,
+}
+
+/// Rewrites an attribute argument naming a struct under a rewritten address.
+fn rewrite_attribute_arg(arg: &mut String, addresses: &BTreeMap<AccountAddress, AccountAddress>) {
+	let Some((address, rest)) = arg.split_once("::") else {
+		return;
+	};
+	let Ok(address) = AccountAddress::from_hex_literal(address) else {
+		return;
+	};
+	if let Some(to) = addresses.get(&address) {
+		*arg = format!("{}::{}", to.to_hex_literal(), rest);
+	}
+}
+
+/// Rewrites the addresses of a module, i.e. of the module handles of the module itself and the modules it uses,
+/// and of the structs named by the attributes in its metadata.
+pub(crate) fn rewrite_addresses(
+	bytecode: &[u8],
+	addresses: &BTreeMap<AccountAddress, AccountAddress>,
+) -> Result<Vec<u8>, PreludeError> {
+	use ::aptos_sdk::move_types::account_address::AccountAddress as CompiledAccountAddress;
+
+	let mut module = move_binary_format::CompiledModule::deserialize(bytecode)
+		.map_err(|e| PreludeError::Internal(e.into()))?;
+	for address in module.address_identifiers.iter_mut() {
+		if let Some(to) = addresses.get(&AccountAddress::new(address.into_bytes())) {
+			*address = CompiledAccountAddress::new(to.into_bytes());
+		}
+	}
+	for metadata in module.metadata.iter_mut() {
+		if metadata.key != METADATA_V1_KEY {
+			continue;
+		}
+		let mut runtime_metadata: RuntimeModuleMetadataV1 =
+			bcs::from_bytes(&metadata.value).map_err(|e| PreludeError::Internal(e.into()))?;
+		for attribute in runtime_metadata
+			.struct_attributes
+			.values_mut()
+			.chain(runtime_metadata.fun_attributes.values_mut())
+			.flatten()
+		{
+			for arg in attribute.args.iter_mut() {
+				rewrite_attribute_arg(arg, addresses);
+			}
+		}
+		metadata.value =
+			bcs::to_bytes(&runtime_metadata).map_err(|e| PreludeError::Internal(e.into()))?;
+	}
+
+	let mut bytes = Vec::new();
+	module.serialize(&mut bytes).map_err(|e| PreludeError::Internal(e.into()))?;
+	Ok(bytes)
+}
+
+/// An argument to an entry function call.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Arg {
+	Bool(bool),
+	U8(u8),
+	U64(u64),
+	Address(AccountAddress),
+	/// The address of the account which published the package.
+	Publisher,
+	String(String),
+	/// Hex-encoded bytes, as a `vector<u8>`.
+	Bytes(String),
+}
+
+impl Arg {
+	/// Serializes the argument for a call from the publisher.
+	fn encode(&self, publisher: AccountAddress) -> Result<Vec<u8>, PreludeError> {
+		match self {
+			Arg::Bool(value) => bcs::to_bytes(value),
+			Arg::U8(value) => bcs::to_bytes(value),
+			Arg::U64(value) => bcs::to_bytes(value),
+			Arg::Address(address) => bcs::to_bytes(address),
+			Arg::Publisher => bcs::to_bytes(&publisher),
+			Arg::String(value) => bcs::to_bytes(value),
+			Arg::Bytes(value) => {
+				bcs::to_bytes(&hex::decode(value).map_err(|e| PreludeError::Internal(e.into()))?)
+			}
+		}
+		.map_err(|e| PreludeError::Internal(e.into()))
+	}
+}
+
+/// An entry function called by the publisher once the package is published.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Call {
+	/// The function, as `<module>::<function>` for a module of the package, or fully qualified by address.
+	pub function: String,
+	/// The type arguments, e.g. `0x1::aptos_coin::AptosCoin`.
+	#[serde(default)]
+	pub type_args: Vec<String>,
+	#[serde(default)]
+	pub args: Vec<Arg>,
+}
+
+impl Call {
+	/// The payload of the call from the publisher.
+	pub fn payload(&self, publisher: AccountAddress) -> Result<TransactionPayload, PreludeError> {
+		let parts: Vec<&str> = self.function.split("::").collect();
+		let (address, module, function) = match parts.as_slice() {
+			[module, function] => (publisher, *module, *function),
+			[address, module, function] => (
+				AccountAddress::from_hex_literal(address)
+					.map_err(|e| PreludeError::Internal(e.into()))?,
+				*module,
+				*function,
+			),
+			_ => {
+				return Err(PreludeError::Internal(
+					format!("malformed function {}", self.function).into(),
+				))
+			}
+		};
+		let type_args = self
+			.type_args
+			.iter()
+			.map(|type_arg| TypeTag::from_str(type_arg))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|e| PreludeError::Internal(e.into()))?;
+		let args = self
+			.args
+			.iter()
+			.map(|arg| arg.encode(publisher))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+			ModuleId::new(
+				address,
+				Identifier::new(module).map_err(|e| PreludeError::Internal(e.into()))?,
+			),
+			Identifier::new(function).map_err(|e| PreludeError::Internal(e.into()))?,
+			type_args,
+			args,
+		)))
+	}
+}
+
+/// The contents of a [CALLS_FILE].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CallsFile {
+	calls: Vec<Call>,
+}
+
+/// A package to publish, along with the calls to make once it is.
+#[derive(Debug, Clone)]
+pub struct Package {
+	/// The directory of the package.
+	pub path: PathBuf,
+	pub compiled: CompiledPackage,
+	pub calls: Vec<Call>,
+}
+
+/// A prelude publishing Move packages from a directory, each from its own generated account, and then calling their entry functions.
+///
+/// Each subdirectory is a package. If it has a `build` directory with a package compiled with [NAMED_ADDRESS] set to [PLACEHOLDER_ADDRESS],
+/// that is loaded, otherwise the package is compiled in place with the configured CLI. The entry functions to call are listed in the package's [CALLS_FILE].
+/// The bundled [fixtures] cover native dependencies, upgrade policies and user-defined resource groups.
+pub struct PackagePrelude {
+	/// The directory of packages.
+	pub packages: PathBuf,
+	/// The CLI packages are compiled with, e.g. `aptos` or `movement`.
+	pub compiler: PathBuf,
+	/// The number of octas each publishing account is funded with.
+	pub funding: u64,
+}
+
+impl PackagePrelude {
+	pub fn new(packages: PathBuf) -> Self {
+		Self { packages, compiler: PathBuf::from(DEFAULT_COMPILER), funding: DEFAULT_FUNDING }
+	}
+
+	/// Builder API: sets the CLI packages are compiled with.
+	pub fn with_compiler(mut self, compiler: PathBuf) -> Self {
+		self.compiler = compiler;
+		self
+	}
+
+	/// Builder API: sets the number of octas each publishing account is funded with.
+	pub fn with_funding(mut self, funding: u64) -> Self {
+		self.funding = funding;
+		self
+	}
+
+	/// Compiles a package in place, so the build is saved alongside it.
+	fn compile(&self, path: &Path) -> Result<(), PreludeError> {
+		info!("Compiling package {:?}", path);
+		let output = Command::new(&self.compiler)
+			.args(["move", "compile", "--save-metadata", "--package-dir"])
+			.arg(path)
+			.arg("--named-addresses")
+			.arg(format!("{}={}", NAMED_ADDRESS, PLACEHOLDER_ADDRESS))
+			.output()
+			.map_err(|e| PreludeError::Internal(e.into()))?;
+		if !output.status.success() {
+			return Err(PreludeError::Internal(
+				format!(
+					"failed to compile package {:?}: {}",
+					path,
+					String::from_utf8_lossy(&output.stderr)
+				)
+				.into(),
+			));
+		}
+		Ok(())
+	}
+
+	/// Finds the build of a package, i.e. the directory under `build` with the package metadata.
+	fn build(path: &Path) -> Option<PathBuf> {
+		std::fs::read_dir(path.join("build"))
+			.ok()?
+			.filter_map(Result::ok)
+			.map(|entry| entry.path())
+			.find(|build| build.join("package-metadata.bcs").is_file())
+	}
+
+	/// Loads the packages in the directory, compiling those which are not yet built.
+	pub fn load_packages(&self) -> Result<Vec<Package>, PreludeError> {
+		let mut paths = std::fs::read_dir(&self.packages)
+			.map_err(|e| PreludeError::File(self.packages.clone(), e.into()))?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|e| PreludeError::File(self.packages.clone(), e.into()))?;
+		// packages are published in a stable order, so the same account publishes the same package
+		paths.retain(|path| path.is_dir());
+		paths.sort();
+
+		let mut packages = Vec::with_capacity(paths.len());
+		for path in paths {
+			let build = match Self::build(&path) {
+				Some(build) => build,
+				None => {
+					self.compile(&path)?;
+					Self::build(&path).ok_or_else(|| {
+						PreludeError::File(path.join("build"), "no package was built".into())
+					})?
+				}
+			};
+
+			let calls_path = path.join(CALLS_FILE);
+			let calls = if calls_path.is_file() {
+				let contents = std::fs::read_to_string(&calls_path)
+					.map_err(|e| PreludeError::File(calls_path.clone(), e.into()))?;
+				serde_yaml::from_str::<CallsFile>(&contents)
+					.map_err(|e| PreludeError::File(calls_path, e.into()))?
+					.calls
+			} else {
+				Vec::new()
+			};
+
+			packages.push(Package { compiled: CompiledPackage::load(&build)?, calls, path });
+		}
+		Ok(packages)
+	}
+}
+
+impl Stage for PackagePrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let packages = self.load_packages()?;
+
+		// create and fund the publishers
+		let mut prelude = Prelude::new_empty();
+		let publishers = signers.generate_accounts(&mut prelude, packages.len(), self.funding);
+
+		// publish the packages, then call into them once they are published
+		prelude.end_block();
+		for (package, publisher) in packages.iter().zip(&publishers) {
+			info!(
+				"Publishing package {} from {:?} at {}",
+				package.compiled.metadata.name,
+				package.path,
+				publisher.address()
+			);
+			let payload = package.compiled.at(publisher.address())?.publish_payload()?;
+			prelude.add_transaction(signers.sign(publisher, payload));
+		}
+		prelude.end_block();
+		for (package, publisher) in packages.iter().zip(&publishers) {
+			for call in &package.calls {
+				let payload = call.payload(publisher.address())?;
+				prelude.add_transaction(signers.sign(publisher, payload));
+			}
+		}
+
+		Ok(prelude)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	#[test]
+	fn test_fixture_calls_parse() -> Result<(), anyhow::Error> {
+		let publisher = AccountAddress::from_hex_literal("0xbeef")?;
+		let mut count = 0;
+		for entry in std::fs::read_dir(fixtures())? {
+			let calls_path = entry?.path().join(CALLS_FILE);
+			let calls: CallsFile = serde_yaml::from_str(&std::fs::read_to_string(calls_path)?)?;
+			for call in calls.calls {
+				let TransactionPayload::EntryFunction(entry_function) = call.payload(publisher)?
+				else {
+					panic!("calls are entry functions");
+				};
+				assert_eq!(entry_function.module().address(), &publisher);
+				count += 1;
+			}
+		}
+		assert!(count > 0);
+		Ok(())
+	}
+
+	#[test]
+	fn test_rewrite_addresses() -> Result<(), anyhow::Error> {
+		use ::aptos_sdk::move_types::account_address::AccountAddress as CompiledAccountAddress;
+		use ::aptos_sdk::move_types::metadata::Metadata;
+
+		let placeholder = placeholder_address()?;
+		let publisher = AccountAddress::from_hex_literal("0xbeef")?;
+
+		// a module at the placeholder address, with a resource group and a member of it
+		let mut module = move_binary_format::file_format::empty_module();
+		module.address_identifiers[0] = CompiledAccountAddress::new(placeholder.into_bytes());
+		let runtime_metadata = RuntimeModuleMetadataV1 {
+			error_map: BTreeMap::new(),
+			struct_attributes: BTreeMap::from([
+				(
+					"Group".to_string(),
+					vec![KnownAttribute { kind: 1, args: vec!["global".to_string()] }],
+				),
+				(
+					"Counter".to_string(),
+					vec![KnownAttribute {
+						kind: 2,
+						args: vec!["0xcafe::resource_groups::Group".to_string()],
+					}],
+				),
+			]),
+			fun_attributes: BTreeMap::new(),
+		};
+		module.metadata.push(Metadata {
+			key: METADATA_V1_KEY.to_vec(),
+			value: bcs::to_bytes(&runtime_metadata)?,
+		});
+		let mut bytecode = Vec::new();
+		module.serialize(&mut bytecode)?;
+
+		let rewritten = rewrite_addresses(&bytecode, &BTreeMap::from([(placeholder, publisher)]))?;
+		let module = move_binary_format::CompiledModule::deserialize(&rewritten)?;
+		assert_eq!(module.address_identifiers[0].into_bytes(), publisher.into_bytes());
+
+		// the member names the group at the publisher's address, and other arguments are kept
+		let runtime_metadata: RuntimeModuleMetadataV1 = bcs::from_bytes(&module.metadata[0].value)?;
+		assert_eq!(
+			runtime_metadata.struct_attributes["Counter"][0].args,
+			vec!["0xbeef::resource_groups::Group"]
+		);
+		assert_eq!(runtime_metadata.struct_attributes["Group"][0].args, vec!["global"]);
+		Ok(())
+	}
+}
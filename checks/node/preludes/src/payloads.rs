@@ -0,0 +1,221 @@
+//! Payloads of framework entry functions which preludes compose workloads from.
+use mtma_node_test_types::{
+	criterion::movement_executor::maptos_opt_executor::{
+		aptos_crypto::SigningKey,
+		aptos_sdk::{
+			move_types::{
+				identifier::Identifier,
+				language_storage::{ModuleId, TypeTag},
+			},
+			types::{AccountKey, LocalAccount},
+		},
+		aptos_types::{
+			account_address::AccountAddress,
+			transaction::{EntryFunction, TransactionPayload},
+		},
+	},
+	prelude::PreludeError,
+	state::resources::{ModuleMetadata, PackageMetadata, UpgradePolicy},
+};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// The bytecode version generated modules are published at, which both Movement and Movement Aptos accept.
+const MODULE_VERSION: u32 = 6;
+
+/// The URI collections and tokens are created with.
+const URI: &str = "https://movementlabs.xyz";
+
+/// Forms an entry function payload, for a module qualified by address, e.g. `0x1::coin`.
+pub fn entry_function(
+	module: &str,
+	function: &str,
+	ty_args: Vec<TypeTag>,
+	args: Vec<Vec<u8>>,
+) -> Result<TransactionPayload, PreludeError> {
+	let (address, module) = module.split_once("::").ok_or_else(|| {
+		PreludeError::Internal(format!("module {} is not qualified by address", module).into())
+	})?;
+	let address =
+		AccountAddress::from_hex_literal(address).map_err(|e| PreludeError::Internal(e.into()))?;
+	Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+		ModuleId::new(
+			address,
+			Identifier::new(module).map_err(|e| PreludeError::Internal(e.into()))?,
+		),
+		Identifier::new(function).map_err(|e| PreludeError::Internal(e.into()))?,
+		ty_args,
+		args,
+	)))
+}
+
+/// Serializes an entry function argument.
+pub fn bcs_arg<T: Serialize + ?Sized>(arg: &T) -> Result<Vec<u8>, PreludeError> {
+	bcs::to_bytes(arg).map_err(|e| PreludeError::Internal(e.into()))
+}
+
+/// Parses a type argument.
+pub fn type_tag(type_tag: &str) -> Result<TypeTag, PreludeError> {
+	TypeTag::from_str(type_tag).map_err(|e| PreludeError::Internal(e.into()))
+}
+
+/// `0x1::aptos_account::transfer`, which creates the recipient if it does not exist.
+pub fn transfer(to: AccountAddress, amount: u64) -> Result<TransactionPayload, PreludeError> {
+	entry_function("0x1::aptos_account", "transfer", vec![], vec![bcs_arg(&to)?, bcs_arg(&amount)?])
+}
+
+/// `0x1::primary_fungible_store::transfer` of the fungible asset paired with the coin.
+pub fn fungible_asset_transfer(
+	to: AccountAddress,
+	amount: u64,
+) -> Result<TransactionPayload, PreludeError> {
+	entry_function(
+		"0x1::primary_fungible_store",
+		"transfer",
+		vec![type_tag("0x1::fungible_asset::Metadata")?],
+		vec![
+			// the metadata of the coin's paired fungible asset
+			bcs_arg(&AccountAddress::TEN)?,
+			bcs_arg(&to)?,
+			bcs_arg(&amount)?,
+		],
+	)
+}
+
+/// `0x4::aptos_token::create_collection`, which creates a collection object.
+pub fn create_collection_object(name: &str) -> Result<TransactionPayload, PreludeError> {
+	entry_function(
+		"0x4::aptos_token",
+		"create_collection",
+		vec![],
+		[
+			vec![bcs_arg("collection")?, bcs_arg(&100u64)?, bcs_arg(name)?, bcs_arg(URI)?],
+			// the mutability settings
+			vec![bcs_arg(&true)?; 9],
+			// the royalty
+			vec![bcs_arg(&0u64)?, bcs_arg(&100u64)?],
+		]
+		.concat(),
+	)
+}
+
+/// `0x3::token::create_collection_script`, which writes to the creator's collection tables.
+pub fn create_token_collection(name: &str) -> Result<TransactionPayload, PreludeError> {
+	entry_function(
+		"0x3::token",
+		"create_collection_script",
+		vec![],
+		vec![
+			bcs_arg(name)?,
+			bcs_arg("collection")?,
+			bcs_arg(URI)?,
+			bcs_arg(&u64::MAX)?,
+			bcs_arg(&vec![false, false, false])?,
+		],
+	)
+}
+
+/// `0x1::resource_account::create_resource_account`.
+pub fn create_resource_account(seed: &[u8]) -> Result<TransactionPayload, PreludeError> {
+	entry_function(
+		"0x1::resource_account",
+		"create_resource_account",
+		vec![],
+		vec![bcs_arg(seed)?, bcs_arg(&Vec::<u8>::new())?],
+	)
+}
+
+/// `0x1::code::publish_package_txn` of a package with a single empty module, both named `name`.
+///
+/// The manifest is stored in the package registry as is, so it can be used to grow the registry.
+pub fn publish_empty_package(
+	address: AccountAddress,
+	name: &str,
+	manifest: Vec<u8>,
+) -> Result<TransactionPayload, PreludeError> {
+	let metadata = PackageMetadata {
+		name: name.to_string(),
+		upgrade_policy: UpgradePolicy { policy: UpgradePolicy::COMPATIBLE },
+		upgrade_number: 0,
+		source_digest: String::new(),
+		manifest,
+		modules: vec![ModuleMetadata {
+			name: name.to_string(),
+			source: vec![],
+			source_map: vec![],
+			extension: None,
+		}],
+		deps: vec![],
+		extension: None,
+	};
+	entry_function(
+		"0x1::code",
+		"publish_package_txn",
+		vec![],
+		vec![bcs_arg(&bcs_arg(&metadata)?)?, bcs_arg(&vec![empty_module(address, name)?])?],
+	)
+}
+
+/// Compiles a module with no members, named `name` and published under `address`.
+pub fn empty_module(address: AccountAddress, name: &str) -> Result<Vec<u8>, PreludeError> {
+	use ::aptos_sdk::move_types::account_address::AccountAddress as CompiledAccountAddress;
+	use ::aptos_sdk::move_types::identifier::Identifier as CompiledIdentifier;
+
+	let mut module = move_binary_format::file_format::empty_module();
+	module.version = MODULE_VERSION;
+	module.address_identifiers[0] = CompiledAccountAddress::new(address.into_bytes());
+	module.identifiers[0] =
+		CompiledIdentifier::new(name).map_err(|e| PreludeError::Internal(e.into()))?;
+
+	let mut bytes = Vec::new();
+	module.serialize(&mut bytes).map_err(|e| PreludeError::Internal(e.into()))?;
+	Ok(bytes)
+}
+
+/// Mirror of `0x1::account::RotationProofChallenge`.
+#[derive(Serialize)]
+struct RotationProofChallenge {
+	account_address: AccountAddress,
+	module_name: String,
+	struct_name: String,
+	sequence_number: u64,
+	originator: AccountAddress,
+	current_auth_key: AccountAddress,
+	new_public_key: Vec<u8>,
+}
+
+/// `0x1::account::rotate_authentication_key` from the account's current key to a new key, signed by both.
+///
+/// The proof is for the account's next sequence number, so the payload must be signed next.
+pub fn rotate_authentication_key(
+	account: &LocalAccount,
+	new_key: &AccountKey,
+) -> Result<TransactionPayload, PreludeError> {
+	let challenge = bcs_arg(&RotationProofChallenge {
+		account_address: AccountAddress::ONE,
+		module_name: String::from("account"),
+		struct_name: String::from("RotationProofChallenge"),
+		sequence_number: account.sequence_number(),
+		originator: account.address(),
+		current_auth_key: AccountAddress::from_bytes(account.authentication_key().to_vec())
+			.map_err(|e| PreludeError::Internal(e.into()))?,
+		new_public_key: new_key.public_key().to_bytes().to_vec(),
+	})?;
+	let cap_rotate_key = account.private_key().sign_arbitrary_message(&challenge);
+	let cap_update_table = new_key.private_key().sign_arbitrary_message(&challenge);
+
+	entry_function(
+		"0x1::account",
+		"rotate_authentication_key",
+		vec![],
+		vec![
+			// both keys use the ed25519 scheme
+			bcs_arg(&0u8)?,
+			bcs_arg(&account.public_key().to_bytes().to_vec())?,
+			bcs_arg(&0u8)?,
+			bcs_arg(&new_key.public_key().to_bytes().to_vec())?,
+			bcs_arg(&cap_rotate_key.to_bytes().to_vec())?,
+			bcs_arg(&cap_update_table.to_bytes().to_vec())?,
+		],
+	)
+}
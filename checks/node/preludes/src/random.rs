@@ -0,0 +1,386 @@
+use crate::compose::{Signers, Stage};
+use crate::payloads;
+use mtma_node_test_types::prelude::{Prelude, PreludeError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::future::Future;
+use tracing::{debug, info};
+
+/// The default number of octas each generated account is funded with.
+const DEFAULT_FUNDING: u64 = 100_000_000_000;
+
+/// The largest amount of octas a generated transfer sends.
+const MAX_TRANSFER: u64 = 1_000_000;
+
+/// The kinds of action a [RandomPrelude] can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ActionKind {
+	CoinTransfer,
+	FungibleAssetTransfer,
+	ObjectCreation,
+	ResourceAccountCreation,
+	ModulePublishing,
+	TableWrite,
+	KeyRotation,
+}
+
+impl ActionKind {
+	/// Every kind of action.
+	pub const ALL: [ActionKind; 7] = [
+		ActionKind::CoinTransfer,
+		ActionKind::FungibleAssetTransfer,
+		ActionKind::ObjectCreation,
+		ActionKind::ResourceAccountCreation,
+		ActionKind::ModulePublishing,
+		ActionKind::TableWrite,
+		ActionKind::KeyRotation,
+	];
+}
+
+/// An action taken by a generated account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+	/// Transfers coins to another generated account, by index.
+	CoinTransfer { to: usize, amount: u64 },
+	/// Transfers the fungible asset of the coin to another generated account, by index.
+	FungibleAssetTransfer { to: usize, amount: u64 },
+	/// Creates a collection object.
+	ObjectCreation,
+	/// Creates a resource account.
+	ResourceAccountCreation,
+	/// Publishes a module.
+	ModulePublishing,
+	/// Creates a token collection, which writes to the creator's collection tables.
+	TableWrite,
+	/// Rotates the authentication key of the sender.
+	KeyRotation,
+}
+
+impl Action {
+	/// The kind of the action.
+	pub fn kind(&self) -> ActionKind {
+		match self {
+			Action::CoinTransfer { .. } => ActionKind::CoinTransfer,
+			Action::FungibleAssetTransfer { .. } => ActionKind::FungibleAssetTransfer,
+			Action::ObjectCreation => ActionKind::ObjectCreation,
+			Action::ResourceAccountCreation => ActionKind::ResourceAccountCreation,
+			Action::ModulePublishing => ActionKind::ModulePublishing,
+			Action::TableWrite => ActionKind::TableWrite,
+			Action::KeyRotation => ActionKind::KeyRotation,
+		}
+	}
+}
+
+/// An action taken by a generated account, by index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+	pub sender: usize,
+	pub action: Action,
+}
+
+/// The blocks of steps a [RandomPrelude] signs into a prelude.
+///
+/// Workloads are what gets shrunk, since they can be re-signed after steps are removed, while signed transactions cannot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workload {
+	pub blocks: Vec<Vec<Step>>,
+}
+
+impl Workload {
+	/// The number of steps across the blocks.
+	pub fn len(&self) -> usize {
+		self.blocks.iter().map(Vec::len).sum()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The workloads one removal smaller than this one, first by whole blocks, then by single steps.
+	pub fn shrinks(&self) -> Vec<Workload> {
+		let mut shrinks = Vec::new();
+		for block in 0..self.blocks.len() {
+			let mut blocks = self.blocks.clone();
+			blocks.remove(block);
+			shrinks.push(Workload { blocks });
+		}
+		for block in 0..self.blocks.len() {
+			if self.blocks[block].len() < 2 {
+				// removing the only step is the same as removing the block
+				continue;
+			}
+			for step in 0..self.blocks[block].len() {
+				let mut blocks = self.blocks.clone();
+				blocks[block].remove(step);
+				shrinks.push(Workload { blocks });
+			}
+		}
+		shrinks
+	}
+}
+
+/// A seeded, randomized workload of transactions, for fuzzing the migration.
+///
+/// The same seed and configuration always generate the same workload, and the same signers sign it for the same accounts,
+/// so a seed which leads to a criterion failing reproduces it, and [RandomPrelude::shrink] can reduce it to a minimal workload.
+/// Some actions depend on features which may not be enabled on the local network, e.g. fungible asset transfers,
+/// so the prelude should be run with aborts allowed unless the kinds are restricted.
+pub struct RandomPrelude {
+	/// The seed the workload is generated from.
+	pub seed: u64,
+	/// The number of accounts generated.
+	pub accounts: usize,
+	/// The number of transactions generated, not counting the funding of the accounts.
+	pub transactions: usize,
+	/// The largest number of transactions in a block, which are sized at random up to it.
+	pub max_block_size: usize,
+	/// The kinds of action generated.
+	pub kinds: Vec<ActionKind>,
+	/// The number of octas each generated account is funded with.
+	pub funding: u64,
+}
+
+impl RandomPrelude {
+	pub fn new(seed: u64) -> Self {
+		Self {
+			seed,
+			accounts: 8,
+			transactions: 64,
+			max_block_size: 16,
+			kinds: ActionKind::ALL.to_vec(),
+			funding: DEFAULT_FUNDING,
+		}
+	}
+
+	/// Builder API: sets the number of accounts generated.
+	pub fn with_accounts(mut self, accounts: usize) -> Self {
+		self.accounts = accounts;
+		self
+	}
+
+	/// Builder API: sets the number of transactions generated.
+	pub fn with_transactions(mut self, transactions: usize) -> Self {
+		self.transactions = transactions;
+		self
+	}
+
+	/// Builder API: sets the largest number of transactions in a block.
+	pub fn with_max_block_size(mut self, max_block_size: usize) -> Self {
+		self.max_block_size = max_block_size;
+		self
+	}
+
+	/// Builder API: sets the kinds of action generated.
+	pub fn with_kinds(mut self, kinds: Vec<ActionKind>) -> Self {
+		self.kinds = kinds;
+		self
+	}
+
+	/// Builder API: sets the number of octas each generated account is funded with.
+	pub fn with_funding(mut self, funding: u64) -> Self {
+		self.funding = funding;
+		self
+	}
+
+	/// Generates the workload for the seed.
+	pub fn workload(&self) -> Workload {
+		let mut rng = StdRng::seed_from_u64(self.seed);
+		let mut workload = Workload::default();
+		if self.accounts == 0 || self.kinds.is_empty() {
+			return workload;
+		}
+
+		let mut remaining = self.transactions;
+		while remaining > 0 {
+			let size = rng.gen_range(1..=self.max_block_size.max(1)).min(remaining);
+			let block = (0..size)
+				.map(|_| {
+					let sender = rng.gen_range(0..self.accounts);
+					let action = match self.kinds[rng.gen_range(0..self.kinds.len())] {
+						ActionKind::CoinTransfer => Action::CoinTransfer {
+							to: rng.gen_range(0..self.accounts),
+							amount: rng.gen_range(1..=MAX_TRANSFER),
+						},
+						ActionKind::FungibleAssetTransfer => Action::FungibleAssetTransfer {
+							to: rng.gen_range(0..self.accounts),
+							amount: rng.gen_range(1..=MAX_TRANSFER),
+						},
+						ActionKind::ObjectCreation => Action::ObjectCreation,
+						ActionKind::ResourceAccountCreation => Action::ResourceAccountCreation,
+						ActionKind::ModulePublishing => Action::ModulePublishing,
+						ActionKind::TableWrite => Action::TableWrite,
+						ActionKind::KeyRotation => Action::KeyRotation,
+					};
+					Step { sender, action }
+				})
+				.collect();
+			workload.blocks.push(block);
+			remaining -= size;
+		}
+		workload
+	}
+
+	/// Signs a workload into a prelude, funding the generated accounts in the first block.
+	///
+	/// The accounts only depend on the signers, so a shrunk workload signed by the same signers is signed for the same accounts as the original.
+	pub fn sign(
+		&self,
+		signers: &mut Signers,
+		workload: &Workload,
+	) -> Result<Prelude, PreludeError> {
+		if self.accounts == 0 && !workload.is_empty() {
+			return Err(PreludeError::Internal(
+				"a workload cannot be signed without accounts".into(),
+			));
+		}
+
+		// create and fund the accounts
+		let mut prelude = Prelude::new_empty();
+		let mut accounts = signers.generate_accounts(&mut prelude, self.accounts, self.funding);
+
+		// names must be unique per sender, so they are drawn from a counter
+		let mut counter = 0u64;
+		for block in &workload.blocks {
+			prelude.end_block();
+			for step in block {
+				counter += 1;
+				let name = format!("random_{}_{}", self.seed, counter);
+				let to = |to: usize| accounts[to % accounts.len()].address();
+				let sender = &accounts[step.sender % accounts.len()];
+				let mut rotation = None;
+				let payload = match &step.action {
+					Action::CoinTransfer { to: recipient, amount } => {
+						payloads::transfer(to(*recipient), *amount)
+					}
+					Action::FungibleAssetTransfer { to: recipient, amount } => {
+						payloads::fungible_asset_transfer(to(*recipient), *amount)
+					}
+					Action::ObjectCreation => payloads::create_collection_object(&name),
+					Action::ResourceAccountCreation => {
+						payloads::create_resource_account(name.as_bytes())
+					}
+					Action::ModulePublishing => {
+						payloads::publish_empty_package(sender.address(), &name, vec![])
+					}
+					Action::TableWrite => payloads::create_token_collection(&name),
+					Action::KeyRotation => {
+						let new_key = signers.key();
+						let payload = payloads::rotate_authentication_key(sender, &new_key);
+						rotation = Some(new_key);
+						payload
+					}
+				}?;
+
+				let account = &mut accounts[step.sender % self.accounts];
+				prelude.add_transaction(signers.sign(account, payload));
+				if let Some(new_key) = rotation {
+					// later transactions from the sender are signed by the new key
+					account.rotate_key(new_key);
+				}
+			}
+		}
+
+		Ok(prelude)
+	}
+
+	/// Shrinks a workload for which `fails` holds, returning a smaller workload for which it still holds.
+	///
+	/// Shrinking is greedy: whole blocks are removed first, then single steps, until no single removal still fails.
+	/// Each shrunk workload is signed by fresh `signers`, e.g. as the stages before the workload left them.
+	/// Save the prelude signed from the result with [Prelude::save] to reproduce the failure.
+	pub async fn shrink<S, F, Fut>(
+		&self,
+		workload: Workload,
+		mut signers: S,
+		mut fails: F,
+	) -> Result<Workload, PreludeError>
+	where
+		S: FnMut() -> Signers,
+		F: FnMut(Prelude) -> Fut,
+		Fut: Future<Output = bool>,
+	{
+		let mut workload = workload;
+		'shrinking: loop {
+			for shrink in workload.shrinks() {
+				if fails(self.sign(&mut signers(), &shrink)?).await {
+					debug!("Shrunk workload to {} steps", shrink.len());
+					workload = shrink;
+					continue 'shrinking;
+				}
+			}
+			break;
+		}
+		info!("Shrunk workload to {} steps in {} blocks", workload.len(), workload.blocks.len());
+		Ok(workload)
+	}
+}
+
+impl Stage for RandomPrelude {
+	async fn generate(self, signers: &mut Signers) -> Result<Prelude, PreludeError> {
+		let workload = self.workload();
+		info!(
+			"Generated workload of {} steps in {} blocks from seed {}",
+			workload.len(),
+			workload.blocks.len(),
+			self.seed
+		);
+		self.sign(signers, &workload)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use mtma_node_test_types::criterion::movement_executor::maptos_opt_executor::{
+		aptos_crypto::ed25519::Ed25519PrivateKey, aptos_types::chain_id::ChainId,
+	};
+
+	fn random_prelude(seed: u64) -> RandomPrelude {
+		RandomPrelude::new(seed)
+			.with_accounts(4)
+			.with_transactions(32)
+			.with_max_block_size(5)
+	}
+
+	#[test]
+	fn test_workload_is_seeded() {
+		let workload = random_prelude(7).workload();
+		assert_eq!(workload, random_prelude(7).workload());
+		assert_ne!(workload, random_prelude(8).workload());
+
+		assert_eq!(workload.len(), 32);
+		assert!(workload.blocks.iter().all(|block| !block.is_empty() && block.len() <= 5));
+		assert!(workload.blocks.iter().flatten().all(|step| step.sender < 4));
+	}
+
+	fn signers() -> Signers {
+		let private_key = Ed25519PrivateKey::try_from([7u8; 32].as_slice()).unwrap();
+		Signers::new(private_key, ChainId::test(), [0u8; 32])
+	}
+
+	#[test]
+	fn test_sign_is_reproducible() -> Result<(), anyhow::Error> {
+		let prelude = random_prelude(7);
+		let workload = prelude.workload();
+		assert_eq!(
+			prelude.sign(&mut signers(), &workload)?.blocks(),
+			prelude.sign(&mut signers(), &workload)?.blocks()
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_shrinks_remove_one_step_or_block() {
+		let workload = random_prelude(7).workload();
+		for shrink in workload.shrinks() {
+			let removed = workload.len() - shrink.len();
+			assert!(removed >= 1);
+			// either a single step was removed, or a whole block
+			assert!(
+				removed == 1
+					|| workload.blocks.iter().any(|block| block.len() == removed)
+						&& shrink.blocks.len() + 1 == workload.blocks.len()
+			);
+		}
+	}
+}
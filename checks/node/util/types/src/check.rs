@@ -1,45 +1,144 @@
-use crate::criterion::{Criterionish, MovementNode};
+use crate::criterion::{AsyncCriterionish, MovementAptosNode, MovementNode};
+use crate::evaluation::Evaluation;
 use crate::prelude::Prelude;
+use crate::report::{CheckReport, MigrationReport, StageReport};
 use mtma_node_types::migration::Migrationish;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
 
 /// Errors thrown when working with the [Config].
 #[derive(Debug, thiserror::Error)]
 pub enum CheckError {
 	#[error("check encountered an error while running prelude: {0}")]
-	Prelude(#[source] Box<dyn std::error::Error + Send + Sync>),
+	Prelude(#[source] Box<dyn std::error::Error + Send + Sync>, Box<CheckReport>),
 	#[error("check encountered an error while running migration: {0}")]
-	Migration(#[source] Box<dyn std::error::Error + Send + Sync>),
-	#[error("check encountered an error while satisfying criteria: {0}")]
-	Criteria(#[source] Box<dyn std::error::Error + Send + Sync>),
+	Migration(#[source] Box<dyn std::error::Error + Send + Sync>, Box<CheckReport>),
+	#[error("{} of {} criteria were not satisfied:\n{}", .0.failures().count(), .0.criteria.len(), .0)]
+	Unsatisfied(Box<CheckReport>),
 	#[error("check encountered an internal error: {0}")]
 	Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
+impl CheckError {
+	/// The report of the check as far as it got, with the stage which could not complete reported as errored.
+	///
+	/// Only an internal error, which is thrown before any stage is run, carries no report.
+	pub fn report(&self) -> Option<&CheckReport> {
+		match self {
+			CheckError::Prelude(_, report)
+			| CheckError::Migration(_, report)
+			| CheckError::Unsatisfied(report) => Some(report),
+			CheckError::Internal(_) => None,
+		}
+	}
+}
+
 /// Runs a migration where prelude is executed, the migration is run, and then the criteria are checked.
+///
+/// Every criterion is evaluated, even if an earlier one fails.
+/// Returns a [CheckReport] of the stages and criteria, which is carried by [CheckError::Unsatisfied] if any criterion failed,
+/// or by [CheckError::Prelude] and [CheckError::Migration] with the stage which could not complete reported as errored.
 pub async fn checked_migration(
 	movement_executor: &mut MovementNode,
 	prelude: &Prelude,
 	migration: &impl Migrationish,
-	criteria: Vec<Box<dyn Criterionish + Send + Sync>>,
-) -> Result<(), CheckError> {
+	criteria: Vec<Box<dyn AsyncCriterionish + Send + Sync>>,
+) -> Result<CheckReport, CheckError> {
 	// Run the prelude
-	prelude
-		.run(movement_executor)
-		.await
-		.map_err(|e| CheckError::Prelude(e.into()))?;
+	info!("Running prelude");
+	let start = Instant::now();
+	let prelude_output = match prelude.run(movement_executor).await {
+		Ok(prelude_output) => prelude_output,
+		Err(e) => {
+			let report = CheckReport::prelude_error(e.to_string(), start.elapsed());
+			return Err(CheckError::Prelude(e.into(), Box::new(report)));
+		}
+	};
+	info!(
+		"Ran {} prelude transactions using {} gas",
+		prelude_output.transactions.len(),
+		prelude_output.gas_used()
+	);
+	let prelude_report = StageReport::pass(start.elapsed());
 
-	// Run the migration
-	let movement_aptos_executor = migration
-		.migrate(movement_executor)
-		.await
-		.map_err(|e| CheckError::Migration(e.into()))?;
+	// Run the migration, reading the versions off the migrated nodes
+	info!("Running migration");
+	let start = Instant::now();
+	let migrated = async {
+		let movement_aptos_executor = migration.migrate(movement_executor).await?;
+		let movement_version = movement_executor.latest_ledger_version()?;
+		let movement_aptos_version = movement_aptos_executor.latest_ledger_version()?;
+		Ok::<_, anyhow::Error>((movement_aptos_executor, movement_version, movement_aptos_version))
+	}
+	.await;
+	let (movement_aptos_executor, movement_version, movement_aptos_version) = match migrated {
+		Ok(migrated) => migrated,
+		Err(e) => {
+			let report =
+				CheckReport::migration_error(prelude_report, format!("{:#}", e), start.elapsed());
+			return Err(CheckError::Migration(e.into(), Box::new(report)));
+		}
+	};
+	let migration_report = MigrationReport {
+		stage: StageReport::pass(start.elapsed()),
+		output_path: Some(movement_aptos_executor.db_dir_path().clone()),
+		movement_version: Some(movement_version),
+		movement_aptos_version: Some(movement_aptos_version),
+	};
 
 	// Run the criteria
+	let evaluations =
+		evaluate_criteria(movement_executor, &movement_aptos_executor, criteria).await;
+
+	into_result(CheckReport {
+		prelude: Some(prelude_report),
+		migration: Some(migration_report),
+		criteria: evaluations,
+	})
+}
+
+/// Checks the criteria against existing nodes, e.g. the result of a production migration, without running a prelude or a migration.
+///
+/// Every criterion is evaluated, even if an earlier one fails.
+/// Returns a [CheckReport] of the criteria, which is carried by [CheckError::Unsatisfied] if any criterion failed.
+pub async fn checked_snapshot(
+	movement_executor: &MovementNode,
+	movement_aptos_executor: &MovementAptosNode,
+	criteria: Vec<Box<dyn AsyncCriterionish + Send + Sync>>,
+) -> Result<CheckReport, CheckError> {
+	let evaluations = evaluate_criteria(movement_executor, movement_aptos_executor, criteria).await;
+
+	into_result(CheckReport { prelude: None, migration: None, criteria: evaluations })
+}
+
+/// Evaluates every criterion in turn.
+///
+/// The nodes are shared with the criteria through read-only handles, since a criterion's future owns what it reads.
+async fn evaluate_criteria(
+	movement_executor: &MovementNode,
+	movement_aptos_executor: &MovementAptosNode,
+	criteria: Vec<Box<dyn AsyncCriterionish + Send + Sync>>,
+) -> Vec<Evaluation> {
+	info!("Running criteria");
+	let movement_executor = Arc::new(movement_executor.reader());
+	let movement_aptos_executor = Arc::new(movement_aptos_executor.clone());
+	let mut evaluations = Vec::with_capacity(criteria.len());
 	for criterion in criteria {
-		criterion
-			.satisfies(&movement_executor, &movement_aptos_executor)
-			.map_err(|e| CheckError::Criteria(e.into()))?;
+		let criterion: Arc<dyn AsyncCriterionish + Send + Sync> = Arc::from(criterion);
+		let evaluation = criterion
+			.evaluate(movement_executor.clone(), movement_aptos_executor.clone())
+			.await;
+		info!("Evaluated criterion:\n{}", evaluation);
+		evaluations.push(evaluation);
 	}
+	evaluations
+}
 
-	Ok(())
+/// Fails with [CheckError::Unsatisfied] if the report did not pass.
+fn into_result(report: CheckReport) -> Result<CheckReport, CheckError> {
+	if !report.passed() {
+		return Err(CheckError::Unsatisfied(Box::new(report)));
+	}
+	Ok(report)
 }
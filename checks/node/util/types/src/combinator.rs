@@ -0,0 +1,527 @@
+use crate::criterion::{AsyncCriterionish, CriterionFuture, MovementAptosNode, MovementNode};
+use crate::evaluation::Evaluation;
+use futures::future::join_all;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The future returned by [Evaluatish::evaluate].
+pub type EvaluationFuture = Pin<Box<dyn Future<Output = Evaluation> + Send>>;
+
+/// A node in a tree of criteria, which evaluates to a tree of verdicts rather than a single error.
+///
+/// The tree is evaluated against a movement side `M` and a movement_aptos side `A`,
+/// i.e., the nodes by default, or the migrators the nodes are derived from.
+/// The sides are shared through [Arc]s, so that the future owns what it reads, and its leaves can run on other threads.
+pub trait Evaluatish<M = MovementNode, A = MovementAptosNode>: Send + Sync {
+	/// The name under which the evaluation is reported.
+	fn name(&self) -> String;
+
+	/// Evaluates the tree against the given movement and movement_aptos sides.
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture;
+}
+
+/// A boxed [Evaluatish], as held by the combinators.
+pub type BoxedEvaluatish<M = MovementNode, A = MovementAptosNode> =
+	Box<dyn Evaluatish<M, A> + Send + Sync>;
+
+/// Evaluates a tree and flattens it back into a criterion result.
+fn satisfies_by_evaluation(
+	evaluatish: &impl Evaluatish,
+	movement_executor: &Arc<MovementNode>,
+	movement_aptos_executor: &Arc<MovementAptosNode>,
+) -> CriterionFuture {
+	let evaluation = evaluatish.evaluate(movement_executor, movement_aptos_executor);
+	Box::pin(async move { evaluation.await.into_result() })
+}
+
+impl<M, A, E> Evaluatish<M, A> for Box<E>
+where
+	E: Evaluatish<M, A> + ?Sized,
+{
+	fn name(&self) -> String {
+		(**self).name()
+	}
+
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture {
+		(**self).evaluate(movement, movement_aptos)
+	}
+}
+
+/// A single criterion, as a leaf of the tree.
+pub struct Leaf<T> {
+	criterion: Arc<T>,
+}
+
+impl<T> Leaf<T>
+where
+	T: AsyncCriterionish,
+{
+	pub fn new(criterion: T) -> Self {
+		Self { criterion: Arc::new(criterion) }
+	}
+}
+
+impl<T> Evaluatish for Leaf<T>
+where
+	T: AsyncCriterionish,
+{
+	fn name(&self) -> String {
+		AsyncCriterionish::name(self.criterion.as_ref())
+	}
+
+	fn evaluate(
+		&self,
+		movement_executor: &Arc<MovementNode>,
+		movement_aptos_executor: &Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		AsyncCriterionish::evaluate(
+			self.criterion.clone(),
+			movement_executor.clone(),
+			movement_aptos_executor.clone(),
+		)
+	}
+}
+
+/// Passes if all children pass.
+///
+/// Children are evaluated concurrently, since synchronous criteria run on the blocking thread pool
+/// and asynchronous ones overlap while they await.
+pub struct AllOf<M = MovementNode, A = MovementAptosNode> {
+	children: Vec<BoxedEvaluatish<M, A>>,
+}
+
+impl<M, A> Default for AllOf<M, A> {
+	fn default() -> Self {
+		Self { children: Vec::new() }
+	}
+}
+
+impl<M, A> AllOf<M, A> {
+	/// Builder API: adds a child.
+	pub fn with(mut self, child: impl Evaluatish<M, A> + Send + Sync + 'static) -> Self {
+		self.children.push(Box::new(child));
+		self
+	}
+}
+
+impl AllOf {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: adds a criterion as a child.
+	pub fn with_criterion(self, criterion: impl AsyncCriterionish + Send + Sync + 'static) -> Self {
+		self.with(Leaf::new(criterion))
+	}
+}
+
+impl<M, A> Evaluatish<M, A> for AllOf<M, A> {
+	fn name(&self) -> String {
+		"all-of".to_string()
+	}
+
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture {
+		let name = Evaluatish::<M, A>::name(self);
+		let children: Vec<EvaluationFuture> = self
+			.children
+			.iter()
+			.map(|child| child.evaluate(movement, movement_aptos))
+			.collect();
+		Box::pin(async move {
+			let start = Instant::now();
+			let children = join_all(children).await;
+			Evaluation::all_of(name, children, start.elapsed())
+		})
+	}
+}
+
+impl AsyncCriterionish for AllOf {
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture {
+		satisfies_by_evaluation(&*self, &movement_executor, &movement_aptos_executor)
+	}
+
+	fn name(&self) -> String {
+		Evaluatish::<MovementNode, MovementAptosNode>::name(self)
+	}
+
+	fn evaluate(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		Evaluatish::evaluate(&*self, &movement_executor, &movement_aptos_executor)
+	}
+}
+
+/// Passes if any child passes.
+///
+/// Children are evaluated concurrently, since synchronous criteria run on the blocking thread pool
+/// and asynchronous ones overlap while they await.
+pub struct AnyOf<M = MovementNode, A = MovementAptosNode> {
+	children: Vec<BoxedEvaluatish<M, A>>,
+}
+
+impl<M, A> Default for AnyOf<M, A> {
+	fn default() -> Self {
+		Self { children: Vec::new() }
+	}
+}
+
+impl<M, A> AnyOf<M, A> {
+	/// Builder API: adds a child.
+	pub fn with(mut self, child: impl Evaluatish<M, A> + Send + Sync + 'static) -> Self {
+		self.children.push(Box::new(child));
+		self
+	}
+}
+
+impl AnyOf {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: adds a criterion as a child.
+	pub fn with_criterion(self, criterion: impl AsyncCriterionish + Send + Sync + 'static) -> Self {
+		self.with(Leaf::new(criterion))
+	}
+}
+
+impl<M, A> Evaluatish<M, A> for AnyOf<M, A> {
+	fn name(&self) -> String {
+		"any-of".to_string()
+	}
+
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture {
+		let name = Evaluatish::<M, A>::name(self);
+		let children: Vec<EvaluationFuture> = self
+			.children
+			.iter()
+			.map(|child| child.evaluate(movement, movement_aptos))
+			.collect();
+		Box::pin(async move {
+			let start = Instant::now();
+			let children = join_all(children).await;
+			Evaluation::any_of(name, children, start.elapsed())
+		})
+	}
+}
+
+impl AsyncCriterionish for AnyOf {
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture {
+		satisfies_by_evaluation(&*self, &movement_executor, &movement_aptos_executor)
+	}
+
+	fn name(&self) -> String {
+		Evaluatish::<MovementNode, MovementAptosNode>::name(self)
+	}
+
+	fn evaluate(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		Evaluatish::evaluate(&*self, &movement_executor, &movement_aptos_executor)
+	}
+}
+
+/// Passes if the child fails, i.e. the child is expected to fail.
+pub struct Not<E> {
+	inner: E,
+}
+
+impl<E> Not<E> {
+	pub fn new(inner: E) -> Self {
+		Self { inner }
+	}
+}
+
+impl<T> Not<Leaf<T>>
+where
+	T: AsyncCriterionish,
+{
+	/// Expects a criterion to fail.
+	pub fn criterion(criterion: T) -> Self {
+		Self::new(Leaf::new(criterion))
+	}
+}
+
+impl<M, A, E> Evaluatish<M, A> for Not<E>
+where
+	E: Evaluatish<M, A>,
+{
+	fn name(&self) -> String {
+		format!("not {}", self.inner.name())
+	}
+
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture {
+		let name = Evaluatish::<M, A>::name(self);
+		let child = self.inner.evaluate(movement, movement_aptos);
+		Box::pin(async move { Evaluation::not(name, child.await) })
+	}
+}
+
+impl<E> AsyncCriterionish for Not<E>
+where
+	E: Evaluatish + 'static,
+{
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture {
+		satisfies_by_evaluation(&*self, &movement_executor, &movement_aptos_executor)
+	}
+
+	fn name(&self) -> String {
+		Evaluatish::<MovementNode, MovementAptosNode>::name(self)
+	}
+
+	fn evaluate(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		Evaluatish::evaluate(&*self, &movement_executor, &movement_aptos_executor)
+	}
+}
+
+/// Errors if the child does not finish within the timeout.
+///
+/// Synchronous criteria run on the blocking thread pool, so the timeout fires while they run,
+/// although they keep running until they return.
+pub struct WithTimeout<E> {
+	inner: E,
+	timeout: Duration,
+}
+
+impl<E> WithTimeout<E> {
+	pub fn new(inner: E, timeout: Duration) -> Self {
+		Self { inner, timeout }
+	}
+}
+
+impl<T> WithTimeout<Leaf<T>>
+where
+	T: AsyncCriterionish,
+{
+	/// Bounds a criterion by the timeout.
+	pub fn criterion(criterion: T, timeout: Duration) -> Self {
+		Self::new(Leaf::new(criterion), timeout)
+	}
+}
+
+impl<M, A, E> Evaluatish<M, A> for WithTimeout<E>
+where
+	E: Evaluatish<M, A>,
+{
+	fn name(&self) -> String {
+		self.inner.name()
+	}
+
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture {
+		let name = Evaluatish::<M, A>::name(self);
+		let timeout = self.timeout;
+		let child = self.inner.evaluate(movement, movement_aptos);
+		Box::pin(async move {
+			match tokio::time::timeout(timeout, child).await {
+				Ok(evaluation) => evaluation,
+				Err(_) => Evaluation::timed_out(name, timeout),
+			}
+		})
+	}
+}
+
+impl<E> AsyncCriterionish for WithTimeout<E>
+where
+	E: Evaluatish + 'static,
+{
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture {
+		satisfies_by_evaluation(&*self, &movement_executor, &movement_aptos_executor)
+	}
+
+	fn name(&self) -> String {
+		Evaluatish::<MovementNode, MovementAptosNode>::name(self)
+	}
+
+	fn evaluate(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		Evaluatish::evaluate(&*self, &movement_executor, &movement_aptos_executor)
+	}
+}
+
+/// Reports the child under a given name.
+pub struct Named<E> {
+	name: String,
+	inner: E,
+}
+
+impl<E> Named<E> {
+	pub fn new(name: impl Into<String>, inner: E) -> Self {
+		Self { name: name.into(), inner }
+	}
+}
+
+impl<T> Named<Leaf<T>>
+where
+	T: AsyncCriterionish,
+{
+	/// Names a criterion.
+	pub fn criterion(name: impl Into<String>, criterion: T) -> Self {
+		Self::new(name, Leaf::new(criterion))
+	}
+}
+
+impl<M, A, E> Evaluatish<M, A> for Named<E>
+where
+	E: Evaluatish<M, A>,
+{
+	fn name(&self) -> String {
+		self.name.clone()
+	}
+
+	fn evaluate(&self, movement: &Arc<M>, movement_aptos: &Arc<A>) -> EvaluationFuture {
+		let name = Evaluatish::<M, A>::name(self);
+		let child = self.inner.evaluate(movement, movement_aptos);
+		Box::pin(async move { child.await.with_name(name) })
+	}
+}
+
+impl<E> AsyncCriterionish for Named<E>
+where
+	E: Evaluatish + 'static,
+{
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture {
+		satisfies_by_evaluation(&*self, &movement_executor, &movement_aptos_executor)
+	}
+
+	fn name(&self) -> String {
+		Evaluatish::<MovementNode, MovementAptosNode>::name(self)
+	}
+
+	fn evaluate(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		Evaluatish::evaluate(&*self, &movement_executor, &movement_aptos_executor)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use crate::evaluation::Verdict;
+
+	/// Reaches a verdict after sleeping, over no sides at all.
+	struct Sleep {
+		verdict: Verdict,
+		duration: Duration,
+	}
+
+	impl Sleep {
+		fn new(verdict: Verdict, duration: Duration) -> Self {
+			Self { verdict, duration }
+		}
+	}
+
+	impl Evaluatish<(), ()> for Sleep {
+		fn name(&self) -> String {
+			"sleep".to_string()
+		}
+
+		fn evaluate(&self, _movement: &Arc<()>, _movement_aptos: &Arc<()>) -> EvaluationFuture {
+			let verdict = self.verdict;
+			let duration = self.duration;
+			Box::pin(async move {
+				tokio::time::sleep(duration).await;
+				Evaluation::leaf("sleep", verdict, None, duration)
+			})
+		}
+	}
+
+	async fn evaluate(evaluatish: &impl Evaluatish<(), ()>) -> Evaluation {
+		evaluatish.evaluate(&Arc::new(()), &Arc::new(())).await
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_all_of_is_concurrent() -> Result<(), anyhow::Error> {
+		let all_of = AllOf::<(), ()>::default()
+			.with(Sleep::new(Verdict::Pass, Duration::from_millis(500)))
+			.with(Sleep::new(Verdict::Pass, Duration::from_millis(500)))
+			.with(Sleep::new(Verdict::Fail, Duration::from_millis(500)));
+
+		let start = Instant::now();
+		let evaluation = evaluate(&all_of).await;
+		assert!(start.elapsed() < Duration::from_millis(1_000), "{:?}", start.elapsed());
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		assert_eq!(evaluation.children.len(), 3);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_any_of_is_concurrent() -> Result<(), anyhow::Error> {
+		let any_of = AnyOf::<(), ()>::default()
+			.with(Sleep::new(Verdict::Fail, Duration::from_millis(500)))
+			.with(Sleep::new(Verdict::Pass, Duration::from_millis(500)));
+
+		let start = Instant::now();
+		let evaluation = evaluate(&any_of).await;
+		assert!(start.elapsed() < Duration::from_millis(1_000), "{:?}", start.elapsed());
+		assert_eq!(evaluation.verdict, Verdict::Pass, "{}", evaluation);
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_not() -> Result<(), anyhow::Error> {
+		let evaluation = evaluate(&Not::new(Sleep::new(Verdict::Fail, Duration::ZERO))).await;
+		assert_eq!(evaluation.verdict, Verdict::Pass, "{}", evaluation);
+		assert_eq!(evaluation.name, "not sleep");
+		Ok(())
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_with_timeout() -> Result<(), anyhow::Error> {
+		// the child would pass, but not in time
+		let with_timeout = WithTimeout::new(
+			Sleep::new(Verdict::Pass, Duration::from_secs(60)),
+			Duration::from_millis(100),
+		);
+		let start = Instant::now();
+		let evaluation = evaluate(&with_timeout).await;
+		assert!(start.elapsed() < Duration::from_secs(10), "{:?}", start.elapsed());
+		assert_eq!(evaluation.verdict, Verdict::Error, "{}", evaluation);
+		assert!(evaluation.to_string().contains("timed out"), "{}", evaluation);
+
+		// a child which finishes in time is reported as is
+		let with_timeout =
+			WithTimeout::new(Sleep::new(Verdict::Fail, Duration::ZERO), Duration::from_secs(60));
+		let evaluation = evaluate(&with_timeout).await;
+		assert_eq!(evaluation.verdict, Verdict::Fail, "{}", evaluation);
+		Ok(())
+	}
+}
@@ -1,8 +1,14 @@
 pub use mtma_node_types::executor::movement_aptos_executor;
 pub use mtma_node_types::executor::movement_executor;
 
+use crate::combinator::EvaluationFuture;
+use crate::evaluation::{short_type_name, Evaluation};
 pub use mtma_node_types::executor::movement_aptos_executor::MovementAptosNode;
 pub use mtma_node_types::executor::movement_executor::MovementNode;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Errors thrown when working with the [Config].
 #[derive(Debug, thiserror::Error)]
@@ -22,6 +28,66 @@ pub trait Criterionish {
 	) -> Result<(), CriterionError>;
 }
 
+/// The future returned by [AsyncCriterionish::satisfies].
+pub type CriterionFuture = Pin<Box<dyn Future<Output = Result<(), CriterionError>> + Send>>;
+
+/// A criterion which may await, e.g. on a REST call, a blocking task, or a timeout.
+///
+/// Every [Criterionish] is also an [AsyncCriterionish], which runs on the blocking thread pool, so criteria which don't need to await can stay synchronous.
+/// The criterion and the nodes are shared through [Arc]s, so that the boxed future owns what it reads, and can be spawned or timed out.
+pub trait AsyncCriterionish: Send + Sync + 'static {
+	/// Whether the criterion is satisfied by the given movement and movement_aptos executors.
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture;
+
+	/// The name under which the criterion is reported.
+	fn name(&self) -> String {
+		short_type_name::<Self>()
+	}
+
+	/// Evaluates the criterion, timing it.
+	///
+	/// Combinators override this to report their whole tree.
+	fn evaluate(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> EvaluationFuture {
+		Box::pin(async move {
+			let start = Instant::now();
+			let name = self.name();
+			let result = self.satisfies(movement_executor, movement_aptos_executor).await;
+			Evaluation::from_result(name, result, start.elapsed())
+		})
+	}
+}
+
+/// Runs a synchronous criterion on the blocking thread pool.
+///
+/// A criterion may scan the whole DB, so it must neither hold up the async workers nor the criteria evaluated alongside it.
+/// A criterion which is timed out keeps running on its blocking thread until it returns, but its verdict is dropped.
+impl<T> AsyncCriterionish for T
+where
+	T: Criterionish + Send + Sync + 'static,
+{
+	fn satisfies(
+		self: Arc<Self>,
+		movement_executor: Arc<MovementNode>,
+		movement_aptos_executor: Arc<MovementAptosNode>,
+	) -> CriterionFuture {
+		Box::pin(async move {
+			tokio::task::spawn_blocking(move || {
+				Criterionish::satisfies(&*self, &movement_executor, &movement_aptos_executor)
+			})
+			.await
+			.map_err(|e| CriterionError::Internal(e.into()))?
+		})
+	}
+}
+
 /// The criterion type simply
 pub struct Criterion<T>(T)
 where
@@ -0,0 +1,347 @@
+use crate::state::{AccountAddress, StateKey, StateKeyKind, StructTag};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// The name of the parameter criteria take a [DivergenceSpec] under.
+pub const DIVERGENCES: &str = "divergences";
+
+/// Errors thrown when working with a [DivergenceSpec].
+#[derive(Debug, thiserror::Error)]
+pub enum DivergenceError {
+	#[error("failed to read divergence spec {0:?}: {1}")]
+	Read(PathBuf, #[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// How an item is expected to differ on Movement Aptos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Change {
+	/// The item is only on Movement Aptos.
+	#[serde(rename = "expected-added")]
+	Added,
+	/// The item is only on Movement.
+	#[serde(rename = "expected-removed")]
+	Removed,
+	/// The item is on both sides, but differs.
+	#[serde(rename = "expected-changed")]
+	Changed,
+}
+
+impl fmt::Display for Change {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Change::Added => write!(f, "expected-added"),
+			Change::Removed => write!(f, "expected-removed"),
+			Change::Changed => write!(f, "expected-changed"),
+		}
+	}
+}
+
+/// What a divergence applies to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Item {
+	/// State keys matching a glob pattern, against the key as criteria print it, e.g. `*0x1::coin*`.
+	Key(String),
+	/// Everything stored under an account.
+	Account(AccountAddress),
+	/// Resources whose type matches a glob pattern, e.g. `0x1::coin::CoinStore<*>`.
+	Resource(String),
+	/// A feature flag.
+	Feature(u64),
+}
+
+/// A divergence a migration is expected to introduce.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+	/// What diverges.
+	#[serde(flatten)]
+	pub item: Item,
+	/// How it diverges.
+	pub expectation: Change,
+	/// Why it diverges.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub reason: Option<String>,
+}
+
+/// An item a criterion found diverging, described by whatever the criterion knows about it.
+#[derive(Debug, Clone, Default)]
+pub struct Subject {
+	/// The state key, as criteria print it.
+	key: Option<String>,
+	/// The account the item is stored under.
+	account: Option<AccountAddress>,
+	/// The resource type.
+	resource: Option<StructTag>,
+	/// The feature flag.
+	feature: Option<u64>,
+}
+
+impl Subject {
+	/// Describes a state key, along with the account and resource type it refers to.
+	pub fn key(key: &StateKey) -> Self {
+		let subject = Self { key: Some(format!("{:?}", key)), ..Self::default() };
+		match StateKeyKind::of(key) {
+			StateKeyKind::Module(module_id) => subject.with_account(*module_id.address()),
+			StateKeyKind::Resource(address, struct_tag)
+			| StateKeyKind::ResourceGroup(address, struct_tag) => {
+				subject.with_account(address).with_resource(struct_tag)
+			}
+			StateKeyKind::Other => subject,
+		}
+	}
+
+	/// Describes an account.
+	pub fn account(account: AccountAddress) -> Self {
+		Self::default().with_account(account)
+	}
+
+	/// Describes a resource type, e.g. an on-chain config.
+	pub fn resource(resource: StructTag) -> Self {
+		Self::default().with_resource(resource)
+	}
+
+	/// Describes a feature flag.
+	pub fn feature(feature: u64) -> Self {
+		Self { feature: Some(feature), ..Self::default() }
+	}
+
+	/// Builder API: sets the account.
+	pub fn with_account(mut self, account: AccountAddress) -> Self {
+		self.account = Some(account);
+		self
+	}
+
+	/// Builder API: sets the resource type.
+	pub fn with_resource(mut self, resource: StructTag) -> Self {
+		self.resource = Some(resource);
+		self
+	}
+}
+
+impl Item {
+	/// Whether the item covers the subject.
+	pub fn matches(&self, subject: &Subject) -> bool {
+		match self {
+			Item::Key(pattern) => subject.key.as_deref().is_some_and(|key| glob(pattern, key)),
+			Item::Account(account) => subject.account == Some(*account),
+			Item::Resource(pattern) => subject
+				.resource
+				.as_ref()
+				.is_some_and(|resource| glob(pattern, &resource.to_string())),
+			Item::Feature(feature) => subject.feature == Some(*feature),
+		}
+	}
+}
+
+/// Matches a glob pattern, treating a malformed pattern as a literal.
+fn glob(pattern: &str, text: &str) -> bool {
+	match glob::Pattern::new(pattern) {
+		Ok(pattern) => pattern.matches(text),
+		Err(_) => pattern == text,
+	}
+}
+
+/// The divergences a migration is expected to introduce, which criteria treat as explained rather than unsatisfied.
+///
+/// As a criterion parameter, the spec is either inline or a path to a YAML or JSON file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "Source")]
+pub struct DivergenceSpec {
+	pub divergences: Vec<Divergence>,
+}
+
+/// Where a [DivergenceSpec] is read from.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Source {
+	Path(PathBuf),
+	Inline { divergences: Vec<Divergence> },
+}
+
+impl TryFrom<Source> for DivergenceSpec {
+	type Error = DivergenceError;
+
+	fn try_from(source: Source) -> Result<Self, Self::Error> {
+		match source {
+			Source::Path(path) => Self::load(&path),
+			Source::Inline { divergences } => Ok(Self { divergences }),
+		}
+	}
+}
+
+/// The contents of a spec file.
+#[derive(Deserialize)]
+struct SpecFile {
+	#[serde(default)]
+	divergences: Vec<Divergence>,
+}
+
+impl DivergenceSpec {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: adds a divergence.
+	pub fn with(mut self, item: Item, expectation: Change) -> Self {
+		self.divergences.push(Divergence { item, expectation, reason: None });
+		self
+	}
+
+	/// Loads a spec from a YAML or JSON file, by extension.
+	pub fn load(path: &Path) -> Result<Self, DivergenceError> {
+		let contents = std::fs::read_to_string(path)
+			.map_err(|e| DivergenceError::Read(path.to_path_buf(), e.into()))?;
+		let file: SpecFile = match path.extension().and_then(|extension| extension.to_str()) {
+			Some("json") => serde_json::from_str(&contents)
+				.map_err(|e| DivergenceError::Read(path.to_path_buf(), e.into()))?,
+			_ => serde_yaml::from_str(&contents)
+				.map_err(|e| DivergenceError::Read(path.to_path_buf(), e.into()))?,
+		};
+		Ok(Self { divergences: file.divergences })
+	}
+
+	/// The divergence which explains the subject changing as observed, if any.
+	pub fn explains(&self, subject: &Subject, change: Change) -> Option<&Divergence> {
+		self.divergences
+			.iter()
+			.find(|divergence| divergence.expectation == change && divergence.item.matches(subject))
+	}
+
+	/// Whether the subject changing as observed is explained, logging the explanation.
+	pub fn is_explained(&self, subject: &Subject, change: Change) -> bool {
+		match self.explains(subject, change) {
+			Some(divergence) => {
+				info!(
+					"{:?} is {} by {:?}: {}",
+					subject,
+					change,
+					divergence.item,
+					divergence.reason.as_deref().unwrap_or("no reason given")
+				);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Records an error for the subject changing as observed, unless it is explained.
+	pub fn record(
+		&self,
+		errors: &mut Vec<String>,
+		subject: &Subject,
+		change: Change,
+		error: String,
+	) {
+		if !self.is_explained(subject, change) {
+			errors.push(error);
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use serde_json::json;
+	use std::str::FromStr;
+	use tempfile::TempDir;
+
+	#[test]
+	fn test_explains() -> Result<(), anyhow::Error> {
+		let spec: DivergenceSpec = serde_yaml::from_str(
+			r#"
+divergences:
+  - account: "0xa550c18"
+    expectation: expected-changed
+    reason: governance accounts are reset
+  - resource: "0x1::coin::CoinStore<*>"
+    expectation: expected-removed
+  - feature: 73
+    expectation: expected-changed
+"#,
+		)?;
+
+		let governance = AccountAddress::from_str("0xa550c18")?;
+		assert!(spec.explains(&Subject::account(governance), Change::Changed).is_some());
+		// the expectation must match as well
+		assert!(spec.explains(&Subject::account(governance), Change::Removed).is_none());
+
+		let coin_store = StructTag::from_str("0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>")?;
+		let subject = Subject::account(AccountAddress::ONE).with_resource(coin_store);
+		assert!(spec.explains(&subject, Change::Removed).is_some());
+		assert!(spec.explains(&Subject::account(AccountAddress::ONE), Change::Removed).is_none());
+
+		assert!(spec.explains(&Subject::feature(73), Change::Changed).is_some());
+		assert!(spec.explains(&Subject::feature(74), Change::Changed).is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn test_deserialize_inline() -> Result<(), anyhow::Error> {
+		let spec: DivergenceSpec = serde_json::from_value(json!({
+			"divergences": [{"feature": 73, "expectation": "expected-changed"}]
+		}))?;
+		assert_eq!(spec.divergences.len(), 1);
+		assert!(spec.explains(&Subject::feature(73), Change::Changed).is_some());
+
+		// an inline spec must list its divergences
+		assert!(serde_json::from_value::<DivergenceSpec>(json!({})).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_deserialize_path() -> Result<(), anyhow::Error> {
+		let temp_dir = TempDir::new()?;
+		let path = temp_dir.path().join("divergences.yaml");
+		std::fs::write(
+			&path,
+			"divergences:\n  - feature: 73\n    expectation: expected-changed\n",
+		)?;
+
+		let spec: DivergenceSpec = serde_json::from_value(json!(path))?;
+		assert!(spec.explains(&Subject::feature(73), Change::Changed).is_some());
+
+		// a path which cannot be read fails to deserialize
+		let missing = temp_dir.path().join("missing.yaml");
+		assert!(serde_json::from_value::<DivergenceSpec>(json!(missing)).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_load() -> Result<(), anyhow::Error> {
+		let temp_dir = TempDir::new()?;
+
+		// JSON by extension
+		let path = temp_dir.path().join("divergences.json");
+		std::fs::write(
+			&path,
+			r#"{"divergences": [{"account": "0x1", "expectation": "expected-added"}]}"#,
+		)?;
+		let spec = DivergenceSpec::load(&path)?;
+		assert!(spec.explains(&Subject::account(AccountAddress::ONE), Change::Added).is_some());
+
+		// YAML otherwise
+		let path = temp_dir.path().join("divergences.yml");
+		std::fs::write(
+			&path,
+			"divergences:\n  - key: \"*0x1::coin*\"\n    expectation: expected-removed\n    reason: coins are migrated to fungible assets\n",
+		)?;
+		let spec = DivergenceSpec::load(&path)?;
+		assert_eq!(
+			spec.divergences[0].reason.as_deref(),
+			Some("coins are migrated to fungible assets")
+		);
+
+		// a file without divergences loads an empty spec
+		let path = temp_dir.path().join("empty.yaml");
+		std::fs::write(&path, "{}")?;
+		assert!(DivergenceSpec::load(&path)?.divergences.is_empty());
+
+		// malformed files are rejected, e.g. YAML read as JSON
+		let path = temp_dir.path().join("malformed.json");
+		std::fs::write(&path, "divergences: []")?;
+		assert!(matches!(DivergenceSpec::load(&path), Err(DivergenceError::Read(_, _))));
+		Ok(())
+	}
+}
@@ -0,0 +1,307 @@
+use crate::criterion::CriterionError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// The verdict of evaluating a criterion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+	/// The criterion was satisfied.
+	Pass,
+	/// The criterion was not satisfied.
+	Fail,
+	/// The criterion could not be checked.
+	Error,
+}
+
+impl fmt::Display for Verdict {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Verdict::Pass => write!(f, "pass"),
+			Verdict::Fail => write!(f, "fail"),
+			Verdict::Error => write!(f, "error"),
+		}
+	}
+}
+
+/// The result of evaluating a tree of criteria.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evaluation {
+	/// The name of the evaluated criterion or combinator.
+	pub name: String,
+	/// The verdict.
+	pub verdict: Verdict,
+	/// Why the verdict was reached, if not obvious.
+	pub message: Option<String>,
+	/// How long the evaluation took.
+	pub duration: Duration,
+	/// The evaluations of the children, for combinators.
+	pub children: Vec<Evaluation>,
+}
+
+impl Evaluation {
+	/// Forms the evaluation of a single criterion.
+	pub fn leaf(
+		name: impl Into<String>,
+		verdict: Verdict,
+		message: Option<String>,
+		duration: Duration,
+	) -> Self {
+		Self { name: name.into(), verdict, message, duration, children: Vec::new() }
+	}
+
+	/// Forms the evaluation of a combinator over its children.
+	pub fn branch(
+		name: impl Into<String>,
+		verdict: Verdict,
+		message: Option<String>,
+		duration: Duration,
+		children: Vec<Evaluation>,
+	) -> Self {
+		Self { name: name.into(), verdict, message, duration, children }
+	}
+
+	/// Forms the evaluation of a criterion from its result.
+	pub fn from_result(
+		name: impl Into<String>,
+		result: Result<(), CriterionError>,
+		duration: Duration,
+	) -> Self {
+		match result {
+			Ok(()) => Self::leaf(name, Verdict::Pass, None, duration),
+			Err(CriterionError::Unsatisfied(e)) => {
+				Self::leaf(name, Verdict::Fail, Some(e.to_string()), duration)
+			}
+			Err(CriterionError::Internal(e)) => {
+				Self::leaf(name, Verdict::Error, Some(e.to_string()), duration)
+			}
+		}
+	}
+
+	/// Forms the evaluation of a criterion which did not finish in time.
+	pub fn timed_out(name: impl Into<String>, timeout: Duration) -> Self {
+		Self::leaf(name, Verdict::Error, Some(format!("timed out after {:?}", timeout)), timeout)
+	}
+
+	/// Passes if all children pass.
+	///
+	/// A failing child fails the branch, otherwise an erroring child errors it.
+	pub fn all_of(name: impl Into<String>, children: Vec<Evaluation>, duration: Duration) -> Self {
+		let branch = Self::branch(name, Verdict::Pass, None, duration, children);
+		let (failed, errored) = (branch.count(Verdict::Fail), branch.count(Verdict::Error));
+		let verdict = if failed > 0 {
+			Verdict::Fail
+		} else if errored > 0 {
+			Verdict::Error
+		} else {
+			Verdict::Pass
+		};
+		branch.with_summary(verdict)
+	}
+
+	/// Passes if any child passes.
+	///
+	/// Otherwise an erroring child errors the branch, as it may have passed.
+	pub fn any_of(name: impl Into<String>, children: Vec<Evaluation>, duration: Duration) -> Self {
+		let branch = Self::branch(name, Verdict::Pass, None, duration, children);
+		let (passed, errored) = (branch.count(Verdict::Pass), branch.count(Verdict::Error));
+		let verdict = if passed > 0 {
+			Verdict::Pass
+		} else if errored > 0 {
+			Verdict::Error
+		} else {
+			Verdict::Fail
+		};
+		branch.with_summary(verdict)
+	}
+
+	/// Inverts the verdict of a child which is expected to fail.
+	///
+	/// Errors are not inverted.
+	pub fn not(name: impl Into<String>, child: Evaluation) -> Self {
+		let (verdict, message) = match child.verdict {
+			Verdict::Pass => {
+				(Verdict::Fail, Some(format!("{} was expected to fail, but passed", child.name)))
+			}
+			Verdict::Fail => (Verdict::Pass, None),
+			Verdict::Error => (Verdict::Error, None),
+		};
+		Self::branch(name, verdict, message, child.duration, vec![child])
+	}
+
+	/// Sets the verdict of a branch and summarizes its children unless it passed.
+	fn with_summary(mut self, verdict: Verdict) -> Self {
+		self.verdict = verdict;
+		if verdict != Verdict::Pass {
+			self.message = Some(format!(
+				"{} of {} failed, {} errored",
+				self.count(Verdict::Fail),
+				self.children.len(),
+				self.count(Verdict::Error)
+			));
+		}
+		self
+	}
+
+	/// Builder API: sets the name.
+	pub fn with_name(mut self, name: impl Into<String>) -> Self {
+		self.name = name.into();
+		self
+	}
+
+	/// Whether the evaluation passed.
+	pub fn passed(&self) -> bool {
+		self.verdict == Verdict::Pass
+	}
+
+	/// Converts the evaluation into the result of a single criterion.
+	///
+	/// The rendered tree is kept as the error, so no detail is lost.
+	pub fn into_result(self) -> Result<(), CriterionError> {
+		match self.verdict {
+			Verdict::Pass => Ok(()),
+			Verdict::Fail => Err(CriterionError::Unsatisfied(self.to_string().into())),
+			Verdict::Error => Err(CriterionError::Internal(self.to_string().into())),
+		}
+	}
+
+	/// Counts the children with the given verdict.
+	pub fn count(&self, verdict: Verdict) -> usize {
+		self.children.iter().filter(|child| child.verdict == verdict).count()
+	}
+
+	/// Visits every evaluation in the tree, depth first, along with its depth.
+	pub fn walk<'a>(&'a self, visit: &mut impl FnMut(usize, &'a Evaluation)) {
+		self.walk_at(0, visit);
+	}
+
+	fn walk_at<'a>(&'a self, depth: usize, visit: &mut impl FnMut(usize, &'a Evaluation)) {
+		visit(depth, self);
+		for child in &self.children {
+			child.walk_at(depth + 1, visit);
+		}
+	}
+}
+
+impl fmt::Display for Evaluation {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut result = Ok(());
+		self.walk(&mut |depth, evaluation| {
+			if result.is_err() {
+				return;
+			}
+			let indent = "  ".repeat(depth);
+			result = match &evaluation.message {
+				Some(message) => writeln!(
+					f,
+					"{}[{}] {} ({:?}): {}",
+					indent,
+					evaluation.verdict,
+					evaluation.name,
+					evaluation.duration,
+					message.replace('\n', &format!("\n{}  ", indent))
+				),
+				None => writeln!(
+					f,
+					"{}[{}] {} ({:?})",
+					indent, evaluation.verdict, evaluation.name, evaluation.duration
+				),
+			};
+		});
+		result
+	}
+}
+
+/// The name of a type without its module path, for naming criteria.
+pub fn short_type_name<T: ?Sized>() -> String {
+	let name = std::any::type_name::<T>();
+	let base = name.split('<').next().unwrap_or(name);
+	let short = base.rsplit("::").next().unwrap_or(base);
+	format!("{}{}", short, &name[base.len()..])
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	fn leaf(name: &str, verdict: Verdict) -> Evaluation {
+		Evaluation::leaf(name, verdict, None, Duration::ZERO)
+	}
+
+	#[test]
+	fn test_all_of() {
+		let all_of = Evaluation::all_of(
+			"all-of",
+			vec![leaf("a", Verdict::Pass), leaf("b", Verdict::Pass)],
+			Duration::ZERO,
+		);
+		assert_eq!(all_of.verdict, Verdict::Pass);
+		assert_eq!(all_of.message, None);
+
+		// an erroring child errors the branch
+		let all_of = Evaluation::all_of(
+			"all-of",
+			vec![leaf("a", Verdict::Pass), leaf("b", Verdict::Error)],
+			Duration::ZERO,
+		);
+		assert_eq!(all_of.verdict, Verdict::Error);
+
+		// but a failing child takes precedence
+		let all_of = Evaluation::all_of(
+			"all-of",
+			vec![leaf("a", Verdict::Error), leaf("b", Verdict::Fail), leaf("c", Verdict::Pass)],
+			Duration::ZERO,
+		);
+		assert_eq!(all_of.verdict, Verdict::Fail);
+		assert_eq!(all_of.message.as_deref(), Some("1 of 3 failed, 1 errored"));
+		assert_eq!(all_of.children.len(), 3);
+
+		// an empty branch passes
+		assert_eq!(Evaluation::all_of("all-of", vec![], Duration::ZERO).verdict, Verdict::Pass);
+	}
+
+	#[test]
+	fn test_any_of() {
+		let any_of = Evaluation::any_of(
+			"any-of",
+			vec![leaf("a", Verdict::Fail), leaf("b", Verdict::Error), leaf("c", Verdict::Pass)],
+			Duration::ZERO,
+		);
+		assert_eq!(any_of.verdict, Verdict::Pass);
+		assert_eq!(any_of.message, None);
+
+		// an erroring child may have passed, so it errors the branch
+		let any_of = Evaluation::any_of(
+			"any-of",
+			vec![leaf("a", Verdict::Fail), leaf("b", Verdict::Error)],
+			Duration::ZERO,
+		);
+		assert_eq!(any_of.verdict, Verdict::Error);
+		assert_eq!(any_of.message.as_deref(), Some("1 of 2 failed, 1 errored"));
+
+		let any_of = Evaluation::any_of(
+			"any-of",
+			vec![leaf("a", Verdict::Fail), leaf("b", Verdict::Fail)],
+			Duration::ZERO,
+		);
+		assert_eq!(any_of.verdict, Verdict::Fail);
+
+		// an empty branch fails
+		assert_eq!(Evaluation::any_of("any-of", vec![], Duration::ZERO).verdict, Verdict::Fail);
+	}
+
+	#[test]
+	fn test_not() {
+		let not = Evaluation::not("not a", leaf("a", Verdict::Fail));
+		assert_eq!(not.verdict, Verdict::Pass);
+		assert_eq!(not.children.len(), 1);
+
+		let not = Evaluation::not("not a", leaf("a", Verdict::Pass));
+		assert_eq!(not.verdict, Verdict::Fail);
+		assert_eq!(not.message.as_deref(), Some("a was expected to fail, but passed"));
+
+		// errors are not inverted
+		let not = Evaluation::not("not a", leaf("a", Verdict::Error));
+		assert_eq!(not.verdict, Verdict::Error);
+	}
+}
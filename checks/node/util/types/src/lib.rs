@@ -1,3 +1,10 @@
 pub mod check;
+pub mod combinator;
 pub mod criterion;
+pub mod divergence;
+pub mod evaluation;
 pub mod prelude;
+pub mod registry;
+pub mod report;
+pub mod state;
+pub mod versions;
@@ -1,5 +1,12 @@
+pub mod format;
+pub mod output;
+
 use crate::criterion::MovementNode;
-use mtma_types::movement::aptos_crypto::HashValue;
+use crate::state::resources::{BlockResource, Configuration};
+use crate::state::{to_movement_state_key, StateKey};
+use anyhow::Context;
+use mtma_types::movement::aptos_crypto::{hash::CryptoHash, HashValue};
+use mtma_types::movement::aptos_types::state_store::TStateView;
 use mtma_types::movement::aptos_types::{
 	block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
 	block_metadata::BlockMetadata,
@@ -7,21 +14,38 @@ use mtma_types::movement::aptos_types::{
 	transaction::SignedTransaction,
 	transaction::Transaction,
 };
+use std::collections::HashMap;
 use std::future::Future;
+use std::path::PathBuf;
+
+pub use format::PreludeFormat;
+pub use output::{Outcome, PreludeOutput, TransactionOutput};
 
 const DEFAULT_INCREMENT_MICROS: u64 = 1_000_000;
 const DEFAULT_EPOCH: u64 = 0;
 const DEFAULT_INCREMENT_EPOCH_EACH: u64 = 100;
 const DEFAULT_ROUND: u64 = 0;
+const DEFAULT_START_TIMESTAMP_MICROS: u64 = 1_000_000;
+/// The Aptos default epoch interval, two hours.
+const DEFAULT_EPOCH_INTERVAL_MICROS: u64 = 7_200_000_000;
+const DEFAULT_SEED: u64 = 0;
 
 /// Errors thrown when working with the [Config].
 #[derive(Debug, thiserror::Error)]
 pub enum PreludeError {
 	#[error("prelude encountered an internal error: {0}")]
 	Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
+	#[error("prelude transactions failed:\n{0}")]
+	Failed(String, Box<PreludeOutput>),
+	#[error("failed to read or write prelude file {0:?}: {1}")]
+	File(PathBuf, #[source] Box<dyn std::error::Error + Send + Sync>),
+	#[error("failed to encode or decode prelude: {0}")]
+	Format(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
 /// A prelude is several blocks of transactions represented as Vec<Vec<SignedTransaction>>.
+///
+/// A prelude can be saved and loaded, see [PreludeFormat], so a workload can be captured once and replayed against every migration.
 #[derive(Debug, Clone)]
 pub struct Prelude(Vec<Vec<SignedTransaction>>);
 
@@ -55,94 +79,558 @@ impl Prelude {
 		Self(transactions.into_iter().map(|t| t.into_iter().collect()).collect())
 	}
 
-	/// Gets executable blocks from the prelude.
-	///
-	/// # Parameters
-	/// * `executor` - The movement executor to use
-	/// * `epoch` - The epoch to start with
-	/// * `increment_epoch_each` - The number of blocks before incrementing the epoch
-	/// * `round` - The round to start with
-	/// * `current_timestamp_micros` - The current timestamp in microseconds
-	/// * `timestamp_increment_micros` - The number of microseconds to increment the timestamp by
+	/// The blocks of transactions.
+	pub fn blocks(&self) -> &[Vec<SignedTransaction>] {
+		&self.0
+	}
+
+	/// Whether the prelude has no transactions.
+	pub fn is_empty(&self) -> bool {
+		self.0.iter().all(Vec::is_empty)
+	}
+
+	/// Appends the blocks of another prelude after the blocks of this one.
+	pub fn extend(&mut self, other: Prelude) -> &mut Self {
+		self.0.extend(other.0);
+
+		self
+	}
+
+	/// Concatenates preludes, running all the blocks of each before the next.
+	pub fn concat(preludes: impl IntoIterator<Item = Prelude>) -> Self {
+		Self(preludes.into_iter().flat_map(|prelude| prelude.0).collect())
+	}
+
+	/// Interleaves preludes block by block, taking the next block of each in turn until all are exhausted.
 	///
-	/// TODO: these parameters could be better as a builder struct or something.
+	/// The blocks of each prelude keep their order, but preludes which share a sender must not be interleaved,
+	/// as the sequence numbers of the sender would no longer be submitted in order.
+	pub fn interleave(preludes: impl IntoIterator<Item = Prelude>) -> Self {
+		let mut iters: Vec<_> = preludes.into_iter().map(|prelude| prelude.0.into_iter()).collect();
+		let mut blocks = Vec::new();
+		loop {
+			let round: Vec<_> = iters.iter_mut().filter_map(Iterator::next).collect();
+			if round.is_empty() {
+				break;
+			}
+			blocks.extend(round);
+		}
+		Self(blocks)
+	}
+
+	/// Gets executable blocks from the prelude, with block metadata as planned by the config.
 	pub fn get_executable_blocks(
 		&self,
 		executor: &MovementNode,
-		mut epoch: u64,
-		increment_epoch_each: u64,
-		mut round: u64,
-		mut current_timestamp_micros: u64,
-		timestamp_increment_micros: u64,
+		config: &PreludeRunConfig,
 	) -> Result<Vec<ExecutableBlock>, PreludeError> {
+		let author = executor
+			.opt_executor()
+			.map_err(|e| PreludeError::Internal(e.into()))?
+			.signer
+			.author();
 		let mut executable_blocks = Vec::new();
 
-		for signed_transactions in &self.0 {
-			// block id is always random, we're the proposer
-			let block_id = HashValue::random();
-
+		for planned in config.plan(self.0.len()) {
 			let block_metadata = Transaction::BlockMetadata(BlockMetadata::new(
-				block_id.clone(),
-				epoch,
-				round,
-				executor.opt_executor().signer.author(),
+				planned.id,
+				planned.epoch,
+				planned.round,
+				author,
 				vec![],
 				vec![],
-				current_timestamp_micros,
+				planned.timestamp_micros,
 			));
 
 			let mut transactions = vec![block_metadata];
-			transactions.extend(
-				signed_transactions.into_iter().map(|t| Transaction::UserTransaction(t.clone())),
-			);
+			if let Some(index) = planned.block {
+				transactions
+					.extend(self.0[index].iter().map(|t| Transaction::UserTransaction(t.clone())));
+			}
 
 			let executable_transactions =
 				ExecutableTransactions::Unsharded(into_signature_verified_block(transactions));
 
-			let block = ExecutableBlock::new(block_id, executable_transactions);
-
-			executable_blocks.push(block);
-
-			round += 1;
-			current_timestamp_micros += timestamp_increment_micros;
-
-			if round % increment_epoch_each == increment_epoch_each - 1 {
-				epoch += 1;
-			}
+			executable_blocks.push(ExecutableBlock::new(planned.id, executable_transactions));
 		}
 
 		Ok(executable_blocks)
 	}
 
-	pub async fn run(&self, movement_executor: &mut MovementNode) -> Result<(), PreludeError> {
-		let current_timestamp_micros = chrono::Utc::now().timestamp_micros() as u64;
+	/// Runs the prelude on the executor, following on from its latest block.
+	///
+	/// Fails if any transaction is discarded or aborts, see [PreludeRunConfig::with_allow_aborts].
+	pub async fn run(
+		&self,
+		movement_executor: &mut MovementNode,
+	) -> Result<PreludeOutput, PreludeError> {
+		let config = PreludeRunConfig::following(movement_executor)?;
+		self.run_with(movement_executor, &config).await
+	}
+
+	/// Runs the prelude on the executor with the given config, returning the output of each user transaction.
+	pub async fn run_with(
+		&self,
+		movement_executor: &mut MovementNode,
+		config: &PreludeRunConfig,
+	) -> Result<PreludeOutput, PreludeError> {
+		let start_version = movement_executor
+			.latest_ledger_version()
+			.map_err(|e| PreludeError::Internal(e.into()))?;
 
 		let executable_blocks = self
-			.get_executable_blocks(
-				movement_executor,
-				DEFAULT_EPOCH,
-				DEFAULT_INCREMENT_EPOCH_EACH,
-				DEFAULT_ROUND,
-				current_timestamp_micros,
-				DEFAULT_INCREMENT_MICROS,
-			)
+			.get_executable_blocks(movement_executor, config)
 			.map_err(|e| PreludeError::Internal(e.into()))?;
 
 		for block in executable_blocks {
 			movement_executor
 				.opt_executor_mut()
+				.map_err(|e| PreludeError::Internal(e.into()))?
 				.execute_block(block)
 				.await
 				.map_err(|e| PreludeError::Internal(e.into()))?;
 		}
 
-		Ok(())
+		let output = self
+			.output(movement_executor, start_version)
+			.map_err(|e| PreludeError::Internal(e.into()))?;
+
+		let failures: Vec<String> = output
+			.failures(config.allow_aborts)
+			.map(|transaction| transaction.to_string())
+			.collect();
+		if !failures.is_empty() {
+			return Err(PreludeError::Failed(failures.join("\n"), Box::new(output)));
+		}
+
+		Ok(output)
+	}
+
+	/// Reads back the output of each user transaction committed after the start version.
+	///
+	/// Transactions which were not committed are discarded.
+	fn output(
+		&self,
+		movement_executor: &MovementNode,
+		start_version: u64,
+	) -> Result<PreludeOutput, anyhow::Error> {
+		let db_reader = movement_executor.db_reader();
+		let latest_version = movement_executor.latest_ledger_version()?;
+
+		let mut committed = HashMap::new();
+		for version in start_version + 1..=latest_version {
+			let transaction =
+				db_reader.get_transaction_by_version(version, latest_version, true)?;
+			let info = transaction.proof.transaction_info;
+			committed.insert(
+				info.transaction_hash(),
+				(version, info.status().clone(), info.gas_used(), transaction.events),
+			);
+		}
+
+		let transactions = self
+			.0
+			.iter()
+			.flatten()
+			.map(|signed_transaction| {
+				let hash = Transaction::UserTransaction(signed_transaction.clone()).hash();
+				let sender = signed_transaction.sender();
+				let sequence_number = signed_transaction.sequence_number();
+				match committed.remove(&hash) {
+					Some((version, status, gas_used, events)) => TransactionOutput {
+						hash,
+						sender,
+						sequence_number,
+						outcome: Outcome::Kept(status),
+						version: Some(version),
+						gas_used,
+						events: events.unwrap_or_default(),
+					},
+					None => TransactionOutput::discarded(hash, sender, sequence_number),
+				}
+			})
+			.collect();
+
+		Ok(PreludeOutput::new(transactions))
 	}
 }
 
+/// The metadata of a block the prelude executes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedBlock {
+	/// The block id.
+	pub id: HashValue,
+	/// The epoch the block is proposed in.
+	pub epoch: u64,
+	/// The round the block is proposed in.
+	pub round: u64,
+	/// The block timestamp.
+	pub timestamp_micros: u64,
+	/// The index of the prelude block whose transactions it carries, or `None` for a block which only ends the epoch.
+	pub block: Option<usize>,
+}
+
+/// How the blocks of a prelude are laid out when run.
+///
+/// Everything is derived from the config, so running the same prelude with the same config from the same state gives byte-identical DBs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreludeRunConfig {
+	/// The epoch to start with.
+	epoch: u64,
+	/// The round to start with.
+	round: u64,
+	/// The number of prelude blocks in each epoch.
+	blocks_per_epoch: u64,
+	/// The timestamp of the first block.
+	start_timestamp_micros: u64,
+	/// The number of microseconds between blocks.
+	timestamp_increment_micros: u64,
+	/// The on-chain epoch interval, which must pass for a block to trigger reconfiguration.
+	epoch_interval_micros: u64,
+	/// The on-chain time of the last reconfiguration, or `None` for the start timestamp.
+	last_reconfiguration_micros: Option<u64>,
+	/// The seed block ids are generated from.
+	seed: u64,
+	/// Whether transactions which abort are allowed, rather than failing the run.
+	allow_aborts: bool,
+}
+
+impl Default for PreludeRunConfig {
+	fn default() -> Self {
+		Self {
+			epoch: DEFAULT_EPOCH,
+			round: DEFAULT_ROUND,
+			blocks_per_epoch: DEFAULT_INCREMENT_EPOCH_EACH,
+			start_timestamp_micros: DEFAULT_START_TIMESTAMP_MICROS,
+			timestamp_increment_micros: DEFAULT_INCREMENT_MICROS,
+			epoch_interval_micros: DEFAULT_EPOCH_INTERVAL_MICROS,
+			last_reconfiguration_micros: None,
+			seed: DEFAULT_SEED,
+			allow_aborts: false,
+		}
+	}
+}
+
+impl PreludeRunConfig {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// A config which follows on from the executor's latest block.
+	///
+	/// The epoch, the epoch interval and the time of the last reconfiguration are read from on-chain state,
+	/// and the round continues from the latest block, or starts from 1 if that block reconfigured.
+	pub fn following(executor: &MovementNode) -> Result<Self, PreludeError> {
+		Self::try_following(executor).map_err(|e| PreludeError::Internal(e.into()))
+	}
+
+	fn try_following(executor: &MovementNode) -> Result<Self, anyhow::Error> {
+		let version = executor.latest_ledger_version()?;
+		let configuration: Configuration =
+			on_chain(executor, version, Configuration::state_key()?)?;
+		let block_resource: BlockResource =
+			on_chain(executor, version, BlockResource::state_key()?)?;
+
+		let (_start, _end, latest_block) = executor
+			.db_reader()
+			.get_block_info_by_version(version)
+			.context("failed to get the latest block")?;
+		let round =
+			if latest_block.epoch() == configuration.epoch { latest_block.round() + 1 } else { 1 };
+
+		let config = Self::default();
+		let start_timestamp_micros =
+			executor.latest_timestamp_micros()? + config.timestamp_increment_micros;
+		Ok(config
+			.with_epoch(configuration.epoch)
+			.with_round(round)
+			.with_start_timestamp_micros(start_timestamp_micros)
+			.with_epoch_interval_micros(block_resource.epoch_interval)
+			.with_last_reconfiguration_micros(configuration.last_reconfiguration_time))
+	}
+
+	/// Builder API: sets the epoch to start with.
+	pub fn with_epoch(mut self, epoch: u64) -> Self {
+		self.epoch = epoch;
+		self
+	}
+
+	/// Builder API: sets the round to start with.
+	pub fn with_round(mut self, round: u64) -> Self {
+		self.round = round;
+		self
+	}
+
+	/// Builder API: sets the number of prelude blocks in each epoch.
+	pub fn with_blocks_per_epoch(mut self, blocks_per_epoch: u64) -> Self {
+		self.blocks_per_epoch = blocks_per_epoch.max(1);
+		self
+	}
+
+	/// Builder API: sets the timestamp of the first block.
+	pub fn with_start_timestamp_micros(mut self, start_timestamp_micros: u64) -> Self {
+		self.start_timestamp_micros = start_timestamp_micros;
+		self
+	}
+
+	/// Builder API: sets the number of microseconds between blocks.
+	pub fn with_timestamp_increment_micros(mut self, timestamp_increment_micros: u64) -> Self {
+		self.timestamp_increment_micros = timestamp_increment_micros;
+		self
+	}
+
+	/// Builder API: sets the on-chain epoch interval.
+	pub fn with_epoch_interval_micros(mut self, epoch_interval_micros: u64) -> Self {
+		self.epoch_interval_micros = epoch_interval_micros;
+		self
+	}
+
+	/// Builder API: sets the on-chain time of the last reconfiguration.
+	pub fn with_last_reconfiguration_micros(mut self, last_reconfiguration_micros: u64) -> Self {
+		self.last_reconfiguration_micros = Some(last_reconfiguration_micros);
+		self
+	}
+
+	/// Builder API: sets the seed block ids are generated from.
+	pub fn with_seed(mut self, seed: u64) -> Self {
+		self.seed = seed;
+		self
+	}
+
+	/// Builder API: sets whether transactions which abort are allowed.
+	///
+	/// Discarded transactions always fail the run.
+	pub fn with_allow_aborts(mut self, allow_aborts: bool) -> Self {
+		self.allow_aborts = allow_aborts;
+		self
+	}
+
+	/// The id of the nth block, derived from the seed.
+	fn block_id(&self, index: u64) -> HashValue {
+		HashValue::sha3_256_of(&[self.seed.to_le_bytes(), index.to_le_bytes()].concat())
+	}
+
+	/// Plans the metadata for a prelude of the given number of blocks.
+	///
+	/// Once an epoch has [Self::with_blocks_per_epoch] blocks, or the next block would be past the epoch interval since the last reconfiguration,
+	/// a block without transactions is inserted which is timestamped past the epoch interval.
+	/// Its prologue reconfigures, which would abort any transactions after it, and the following blocks are in the next epoch from round 1.
+	pub fn plan(&self, blocks: usize) -> Vec<PlannedBlock> {
+		let mut planned = Vec::with_capacity(blocks);
+		let mut epoch = self.epoch;
+		let mut round = self.round;
+		let mut timestamp_micros = self.start_timestamp_micros;
+		let mut last_reconfiguration_micros =
+			self.last_reconfiguration_micros.unwrap_or(self.start_timestamp_micros);
+		let mut blocks_in_epoch = 0;
+
+		for block in 0..blocks {
+			let reconfiguration_micros = last_reconfiguration_micros + self.epoch_interval_micros;
+			if blocks_in_epoch == self.blocks_per_epoch
+				|| timestamp_micros >= reconfiguration_micros
+			{
+				timestamp_micros = timestamp_micros.max(reconfiguration_micros);
+				planned.push(PlannedBlock {
+					id: self.block_id(planned.len() as u64),
+					epoch,
+					round,
+					timestamp_micros,
+					block: None,
+				});
+				last_reconfiguration_micros = timestamp_micros;
+				timestamp_micros += self.timestamp_increment_micros;
+				epoch += 1;
+				round = 1;
+				blocks_in_epoch = 0;
+			}
+
+			planned.push(PlannedBlock {
+				id: self.block_id(planned.len() as u64),
+				epoch,
+				round,
+				timestamp_micros,
+				block: Some(block),
+			});
+			round += 1;
+			timestamp_micros += self.timestamp_increment_micros;
+			blocks_in_epoch += 1;
+		}
+
+		planned
+	}
+}
+
+/// Reads and decodes a framework resource from the executor's state at a version.
+fn on_chain<T: serde::de::DeserializeOwned>(
+	executor: &MovementNode,
+	version: u64,
+	key: StateKey,
+) -> Result<T, anyhow::Error> {
+	let bytes = executor
+		.state_view_at_version(Some(version))?
+		.get_state_value_bytes(&to_movement_state_key(&key)?)?
+		.with_context(|| format!("{:?} not found on chain", key))?;
+	Ok(bcs::from_bytes(&bytes)?)
+}
+
 pub trait PreludeGenerator {
 	/// Generates a prelude.
 	///
 	/// We'll make this async for now because we may want to pull some things off the wire at some point.
 	fn generate(self) -> impl Future<Output = Result<Prelude, PreludeError>>;
 }
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use crate::criterion::movement_executor::MovementOptExecutor;
+	use mtma_types::movement::aptos_crypto::ed25519::Ed25519PrivateKey;
+	use mtma_types::movement::aptos_sdk::transaction_builder::TransactionFactory;
+	use mtma_types::movement::aptos_sdk::types::{AccountKey, LocalAccount};
+	use mtma_types::movement::aptos_types::account_address::AccountAddress;
+	use mtma_types::movement::aptos_types::account_config::aptos_test_root_address;
+	use mtma_types::movement::aptos_types::chain_id::ChainId;
+	use mtma_types::movement::aptos_types::transaction::SignedTransaction;
+
+	/// A prelude of one transfer per block, of the given amounts.
+	fn transfers(amounts: &[u64]) -> Result<Prelude, anyhow::Error> {
+		let private_key = Ed25519PrivateKey::try_from(&[7u8; 32][..])?;
+		let account =
+			LocalAccount::new(AccountAddress::ONE, AccountKey::from_private_key(private_key), 0);
+		let factory = TransactionFactory::new(ChainId::test());
+		Ok(Prelude::new(amounts.iter().map(|amount| {
+			vec![account
+				.sign_with_transaction_builder(factory.transfer(AccountAddress::TWO, *amount))]
+		})))
+	}
+
+	#[test]
+	fn test_concat_and_interleave() -> Result<(), anyhow::Error> {
+		let (a, b) = (transfers(&[1, 2, 3])?, transfers(&[4])?);
+		let blocks = |prelude: &Prelude| prelude.blocks().to_vec();
+
+		let concatenated = Prelude::concat([a.clone(), b.clone()]);
+		assert_eq!(blocks(&concatenated), [blocks(&a), blocks(&b)].concat());
+
+		let mut extended = a.clone();
+		extended.extend(b.clone());
+		assert_eq!(blocks(&extended), blocks(&concatenated));
+
+		let interleaved = Prelude::interleave([a.clone(), b.clone()]);
+		assert_eq!(
+			blocks(&interleaved),
+			vec![a.0[0].clone(), b.0[0].clone(), a.0[1].clone(), a.0[2].clone()]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_plan_is_deterministic() {
+		let config = PreludeRunConfig::new().with_seed(7);
+		assert_eq!(config.plan(10), config.plan(10));
+		assert_ne!(config.plan(10), PreludeRunConfig::new().with_seed(8).plan(10));
+	}
+
+	#[test]
+	fn test_plan_reconfigures_between_epochs() {
+		let config = PreludeRunConfig::new()
+			.with_blocks_per_epoch(2)
+			.with_start_timestamp_micros(10)
+			.with_timestamp_increment_micros(1)
+			.with_epoch_interval_micros(100);
+		let planned = config.plan(5);
+
+		// two blocks per epoch, each epoch ended by a block without transactions
+		let blocks: Vec<Option<usize>> = planned.iter().map(|planned| planned.block).collect();
+		assert_eq!(blocks, vec![Some(0), Some(1), None, Some(2), Some(3), None, Some(4)]);
+		let epochs: Vec<u64> = planned.iter().map(|planned| planned.epoch).collect();
+		assert_eq!(epochs, vec![0, 0, 0, 1, 1, 1, 2]);
+
+		// the reconfiguring blocks are past the epoch interval, and timestamps always increase
+		assert_eq!(planned[2].timestamp_micros, 110);
+		assert_eq!(planned[5].timestamp_micros, 210);
+		assert!(planned
+			.windows(2)
+			.all(|pair| pair[0].timestamp_micros < pair[1].timestamp_micros));
+		assert_eq!(planned[3].round, 1);
+	}
+
+	#[test]
+	fn test_plan_reconfigures_past_the_epoch_interval() {
+		let config = PreludeRunConfig::new()
+			.with_start_timestamp_micros(4)
+			.with_timestamp_increment_micros(2)
+			.with_epoch_interval_micros(5)
+			.with_last_reconfiguration_micros(0);
+		let planned = config.plan(2);
+
+		// the second block would be past the interval, so the epoch is ended before it
+		let blocks: Vec<Option<usize>> = planned.iter().map(|planned| planned.block).collect();
+		assert_eq!(blocks, vec![Some(0), None, Some(1)]);
+		assert_eq!(planned[1].timestamp_micros, 6);
+		assert_eq!(planned[2].epoch, config.epoch + 1);
+	}
+
+	/// Mints from the root account, one transaction per block.
+	fn mints(
+		private_key: Ed25519PrivateKey,
+		chain_id: ChainId,
+		blocks: u64,
+	) -> Vec<SignedTransaction> {
+		let root_account = LocalAccount::new(
+			aptos_test_root_address(),
+			AccountKey::from_private_key(private_key),
+			0,
+		);
+		// a fixed expiry keeps the transactions the same whenever they are signed
+		let factory =
+			TransactionFactory::new(chain_id).with_transaction_expiration_time(4_000_000_000);
+		(0..blocks)
+			.map(|amount| {
+				root_account
+					.sign_with_transaction_builder(factory.mint(AccountAddress::TWO, amount + 1))
+			})
+			.collect()
+	}
+
+	/// The ledger version, transaction accumulator hash and latest state checkpoint hash of a node.
+	fn roots(node: &MovementNode) -> Result<(u64, HashValue, Option<HashValue>), anyhow::Error> {
+		let version = node.latest_ledger_version()?;
+		let ledger_info = node.db_reader().get_latest_ledger_info()?;
+		let transaction = node.db_reader().get_transaction_by_version(version, version, false)?;
+		Ok((
+			version,
+			ledger_info.ledger_info().transaction_accumulator_hash(),
+			transaction.proof.transaction_info.state_checkpoint_hash(),
+		))
+	}
+
+	#[tokio::test]
+	#[tracing_test::traced_test]
+	async fn test_run_is_reproducible_and_reconfigures() -> Result<(), anyhow::Error> {
+		let (opt_executor, temp_dir, private_key, _receiver) =
+			MovementOptExecutor::try_generated().await?;
+		let chain_id = opt_executor.config().chain.maptos_chain_id.clone();
+		let (mut original, mut duplicate) =
+			MovementNode::try_duplicate(opt_executor, &temp_dir.path().join("duplicate")).await?;
+		let prelude = Prelude::new(mints(private_key, chain_id, 3).into_iter().map(|t| vec![t]));
+
+		let following = PreludeRunConfig::following(&original)?;
+		assert_eq!(following, PreludeRunConfig::following(&duplicate)?);
+		let config = following.clone().with_blocks_per_epoch(2);
+		for node in [&mut original, &mut duplicate] {
+			prelude.run_with(node, &config).await?;
+		}
+
+		// the same prelude from the same state gives the same ledger
+		assert_eq!(roots(&original)?, roots(&duplicate)?);
+
+		// the block inserted after the second prelude block reconfigured, so the third is in the next epoch from round 1
+		let after = PreludeRunConfig::following(&original)?;
+		assert_eq!(after.epoch, following.epoch + 1);
+		assert_eq!(after.round, 2);
+		assert!(after.last_reconfiguration_micros > following.last_reconfiguration_micros);
+		Ok(())
+	}
+}
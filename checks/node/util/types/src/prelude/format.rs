@@ -0,0 +1,150 @@
+use super::{Prelude, PreludeError};
+use mtma_types::movement::aptos_types::transaction::SignedTransaction;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+/// How a [Prelude] is stored on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreludeFormat {
+	/// The blocks of signed transactions as BCS.
+	Bcs,
+	/// One JSON line per signed transaction, with a line marking the end of each block.
+	JsonLines,
+}
+
+impl PreludeFormat {
+	/// The format for a path, by extension: `.bcs` is BCS and anything else is JSON lines.
+	pub fn of(path: &Path) -> Self {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("bcs") => Self::Bcs,
+			_ => Self::JsonLines,
+		}
+	}
+}
+
+/// A line of a JSON lines prelude.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Line {
+	/// A transaction in the current block.
+	Transaction(SignedTransaction),
+	/// The end of the current block.
+	EndBlock,
+}
+
+impl Prelude {
+	/// Encodes the prelude as BCS.
+	pub fn to_bcs(&self) -> Result<Vec<u8>, PreludeError> {
+		bcs::to_bytes(&self.0).map_err(|e| PreludeError::Format(e.into()))
+	}
+
+	/// Decodes a prelude from BCS.
+	pub fn from_bcs(bytes: &[u8]) -> Result<Self, PreludeError> {
+		Ok(Self(bcs::from_bytes(bytes).map_err(|e| PreludeError::Format(e.into()))?))
+	}
+
+	/// Writes the prelude as JSON lines.
+	pub fn write_json_lines(&self, mut writer: impl Write) -> Result<(), PreludeError> {
+		for block in &self.0 {
+			for transaction in block {
+				let line = serde_json::to_string(&Line::Transaction(transaction.clone()))
+					.map_err(|e| PreludeError::Format(e.into()))?;
+				writeln!(writer, "{}", line).map_err(|e| PreludeError::Format(e.into()))?;
+			}
+			let line = serde_json::to_string(&Line::EndBlock)
+				.map_err(|e| PreludeError::Format(e.into()))?;
+			writeln!(writer, "{}", line).map_err(|e| PreludeError::Format(e.into()))?;
+		}
+		Ok(())
+	}
+
+	/// Reads a prelude from JSON lines.
+	///
+	/// Transactions after the last end of block form a final block.
+	pub fn read_json_lines(reader: impl BufRead) -> Result<Self, PreludeError> {
+		let mut blocks = Vec::new();
+		let mut block = Vec::new();
+		for line in reader.lines() {
+			let line = line.map_err(|e| PreludeError::Format(e.into()))?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			match serde_json::from_str(&line).map_err(|e| PreludeError::Format(e.into()))? {
+				Line::Transaction(transaction) => block.push(transaction),
+				Line::EndBlock => blocks.push(std::mem::take(&mut block)),
+			}
+		}
+		if !block.is_empty() {
+			blocks.push(block);
+		}
+		Ok(Self(blocks))
+	}
+
+	/// Saves the prelude to a file, in the format given by its extension.
+	pub fn save(&self, path: &Path) -> Result<(), PreludeError> {
+		let file = |e: std::io::Error| PreludeError::File(path.to_path_buf(), e.into());
+		match PreludeFormat::of(path) {
+			PreludeFormat::Bcs => std::fs::write(path, self.to_bcs()?).map_err(file),
+			PreludeFormat::JsonLines => {
+				let mut writer =
+					std::io::BufWriter::new(std::fs::File::create(path).map_err(file)?);
+				self.write_json_lines(&mut writer)?;
+				writer.flush().map_err(file)
+			}
+		}
+	}
+
+	/// Loads a prelude from a file, in the format given by its extension.
+	pub fn load(path: &Path) -> Result<Self, PreludeError> {
+		let file = |e: std::io::Error| PreludeError::File(path.to_path_buf(), e.into());
+		match PreludeFormat::of(path) {
+			PreludeFormat::Bcs => Self::from_bcs(&std::fs::read(path).map_err(file)?),
+			PreludeFormat::JsonLines => {
+				Self::read_json_lines(BufReader::new(std::fs::File::open(path).map_err(file)?))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use mtma_types::movement::aptos_crypto::ed25519::Ed25519PrivateKey;
+	use mtma_types::movement::aptos_sdk::transaction_builder::TransactionFactory;
+	use mtma_types::movement::aptos_sdk::types::{AccountKey, LocalAccount};
+	use mtma_types::movement::aptos_types::account_address::AccountAddress;
+	use mtma_types::movement::aptos_types::chain_id::ChainId;
+
+	/// A prelude of two blocks, the second of them empty.
+	fn prelude() -> Result<Prelude, anyhow::Error> {
+		let private_key = Ed25519PrivateKey::try_from(&[7u8; 32][..])?;
+		let account =
+			LocalAccount::new(AccountAddress::ONE, AccountKey::from_private_key(private_key), 0);
+		let factory = TransactionFactory::new(ChainId::test());
+
+		let mut prelude = Prelude::new_empty();
+		prelude
+			.add_transaction(
+				account.sign_with_transaction_builder(factory.transfer(AccountAddress::TWO, 1)),
+			)
+			.add_transaction(
+				account.sign_with_transaction_builder(factory.transfer(AccountAddress::TWO, 2)),
+			)
+			.end_block();
+		Ok(prelude)
+	}
+
+	#[test]
+	fn test_round_trips() -> Result<(), anyhow::Error> {
+		let prelude = prelude()?;
+
+		assert_eq!(Prelude::from_bcs(&prelude.to_bcs()?)?.0, prelude.0);
+
+		let mut json_lines = Vec::new();
+		prelude.write_json_lines(&mut json_lines)?;
+		assert_eq!(Prelude::read_json_lines(json_lines.as_slice())?.0, prelude.0);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,147 @@
+use mtma_types::movement::aptos_crypto::HashValue;
+use mtma_types::movement::aptos_types::{
+	account_address::AccountAddress, contract_event::ContractEvent, transaction::ExecutionStatus,
+	transaction::Version,
+};
+use std::fmt;
+
+/// What became of a user transaction the prelude submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+	/// The transaction was committed with the given status, which may be an abort.
+	Kept(ExecutionStatus),
+	/// The transaction was not committed, e.g. because its sequence number was too old.
+	Discarded,
+}
+
+/// The output of a user transaction the prelude submitted.
+#[derive(Debug, Clone)]
+pub struct TransactionOutput {
+	/// The hash of the transaction.
+	pub hash: HashValue,
+	/// The sender of the transaction.
+	pub sender: AccountAddress,
+	/// The sequence number of the transaction.
+	pub sequence_number: u64,
+	/// What became of the transaction.
+	pub outcome: Outcome,
+	/// The version the transaction was committed at, if it was.
+	pub version: Option<Version>,
+	/// The gas the transaction used.
+	pub gas_used: u64,
+	/// The events the transaction emitted.
+	pub events: Vec<ContractEvent>,
+}
+
+impl TransactionOutput {
+	/// A transaction which was not committed.
+	pub fn discarded(hash: HashValue, sender: AccountAddress, sequence_number: u64) -> Self {
+		Self {
+			hash,
+			sender,
+			sequence_number,
+			outcome: Outcome::Discarded,
+			version: None,
+			gas_used: 0,
+			events: Vec::new(),
+		}
+	}
+
+	/// Whether the transaction was discarded.
+	pub fn is_discarded(&self) -> bool {
+		self.outcome == Outcome::Discarded
+	}
+
+	/// Whether the transaction was committed, but did not succeed.
+	pub fn is_aborted(&self) -> bool {
+		matches!(&self.outcome, Outcome::Kept(status) if !status.is_success())
+	}
+}
+
+impl fmt::Display for TransactionOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Transaction {} from {} at sequence number {} ",
+			self.hash, self.sender, self.sequence_number
+		)?;
+		match (&self.outcome, self.version) {
+			(Outcome::Kept(status), Some(version)) => write!(
+				f,
+				"was kept at version {} with {:?}, using {} gas",
+				version, status, self.gas_used
+			),
+			(Outcome::Kept(status), None) => write!(f, "was kept with {:?}", status),
+			(Outcome::Discarded, _) => write!(f, "was discarded"),
+		}
+	}
+}
+
+/// The outputs of the user transactions a prelude submitted, in the order they were submitted.
+#[derive(Debug, Clone, Default)]
+pub struct PreludeOutput {
+	pub transactions: Vec<TransactionOutput>,
+}
+
+impl PreludeOutput {
+	pub fn new(transactions: Vec<TransactionOutput>) -> Self {
+		Self { transactions }
+	}
+
+	/// The transactions which were discarded.
+	pub fn discarded(&self) -> impl Iterator<Item = &TransactionOutput> {
+		self.transactions.iter().filter(|transaction| transaction.is_discarded())
+	}
+
+	/// The transactions which were committed, but did not succeed.
+	pub fn aborted(&self) -> impl Iterator<Item = &TransactionOutput> {
+		self.transactions.iter().filter(|transaction| transaction.is_aborted())
+	}
+
+	/// The transactions which count as failures, which includes aborts unless they are allowed.
+	pub fn failures(&self, allow_aborts: bool) -> impl Iterator<Item = &TransactionOutput> {
+		self.transactions.iter().filter(move |transaction| {
+			transaction.is_discarded() || (!allow_aborts && transaction.is_aborted())
+		})
+	}
+
+	/// The total gas the transactions used.
+	pub fn gas_used(&self) -> u64 {
+		self.transactions.iter().map(|transaction| transaction.gas_used).sum()
+	}
+}
+
+impl fmt::Display for PreludeOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for transaction in &self.transactions {
+			writeln!(f, "{}", transaction)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use mtma_types::movement::aptos_types::vm_status::StatusCode;
+
+	#[test]
+	fn test_failures() {
+		let kept = |status| TransactionOutput {
+			outcome: Outcome::Kept(status),
+			version: Some(1),
+			..TransactionOutput::discarded(HashValue::zero(), AccountAddress::ONE, 0)
+		};
+		let output = PreludeOutput::new(vec![
+			kept(ExecutionStatus::Success),
+			kept(ExecutionStatus::MiscellaneousError(Some(StatusCode::INSUFFICIENT_BALANCE))),
+			TransactionOutput::discarded(HashValue::zero(), AccountAddress::ONE, 1),
+		]);
+
+		assert_eq!(output.discarded().count(), 1);
+		assert_eq!(output.aborted().count(), 1);
+		assert_eq!(output.failures(false).count(), 2);
+		// discarded transactions fail even when aborts are allowed
+		assert_eq!(output.failures(true).count(), 1);
+	}
+}
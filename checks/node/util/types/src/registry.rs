@@ -0,0 +1,455 @@
+use crate::divergence::DIVERGENCES;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Errors thrown when working with the [Registry].
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+	#[error("malformed parameter {0:?}, expected <criterion>.<name>=<value>")]
+	Malformed(String),
+	#[error("no criterion is registered as {0:?}")]
+	Unknown(String),
+	#[error("a criterion is already registered as {0:?}")]
+	Duplicate(String),
+	#[error("invalid parameters for criterion {0:?}: {1}")]
+	Parameters(String, #[source] Box<dyn std::error::Error + Send + Sync>),
+	#[error("failed to build criterion: {0}")]
+	Build(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The criterion name under which a parameter is shared by every criterion.
+pub const SHARED: &str = "*";
+
+/// Describes a parameter a criterion accepts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterSpec {
+	/// The name of the parameter.
+	pub name: String,
+	/// What the parameter does.
+	pub description: String,
+	/// The default, as JSON.
+	pub default: String,
+}
+
+/// A parameter passed to a criterion, as `<criterion>.<name>=<value>`.
+///
+/// The value is read as JSON, falling back to a string.
+/// A parameter for the criterion [SHARED], i.e. `*.<name>=<value>`, is passed to every selected criterion which declares it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+	/// The name of the criterion.
+	pub criterion: String,
+	/// The name of the parameter.
+	pub name: String,
+	/// The value.
+	pub value: Value,
+}
+
+impl FromStr for Parameter {
+	type Err = RegistryError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (path, value) =
+			s.split_once('=').ok_or_else(|| RegistryError::Malformed(s.to_string()))?;
+		let (criterion, name) =
+			path.split_once('.').ok_or_else(|| RegistryError::Malformed(s.to_string()))?;
+		let value =
+			serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+		Ok(Self { criterion: criterion.to_string(), name: name.to_string(), value })
+	}
+}
+
+impl fmt::Display for Parameter {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}.{}={}", self.criterion, self.name, self.value)
+	}
+}
+
+impl Parameter {
+	/// A parameter passed to every selected criterion which declares it.
+	pub fn shared(name: impl Into<String>, value: impl Into<Value>) -> Self {
+		Self { criterion: SHARED.to_string(), name: name.into(), value: value.into() }
+	}
+
+	/// Groups parameters into one JSON object per criterion.
+	pub fn group(parameters: &[Parameter]) -> BTreeMap<String, Value> {
+		let mut grouped: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+		for parameter in parameters {
+			grouped
+				.entry(parameter.criterion.clone())
+				.or_default()
+				.insert(parameter.name.clone(), parameter.value.clone());
+		}
+		grouped
+			.into_iter()
+			.map(|(criterion, object)| (criterion, Value::Object(object)))
+			.collect()
+	}
+}
+
+/// Deserializes the parameters of a criterion, treating null as the default.
+pub fn parameters<P>(criterion: &str, value: &Value) -> Result<P, RegistryError>
+where
+	P: DeserializeOwned + Default,
+{
+	if value.is_null() {
+		return Ok(P::default());
+	}
+	serde_json::from_value(value.clone())
+		.map_err(|e| RegistryError::Parameters(criterion.to_string(), e.into()))
+}
+
+/// Builds a criterion from its parameters.
+pub type Constructor<E> = Box<dyn Fn(&Value) -> Result<E, RegistryError> + Send + Sync>;
+
+/// A criterion as registered under a name.
+pub struct Registration<E> {
+	name: String,
+	description: String,
+	parameters: Vec<ParameterSpec>,
+	constructor: Constructor<E>,
+}
+
+impl<E> Registration<E> {
+	pub fn new(
+		name: impl Into<String>,
+		description: impl Into<String>,
+		constructor: impl Fn(&Value) -> Result<E, RegistryError> + Send + Sync + 'static,
+	) -> Self {
+		Self {
+			name: name.into(),
+			description: description.into(),
+			parameters: Vec::new(),
+			constructor: Box::new(constructor),
+		}
+	}
+
+	/// Builder API: adds a parameter description.
+	pub fn with_parameter(
+		mut self,
+		name: impl Into<String>,
+		description: impl Into<String>,
+		default: impl Into<String>,
+	) -> Self {
+		self.parameters.push(ParameterSpec {
+			name: name.into(),
+			description: description.into(),
+			default: default.into(),
+		});
+		self
+	}
+
+	/// Builder API: declares the [DIVERGENCES] parameter, so the criterion receives a shared divergence spec.
+	pub fn with_divergences(self) -> Self {
+		self.with_parameter(
+			DIVERGENCES,
+			"Divergences the migration is expected to introduce, inline or as a YAML or JSON path.",
+			r#"{"divergences":[]}"#,
+		)
+	}
+
+	/// The name of the criterion.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// What the criterion checks.
+	pub fn description(&self) -> &str {
+		&self.description
+	}
+
+	/// The parameters the criterion accepts.
+	pub fn parameters(&self) -> &[ParameterSpec] {
+		&self.parameters
+	}
+
+	/// Builds the criterion from its parameters.
+	pub fn build(&self, parameters: &Value) -> Result<E, RegistryError> {
+		(self.constructor)(parameters)
+	}
+
+	/// Merges the shared parameters the criterion declares under its own, which take precedence.
+	///
+	/// Its own parameters must be an object if any shared parameter is merged in, as they could not be merged otherwise.
+	fn shared(&self, shared: Option<&Value>, own: Option<&Value>) -> Result<Value, RegistryError> {
+		let mut merged = Map::new();
+		if let Some(Value::Object(shared)) = shared {
+			for (name, value) in shared {
+				if self.parameters.iter().any(|parameter| &parameter.name == name) {
+					merged.insert(name.clone(), value.clone());
+				}
+			}
+		}
+		match own {
+			Some(Value::Object(own)) => merged.extend(own.clone()),
+			Some(own) if merged.is_empty() => return Ok(own.clone()),
+			Some(own) => {
+				return Err(RegistryError::Parameters(
+					self.name.clone(),
+					format!("expected an object to merge the shared parameters into, got {}", own)
+						.into(),
+				))
+			}
+			None => {}
+		}
+		if merged.is_empty() {
+			return Ok(Value::Null);
+		}
+		Ok(Value::Object(merged))
+	}
+
+	/// Maps what the registration builds, e.g. to lift a criterion to another level.
+	pub fn map<F>(self, f: impl Fn(E) -> F + Send + Sync + 'static) -> Registration<F>
+	where
+		E: 'static,
+	{
+		let constructor = self.constructor;
+		Registration {
+			name: self.name,
+			description: self.description,
+			parameters: self.parameters,
+			constructor: Box::new(move |parameters| constructor(parameters).map(&f)),
+		}
+	}
+}
+
+/// Criteria by name.
+pub struct Registry<E> {
+	registrations: BTreeMap<String, Registration<E>>,
+}
+
+impl<E> Default for Registry<E> {
+	fn default() -> Self {
+		Self { registrations: BTreeMap::new() }
+	}
+}
+
+impl<E> Registry<E> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Builder API: registers a criterion.
+	///
+	/// Panics if a criterion is already registered under the same name, see [Registry::register].
+	pub fn with(mut self, registration: Registration<E>) -> Self {
+		if let Err(e) = self.register(registration) {
+			panic!("{}", e);
+		}
+		self
+	}
+
+	/// Registers a criterion, rejecting a name which is already registered rather than replacing its criterion.
+	pub fn register(&mut self, registration: Registration<E>) -> Result<(), RegistryError> {
+		match self.registrations.entry(registration.name().to_string()) {
+			Entry::Occupied(entry) => Err(RegistryError::Duplicate(entry.key().clone())),
+			Entry::Vacant(entry) => {
+				entry.insert(registration);
+				Ok(())
+			}
+		}
+	}
+
+	/// Gets a registration by name.
+	pub fn get(&self, name: &str) -> Result<&Registration<E>, RegistryError> {
+		self.registrations
+			.get(name)
+			.ok_or_else(|| RegistryError::Unknown(name.to_string()))
+	}
+
+	/// The registered names, in order.
+	pub fn names(&self) -> impl Iterator<Item = &str> {
+		self.registrations.keys().map(String::as_str)
+	}
+
+	/// The registrations, in name order.
+	pub fn registrations(&self) -> impl Iterator<Item = &Registration<E>> {
+		self.registrations.values()
+	}
+
+	/// Consumes the registry into its registrations, in name order.
+	pub fn into_registrations(self) -> impl Iterator<Item = Registration<E>> {
+		self.registrations.into_values()
+	}
+
+	/// Builds the named criteria, passing each its parameters.
+	///
+	/// Parameters for criteria which are not named are rejected, as they are likely a typo.
+	/// Shared parameters are passed to each named criterion which declares them.
+	pub fn build(
+		&self,
+		names: &[String],
+		parameters: &[Parameter],
+	) -> Result<Vec<E>, RegistryError> {
+		let grouped = Parameter::group(parameters);
+		for criterion in grouped.keys() {
+			if criterion != SHARED && !names.contains(criterion) {
+				return Err(RegistryError::Parameters(
+					criterion.clone(),
+					"the criterion is not selected".into(),
+				));
+			}
+		}
+
+		names
+			.iter()
+			.map(|name| {
+				let registration = self.get(name)?;
+				let parameters = registration.shared(grouped.get(SHARED), grouped.get(name))?;
+				registration.build(&parameters)
+			})
+			.collect()
+	}
+
+	/// Builds every registered criterion, passing each its parameters.
+	pub fn build_all(&self, parameters: &[Parameter]) -> Result<Vec<E>, RegistryError> {
+		let names: Vec<String> = self.names().map(String::from).collect();
+		self.build(&names, parameters)
+	}
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use serde_json::json;
+
+	/// Registers a criterion which builds into its parameters.
+	fn echo(name: &str) -> Registration<Value> {
+		Registration::new(name, "Builds into its parameters.", |parameters| Ok(parameters.clone()))
+	}
+
+	#[test]
+	fn test_parameter_from_str() -> Result<(), anyhow::Error> {
+		let parameter: Parameter = "supply-conserved.per_side_tolerance=10".parse()?;
+		assert_eq!(
+			parameter,
+			Parameter {
+				criterion: "supply-conserved".to_string(),
+				name: "per_side_tolerance".to_string(),
+				value: json!(10),
+			}
+		);
+		assert_eq!(parameter.to_string(), "supply-conserved.per_side_tolerance=10");
+
+		// the value is read as JSON, falling back to a string
+		let parameter: Parameter = "a.b=[1,2]".parse()?;
+		assert_eq!(parameter.value, json!([1, 2]));
+		let parameter: Parameter = "a.b=not json".parse()?;
+		assert_eq!(parameter.value, json!("not json"));
+
+		// the name may itself contain dots and the value equals signs
+		let parameter: Parameter = "a.b.c=d=e".parse()?;
+		assert_eq!(parameter.name, "b.c");
+		assert_eq!(parameter.value, json!("d=e"));
+
+		assert!(matches!("a.b".parse::<Parameter>(), Err(RegistryError::Malformed(_))));
+		assert!(matches!("a=b".parse::<Parameter>(), Err(RegistryError::Malformed(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn test_group() -> Result<(), anyhow::Error> {
+		let parameters: Vec<Parameter> =
+			vec!["a.x=1".parse()?, "b.x=2".parse()?, "a.y=3".parse()?, "a.x=4".parse()?];
+		let grouped = Parameter::group(&parameters);
+		assert_eq!(grouped.len(), 2);
+		// a later parameter overrides an earlier one of the same name
+		assert_eq!(grouped["a"], json!({"x": 4, "y": 3}));
+		assert_eq!(grouped["b"], json!({"x": 2}));
+		assert!(Parameter::group(&[]).is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn test_build() -> Result<(), anyhow::Error> {
+		let registry = Registry::new().with(echo("a")).with(echo("b"));
+		let parameters: Vec<Parameter> = vec!["a.x=1".parse()?];
+
+		let built = registry.build(&["a".to_string(), "b".to_string()], &parameters)?;
+		assert_eq!(built, vec![json!({"x": 1}), Value::Null]);
+		assert_eq!(registry.build_all(&[])?.len(), 2);
+
+		// unknown criteria are rejected
+		assert!(matches!(
+			registry.build(&["c".to_string()], &[]),
+			Err(RegistryError::Unknown(name)) if name == "c"
+		));
+
+		// as are parameters for criteria which are not selected
+		assert!(matches!(
+			registry.build(&["b".to_string()], &parameters),
+			Err(RegistryError::Parameters(name, _)) if name == "a"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_build_merges_shared_parameters() -> Result<(), anyhow::Error> {
+		let registry = Registry::new()
+			.with(echo("a").with_divergences())
+			.with(echo("b").with_divergences())
+			.with(echo("c"));
+		let names = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+		let parameters: Vec<Parameter> = vec![
+			Parameter::shared(DIVERGENCES, json!("shared.yaml")),
+			"a.x=1".parse()?,
+			"b.divergences=\"own.yaml\"".parse()?,
+		];
+
+		// the shared parameter is only passed to criteria which declare it, and their own take precedence
+		let built = registry.build(&names, &parameters)?;
+		assert_eq!(
+			built,
+			vec![
+				json!({"divergences": "shared.yaml", "x": 1}),
+				json!({"divergences": "own.yaml"}),
+				Value::Null,
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_shared_rejects_unmergeable_parameters() -> Result<(), anyhow::Error> {
+		let registration = echo("a").with_divergences();
+		let shared = json!({"divergences": "shared.yaml", "x": 1});
+
+		// only the shared parameters the criterion declares are merged
+		assert_eq!(
+			registration.shared(Some(&shared), None)?,
+			json!({"divergences": "shared.yaml"})
+		);
+
+		// parameters which are not an object are passed as they are if there is nothing to merge
+		assert_eq!(registration.shared(None, Some(&json!(1)))?, json!(1));
+
+		// but are not dropped if there is
+		assert!(matches!(
+			registration.shared(Some(&shared), Some(&json!(1))),
+			Err(RegistryError::Parameters(name, _)) if name == "a"
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_register_rejects_duplicates() {
+		let mut registry = Registry::new().with(echo("a"));
+		assert!(matches!(
+			registry.register(echo("a")),
+			Err(RegistryError::Duplicate(name)) if name == "a"
+		));
+		assert_eq!(registry.names().collect::<Vec<_>>(), vec!["a"]);
+		assert!(registry.register(echo("b")).is_ok());
+	}
+
+	#[test]
+	#[should_panic(expected = "already registered")]
+	fn test_with_panics_on_duplicates() {
+		let _ = Registry::new().with(echo("a")).with(echo("a"));
+	}
+}
@@ -0,0 +1,362 @@
+use crate::evaluation::{Evaluation, Verdict};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Errors thrown when working with a [CheckReport].
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+	#[error("failed to serialize the report: {0}")]
+	Serialize(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The outcome of a stage of a checked migration, i.e. the prelude or the migration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageReport {
+	/// The verdict.
+	pub verdict: Verdict,
+	/// Why the verdict was reached, if not obvious.
+	pub message: Option<String>,
+	/// How long the stage took.
+	pub duration: Duration,
+}
+
+impl StageReport {
+	/// Forms the report of a stage which completed.
+	pub fn pass(duration: Duration) -> Self {
+		Self { verdict: Verdict::Pass, message: None, duration }
+	}
+
+	/// Forms the report of a stage which could not complete.
+	pub fn error(message: impl Into<String>, duration: Duration) -> Self {
+		Self { verdict: Verdict::Error, message: Some(message.into()), duration }
+	}
+}
+
+/// The outcome of the migration stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationReport {
+	#[serde(flatten)]
+	pub stage: StageReport,
+	/// Where the migrated db was written.
+	pub output_path: Option<PathBuf>,
+	/// The latest ledger version of the Movement node.
+	pub movement_version: Option<u64>,
+	/// The latest ledger version of the migrated Movement Aptos node.
+	pub movement_aptos_version: Option<u64>,
+}
+
+/// The report of a checked migration, or of a check against a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckReport {
+	/// The outcome of the prelude, if one was run.
+	pub prelude: Option<StageReport>,
+	/// The outcome of the migration, if one was run.
+	pub migration: Option<MigrationReport>,
+	/// The evaluation of each criterion, in the order they were given.
+	pub criteria: Vec<Evaluation>,
+}
+
+impl CheckReport {
+	/// Forms the report of a check whose prelude could not complete, so nothing after it was run.
+	pub fn prelude_error(message: impl Into<String>, duration: Duration) -> Self {
+		Self {
+			prelude: Some(StageReport::error(message, duration)),
+			migration: None,
+			criteria: Vec::new(),
+		}
+	}
+
+	/// Forms the report of a check whose migration could not complete after the prelude, so no criteria were evaluated.
+	pub fn migration_error(
+		prelude: StageReport,
+		message: impl Into<String>,
+		duration: Duration,
+	) -> Self {
+		Self {
+			prelude: Some(prelude),
+			migration: Some(MigrationReport {
+				stage: StageReport::error(message, duration),
+				output_path: None,
+				movement_version: None,
+				movement_aptos_version: None,
+			}),
+			criteria: Vec::new(),
+		}
+	}
+
+	/// Whether every stage completed and every criterion passed.
+	pub fn passed(&self) -> bool {
+		self.stages().all(|(_, stage)| stage.verdict == Verdict::Pass)
+			&& self.criteria.iter().all(Evaluation::passed)
+	}
+
+	/// The stages which were run, by name.
+	pub fn stages(&self) -> impl Iterator<Item = (&'static str, &StageReport)> {
+		self.prelude
+			.iter()
+			.map(|prelude| ("prelude", prelude))
+			.chain(self.migration.iter().map(|migration| ("migration", &migration.stage)))
+	}
+
+	/// The criteria which did not pass.
+	pub fn failures(&self) -> impl Iterator<Item = &Evaluation> {
+		self.criteria.iter().filter(|evaluation| !evaluation.passed())
+	}
+
+	/// The total duration of the stages and criteria.
+	pub fn duration(&self) -> Duration {
+		self.stages().map(|(_, stage)| stage.duration).sum::<Duration>()
+			+ self.criteria.iter().map(|evaluation| evaluation.duration).sum::<Duration>()
+	}
+
+	/// Serializes the report to pretty JSON.
+	pub fn to_json(&self) -> Result<String, ReportError> {
+		serde_json::to_string_pretty(self).map_err(|e| ReportError::Serialize(e.into()))
+	}
+
+	/// Serializes the report to JUnit XML.
+	///
+	/// The stages which were run and the criteria are reported as two test suites.
+	/// Each top-level criterion is a test case, with its whole evaluation tree in the failure or error body.
+	pub fn to_junit(&self) -> String {
+		let mut stages = Vec::new();
+		if let Some(prelude) = &self.prelude {
+			stages.push(TestCase::from_stage("prelude", prelude, None));
+		}
+		if let Some(migration) = &self.migration {
+			stages.push(TestCase::from_stage(
+				"migration",
+				&migration.stage,
+				Some(migration.details()),
+			));
+		}
+		let criteria: Vec<TestCase> = self.criteria.iter().map(TestCase::from_evaluation).collect();
+
+		let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+		let all: Vec<&TestCase> = stages.iter().chain(criteria.iter()).collect();
+		let _ = writeln!(
+			xml,
+			"<testsuites name=\"check\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+			all.len(),
+			count(&all, Verdict::Fail),
+			count(&all, Verdict::Error),
+			self.duration().as_secs_f64()
+		);
+		if !stages.is_empty() {
+			write_suite(&mut xml, "stages", &stages);
+		}
+		write_suite(&mut xml, "criteria", &criteria);
+		xml.push_str("</testsuites>\n");
+		xml
+	}
+}
+
+impl MigrationReport {
+	/// The migration details, as reported in the JUnit output.
+	fn details(&self) -> String {
+		let mut details = String::new();
+		if let Some(output_path) = &self.output_path {
+			let _ = writeln!(details, "output path: {}", output_path.display());
+		}
+		if let Some(version) = self.movement_version {
+			let _ = writeln!(details, "movement version: {}", version);
+		}
+		if let Some(version) = self.movement_aptos_version {
+			let _ = writeln!(details, "movement aptos version: {}", version);
+		}
+		details
+	}
+}
+
+impl fmt::Display for CheckReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (name, stage) in self.stages() {
+			writeln!(f, "[{}] {} ({:?})", stage.verdict, name, stage.duration)?;
+		}
+		for evaluation in &self.criteria {
+			write!(f, "{}", evaluation)?;
+		}
+		Ok(())
+	}
+}
+
+/// A JUnit test case.
+struct TestCase {
+	name: String,
+	verdict: Verdict,
+	duration: Duration,
+	/// A one line summary for the failure or error.
+	message: Option<String>,
+	/// The body of the failure or error, or the system output of a passing test case.
+	body: Option<String>,
+}
+
+impl TestCase {
+	fn from_stage(name: &str, stage: &StageReport, details: Option<String>) -> Self {
+		Self {
+			name: name.to_string(),
+			verdict: stage.verdict,
+			duration: stage.duration,
+			message: stage.message.clone(),
+			body: details.filter(|details| !details.is_empty()).or_else(|| stage.message.clone()),
+		}
+	}
+
+	fn from_evaluation(evaluation: &Evaluation) -> Self {
+		Self {
+			name: evaluation.name.clone(),
+			verdict: evaluation.verdict,
+			duration: evaluation.duration,
+			message: evaluation.message.clone(),
+			body: Some(evaluation.to_string()),
+		}
+	}
+}
+
+fn count(test_cases: &[&TestCase], verdict: Verdict) -> usize {
+	test_cases.iter().filter(|test_case| test_case.verdict == verdict).count()
+}
+
+fn write_suite(xml: &mut String, name: &str, test_cases: &[TestCase]) {
+	let all: Vec<&TestCase> = test_cases.iter().collect();
+	let duration: Duration = test_cases.iter().map(|test_case| test_case.duration).sum();
+	let _ = writeln!(
+		xml,
+		"  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+		escape(name),
+		all.len(),
+		count(&all, Verdict::Fail),
+		count(&all, Verdict::Error),
+		duration.as_secs_f64()
+	);
+	for test_case in test_cases {
+		let _ = write!(
+			xml,
+			"    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+			escape(name),
+			escape(&test_case.name),
+			test_case.duration.as_secs_f64()
+		);
+		let element = match test_case.verdict {
+			Verdict::Pass => "system-out",
+			Verdict::Fail => "failure",
+			Verdict::Error => "error",
+		};
+		match (&test_case.body, test_case.verdict) {
+			(None, Verdict::Pass) => {
+				xml.push_str("/>\n");
+			}
+			(body, verdict) => {
+				xml.push_str(">\n");
+				let message = match verdict {
+					Verdict::Pass => String::new(),
+					_ => format!(
+						" message=\"{}\"",
+						escape(test_case.message.as_deref().unwrap_or_default())
+					),
+				};
+				let _ = writeln!(
+					xml,
+					"      <{}{}>{}</{}>",
+					element,
+					message,
+					escape(body.as_deref().unwrap_or_default()),
+					element
+				);
+				xml.push_str("    </testcase>\n");
+			}
+		}
+	}
+	xml.push_str("  </testsuite>\n");
+}
+
+/// Escapes text for use in XML attributes and bodies.
+fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&apos;"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	fn report() -> CheckReport {
+		CheckReport {
+			prelude: Some(StageReport::pass(Duration::from_millis(10))),
+			migration: Some(MigrationReport {
+				stage: StageReport::pass(Duration::from_millis(20)),
+				output_path: Some(PathBuf::from("/tmp/migrated")),
+				movement_version: Some(7),
+				movement_aptos_version: Some(7),
+			}),
+			criteria: vec![
+				Evaluation::leaf("balances-equal", Verdict::Pass, None, Duration::from_millis(5)),
+				Evaluation::leaf(
+					"modules-equal",
+					Verdict::Fail,
+					Some("module <0x1::coin> differs".to_string()),
+					Duration::from_millis(5),
+				),
+			],
+		}
+	}
+
+	#[test]
+	fn test_junit() {
+		let report = report();
+		assert!(!report.passed());
+
+		let junit = report.to_junit();
+		assert!(junit.contains("tests=\"4\" failures=\"1\" errors=\"0\""));
+		assert!(junit.contains("<testcase classname=\"criteria\" name=\"balances-equal\""));
+		assert!(junit.contains("<failure message=\"module &lt;0x1::coin&gt; differs\">"));
+		assert!(junit.contains("output path: /tmp/migrated"));
+	}
+
+	#[test]
+	fn test_junit_without_stages() {
+		let report = CheckReport { prelude: None, migration: None, ..report() };
+		let junit = report.to_junit();
+		assert!(junit.contains("tests=\"2\" failures=\"1\" errors=\"0\""));
+		assert!(!junit.contains("name=\"stages\""));
+	}
+
+	#[test]
+	fn test_junit_migration_error() {
+		let report = CheckReport::migration_error(
+			StageReport::pass(Duration::from_millis(10)),
+			"failed to run migration",
+			Duration::from_millis(20),
+		);
+		assert!(!report.passed());
+
+		let junit = report.to_junit();
+		assert!(junit.contains("tests=\"2\" failures=\"0\" errors=\"1\""));
+		assert!(junit.contains("<error message=\"failed to run migration\">"));
+	}
+
+	#[test]
+	fn test_json_round_trip() -> Result<(), anyhow::Error> {
+		let report = report();
+		let json = report.to_json()?;
+		let parsed: CheckReport = serde_json::from_str(&json)?;
+		assert_eq!(parsed.criteria.len(), 2);
+		assert_eq!(
+			parsed.migration.and_then(|migration| migration.movement_aptos_version),
+			Some(7)
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,250 @@
+pub mod resources;
+
+use crate::criterion::movement_aptos_executor::TStateView as _;
+use crate::criterion::movement_executor::TStateView as _;
+use crate::criterion::{MovementAptosNode, MovementNode};
+use anyhow::Context;
+use mtma_types::movement::aptos_storage_interface::state_view::DbStateView as MovementStateView;
+use mtma_types::movement::aptos_types::state_store::state_key::StateKey as MovementStateKey;
+pub use mtma_types::movement_aptos::aptos_sdk::move_types::language_storage::{
+	ModuleId, StructTag,
+};
+use mtma_types::movement_aptos::aptos_storage_interface::state_store::state_view::db_state_view::DbStateView as MovementAptosStateView;
+use mtma_types::movement_aptos::aptos_types::access_path::Path;
+pub use mtma_types::movement_aptos::aptos_types::account_address::AccountAddress;
+use mtma_types::movement_aptos::aptos_types::state_store::state_key::inner::StateKeyInner;
+pub use mtma_types::movement_aptos::aptos_types::state_store::state_key::StateKey;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+
+/// Converts a Movement [MovementStateKey] into a Movement Aptos [StateKey].
+///
+/// Both sides encode state keys identically, so we go through the encoding.
+pub fn to_movement_aptos_state_key(key: &MovementStateKey) -> Result<StateKey, anyhow::Error> {
+	StateKey::decode(key.encoded().to_vec().as_slice())
+		.context("failed to decode movement state key as a movement aptos state key")
+}
+
+/// Converts a Movement Aptos [StateKey] into a Movement [MovementStateKey].
+pub fn to_movement_state_key(key: &StateKey) -> Result<MovementStateKey, anyhow::Error> {
+	MovementStateKey::decode(key.encoded().to_vec().as_slice())
+		.context("failed to decode movement aptos state key as a movement state key")
+}
+
+/// One side of the migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Side {
+	Movement,
+	MovementAptos,
+}
+
+impl Side {
+	/// Both sides, in order.
+	pub const BOTH: [Side; 2] = [Side::Movement, Side::MovementAptos];
+
+	/// The other side.
+	pub fn other(self) -> Side {
+		match self {
+			Side::Movement => Side::MovementAptos,
+			Side::MovementAptos => Side::Movement,
+		}
+	}
+}
+
+impl std::fmt::Display for Side {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Side::Movement => write!(f, "Movement"),
+			Side::MovementAptos => write!(f, "Movement Aptos"),
+		}
+	}
+}
+
+/// What a [StateKey] refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateKeyKind {
+	/// Published module bytecode.
+	Module(ModuleId),
+	/// A resource stored standalone under an account.
+	Resource(AccountAddress, StructTag),
+	/// A resource group stored as a single value under an account.
+	ResourceGroup(AccountAddress, StructTag),
+	/// A table item or a raw key.
+	Other,
+}
+
+impl StateKeyKind {
+	/// Classifies a [StateKey].
+	pub fn of(key: &StateKey) -> Self {
+		match key.inner() {
+			StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+				Path::Code(module_id) => Self::Module(module_id),
+				Path::Resource(struct_tag) => Self::Resource(access_path.address, struct_tag),
+				Path::ResourceGroup(struct_tag) => {
+					Self::ResourceGroup(access_path.address, struct_tag)
+				}
+			},
+			_ => Self::Other,
+		}
+	}
+}
+
+/// Collects the unique [StateKey]s written on the Movement side.
+///
+/// Keys are written many times over the history of the ledger, so this deduplicates them.
+/// The filter is applied before collecting so that callers can keep memory down on large dbs.
+pub fn movement_state_keys(
+	movement_node: &MovementNode,
+	filter: impl Fn(&StateKey) -> bool,
+) -> Result<HashSet<StateKey>, anyhow::Error> {
+	let iterable = movement_node.global_state_keys_from_version(None);
+	let mut keys = HashSet::new();
+	for key in iterable.iter()? {
+		let key = to_movement_aptos_state_key(&key?)?;
+		if filter(&key) {
+			keys.insert(key);
+		}
+	}
+	Ok(keys)
+}
+
+/// Collects the unique [StateKey]s written on the Movement Aptos side.
+pub fn movement_aptos_state_keys(
+	movement_aptos_node: &MovementAptosNode,
+	filter: impl Fn(&StateKey) -> bool,
+) -> Result<HashSet<StateKey>, anyhow::Error> {
+	let iterable = movement_aptos_node.global_state_keys_from_version(None);
+	let mut keys = HashSet::new();
+	for key in iterable.iter()? {
+		let key = key?;
+		if filter(&key) {
+			keys.insert(key);
+		}
+	}
+	Ok(keys)
+}
+
+/// Collects the unique [StateKey]s written on one side.
+pub fn state_keys(
+	side: Side,
+	movement_node: &MovementNode,
+	movement_aptos_node: &MovementAptosNode,
+	filter: impl Fn(&StateKey) -> bool,
+) -> Result<HashSet<StateKey>, anyhow::Error> {
+	match side {
+		Side::Movement => movement_state_keys(movement_node, filter),
+		Side::MovementAptos => movement_aptos_state_keys(movement_aptos_node, filter),
+	}
+}
+
+/// A pair of state views, one for each side of the migration, at the same version.
+///
+/// All keys are expressed as Movement Aptos [StateKey]s and converted for the Movement side,
+/// so criteria only need to deal with one set of types.
+pub struct StateViewPair {
+	version: u64,
+	movement: MovementStateView,
+	movement_aptos: MovementAptosStateView,
+}
+
+impl StateViewPair {
+	/// Forms the pair of state views at a given version.
+	pub fn at_version(
+		movement_node: &MovementNode,
+		movement_aptos_node: &MovementAptosNode,
+		version: u64,
+	) -> Result<Self, anyhow::Error> {
+		let movement = movement_node
+			.state_view_at_version(Some(version))
+			.context("failed to get movement state view")?;
+		let movement_aptos = movement_aptos_node
+			.state_view_at_version(Some(version))
+			.context("failed to get movement aptos state view")?;
+		Ok(Self { version, movement, movement_aptos })
+	}
+
+	/// Forms the pair of state views at the latest Movement ledger version.
+	pub fn latest(
+		movement_node: &MovementNode,
+		movement_aptos_node: &MovementAptosNode,
+	) -> Result<Self, anyhow::Error> {
+		let version = movement_node
+			.latest_ledger_version()
+			.context("failed to get movement latest ledger version")?;
+		Self::at_version(movement_node, movement_aptos_node, version)
+	}
+
+	/// The version at which the state views are formed.
+	pub fn version(&self) -> u64 {
+		self.version
+	}
+
+	/// Gets the Movement state value bytes for a key.
+	pub fn movement_bytes(&self, key: &StateKey) -> Result<Option<Vec<u8>>, anyhow::Error> {
+		let movement_key = to_movement_state_key(key)?;
+		let bytes = self
+			.movement
+			.get_state_value_bytes(&movement_key)
+			.context("failed to get movement state value")?;
+		Ok(bytes.map(|bytes| bytes.to_vec()))
+	}
+
+	/// Gets the Movement Aptos state value bytes for a key.
+	pub fn movement_aptos_bytes(&self, key: &StateKey) -> Result<Option<Vec<u8>>, anyhow::Error> {
+		let bytes = self
+			.movement_aptos
+			.get_state_value_bytes(key)
+			.context("failed to get movement aptos state value")?;
+		Ok(bytes.map(|bytes| bytes.to_vec()))
+	}
+
+	/// Gets the Movement and Movement Aptos state value bytes for a key.
+	pub fn bytes(
+		&self,
+		key: &StateKey,
+	) -> Result<(Option<Vec<u8>>, Option<Vec<u8>>), anyhow::Error> {
+		Ok((self.movement_bytes(key)?, self.movement_aptos_bytes(key)?))
+	}
+
+	/// Decodes the Movement value for a key.
+	pub fn movement_value<T: DeserializeOwned>(
+		&self,
+		key: &StateKey,
+	) -> Result<Option<T>, anyhow::Error> {
+		self.movement_bytes(key)?
+			.map(|bytes| bcs::from_bytes(&bytes))
+			.transpose()
+			.context(format!("failed to decode movement value for {:?}", key))
+	}
+
+	/// Decodes the Movement Aptos value for a key.
+	pub fn movement_aptos_value<T: DeserializeOwned>(
+		&self,
+		key: &StateKey,
+	) -> Result<Option<T>, anyhow::Error> {
+		self.movement_aptos_bytes(key)?
+			.map(|bytes| bcs::from_bytes(&bytes))
+			.transpose()
+			.context(format!("failed to decode movement aptos value for {:?}", key))
+	}
+
+	/// Gets the state value bytes for a key on one side.
+	pub fn side_bytes(&self, side: Side, key: &StateKey) -> Result<Option<Vec<u8>>, anyhow::Error> {
+		match side {
+			Side::Movement => self.movement_bytes(key),
+			Side::MovementAptos => self.movement_aptos_bytes(key),
+		}
+	}
+
+	/// Decodes the value for a key on one side.
+	pub fn side_value<T: DeserializeOwned>(
+		&self,
+		side: Side,
+		key: &StateKey,
+	) -> Result<Option<T>, anyhow::Error> {
+		match side {
+			Side::Movement => self.movement_value(key),
+			Side::MovementAptos => self.movement_aptos_value(key),
+		}
+	}
+}
@@ -0,0 +1,541 @@
+//! Mirrors of Move resources which the criteria decode from raw state values.
+//!
+//! These follow the BCS layout of the framework structs, so they can be decoded on either side of the migration.
+use super::{AccountAddress, StateKey, StructTag};
+use anyhow::Context;
+use mtma_types::movement_aptos::aptos_crypto::HashValue;
+use mtma_types::movement_aptos::aptos_sdk::move_types::identifier::Identifier;
+use mtma_types::movement_aptos::aptos_sdk::move_types::language_storage::{
+	TypeTag, CORE_CODE_ADDRESS,
+};
+use mtma_types::movement_aptos::aptos_types::state_store::table::TableHandle;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Mirror of `0x1::copyable_any::Any`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Any {
+	pub type_name: String,
+	pub data: Vec<u8>,
+}
+
+/// Mirror of `0x1::code::UpgradePolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UpgradePolicy {
+	pub policy: u8,
+}
+
+impl UpgradePolicy {
+	pub const ARBITRARY: u8 = 0;
+	pub const COMPATIBLE: u8 = 1;
+	pub const IMMUTABLE: u8 = 2;
+}
+
+impl std::fmt::Display for UpgradePolicy {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.policy {
+			Self::ARBITRARY => write!(f, "arbitrary"),
+			Self::COMPATIBLE => write!(f, "compatible"),
+			Self::IMMUTABLE => write!(f, "immutable"),
+			policy => write!(f, "unknown({})", policy),
+		}
+	}
+}
+
+/// Mirror of `0x1::code::PackageDep`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageDep {
+	pub account: AccountAddress,
+	pub package_name: String,
+}
+
+/// Mirror of `0x1::code::ModuleMetadata`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleMetadata {
+	pub name: String,
+	pub source: Vec<u8>,
+	pub source_map: Vec<u8>,
+	pub extension: Option<Any>,
+}
+
+/// Mirror of `0x1::code::PackageMetadata`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageMetadata {
+	pub name: String,
+	pub upgrade_policy: UpgradePolicy,
+	pub upgrade_number: u64,
+	pub source_digest: String,
+	pub manifest: Vec<u8>,
+	pub modules: Vec<ModuleMetadata>,
+	pub deps: Vec<PackageDep>,
+	pub extension: Option<Any>,
+}
+
+/// Mirror of `0x1::code::PackageRegistry`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageRegistry {
+	pub packages: Vec<PackageMetadata>,
+}
+
+impl PackageRegistry {
+	/// The struct tag of `0x1::code::PackageRegistry`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("code", "PackageRegistry", vec![])
+	}
+
+	/// The state key of the registry published under an account.
+	pub fn state_key(address: AccountAddress) -> Result<StateKey, anyhow::Error> {
+		StateKey::resource(&address, &Self::struct_tag())
+	}
+}
+
+/// The struct tag of a framework struct without type arguments.
+fn framework_struct_tag(module: &str, name: &str, type_args: Vec<TypeTag>) -> StructTag {
+	StructTag {
+		address: CORE_CODE_ADDRESS,
+		module: Identifier::new(module).expect("framework module names are valid identifiers"),
+		name: Identifier::new(name).expect("framework struct names are valid identifiers"),
+		type_args,
+	}
+}
+
+/// Whether a struct tag names a given framework struct, ignoring type arguments.
+fn is_framework_struct(struct_tag: &StructTag, module: &str, name: &str) -> bool {
+	struct_tag.address == CORE_CODE_ADDRESS
+		&& struct_tag.module.as_str() == module
+		&& struct_tag.name.as_str() == name
+}
+
+/// The type tag of `0x1::aptos_coin::AptosCoin`.
+pub fn aptos_coin_type_tag() -> TypeTag {
+	TypeTag::Struct(Box::new(framework_struct_tag("aptos_coin", "AptosCoin", vec![])))
+}
+
+/// The address of the fungible asset metadata paired with `0x1::aptos_coin::AptosCoin`.
+pub fn aptos_coin_metadata_address() -> AccountAddress {
+	AccountAddress::from_hex_literal("0xa").expect("0xa is a valid address")
+}
+
+/// Mirror of `0x1::guid::ID`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Id {
+	pub creation_num: u64,
+	pub addr: AccountAddress,
+}
+
+/// Mirror of `0x1::guid::GUID`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guid {
+	pub id: Id,
+}
+
+/// Mirror of `0x1::event::EventHandle`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventHandle {
+	pub counter: u64,
+	pub guid: Guid,
+}
+
+/// Mirror of `0x1::coin::Coin`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coin {
+	pub value: u64,
+}
+
+/// Mirror of `0x1::coin::CoinStore`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinStore {
+	pub coin: Coin,
+	pub frozen: bool,
+	pub deposit_events: EventHandle,
+	pub withdraw_events: EventHandle,
+}
+
+impl CoinStore {
+	/// The struct tag of `0x1::coin::CoinStore<coin_type>`.
+	pub fn struct_tag(coin_type: TypeTag) -> StructTag {
+		framework_struct_tag("coin", "CoinStore", vec![coin_type])
+	}
+
+	/// The coin type if the struct tag is a `0x1::coin::CoinStore`.
+	pub fn coin_type(struct_tag: &StructTag) -> Option<&TypeTag> {
+		if is_framework_struct(struct_tag, "coin", "CoinStore") {
+			struct_tag.type_args.first()
+		} else {
+			None
+		}
+	}
+}
+
+/// Mirror of `0x1::aggregator::Aggregator`, whose value lives in a table item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatorV1 {
+	pub handle: AccountAddress,
+	pub key: AccountAddress,
+	pub limit: u128,
+}
+
+impl AggregatorV1 {
+	/// The state key of the table item holding the aggregator value.
+	pub fn state_key(&self) -> Result<StateKey, anyhow::Error> {
+		Ok(StateKey::table_item(&TableHandle(self.handle), &bcs::to_bytes(&self.key)?))
+	}
+}
+
+/// Mirror of `0x1::optional_aggregator::Integer`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Integer {
+	pub value: u128,
+	pub limit: u128,
+}
+
+/// Mirror of `0x1::optional_aggregator::OptionalAggregator`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionalAggregator {
+	pub aggregator: Option<AggregatorV1>,
+	pub integer: Option<Integer>,
+}
+
+/// Mirror of `0x1::coin::CoinInfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinInfo {
+	pub name: String,
+	pub symbol: String,
+	pub decimals: u8,
+	pub supply: Option<OptionalAggregator>,
+}
+
+impl CoinInfo {
+	/// The struct tag of `0x1::coin::CoinInfo<coin_type>`.
+	pub fn struct_tag(coin_type: TypeTag) -> StructTag {
+		framework_struct_tag("coin", "CoinInfo", vec![coin_type])
+	}
+}
+
+/// Mirror of `0x1::aggregator_v2::Aggregator`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatorV2<T> {
+	pub value: T,
+	pub max_value: T,
+}
+
+/// Mirror of `0x1::object::Object`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Object {
+	pub inner: AccountAddress,
+}
+
+/// Mirror of `0x1::object::ObjectCore`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectCore {
+	pub guid_creation_num: u64,
+	pub owner: AccountAddress,
+	pub allow_ungated_transfer: bool,
+	pub transfer_events: EventHandle,
+}
+
+impl ObjectCore {
+	/// The struct tag of `0x1::object::ObjectCore`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("object", "ObjectCore", vec![])
+	}
+}
+
+/// The members of a resource group, keyed by their struct tags.
+pub type ResourceGroup = BTreeMap<StructTag, Vec<u8>>;
+
+/// Helpers for `0x1::object::ObjectGroup`, the resource group objects and fungible assets live in.
+pub struct ObjectGroup;
+
+impl ObjectGroup {
+	/// The struct tag of `0x1::object::ObjectGroup`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("object", "ObjectGroup", vec![])
+	}
+
+	/// Decodes a member of a resource group, if present.
+	pub fn member<T: DeserializeOwned>(
+		group: &ResourceGroup,
+		struct_tag: &StructTag,
+	) -> Result<Option<T>, anyhow::Error> {
+		group
+			.get(struct_tag)
+			.map(|bytes| bcs::from_bytes(bytes))
+			.transpose()
+			.context(format!("failed to decode resource group member {}", struct_tag))
+	}
+}
+
+/// Mirror of `0x1::fungible_asset::FungibleStore`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FungibleStore {
+	pub metadata: Object,
+	pub balance: u64,
+	pub frozen: bool,
+}
+
+impl FungibleStore {
+	/// The struct tag of `0x1::fungible_asset::FungibleStore`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("fungible_asset", "FungibleStore", vec![])
+	}
+}
+
+/// Mirror of `0x1::fungible_asset::ConcurrentFungibleBalance`.
+///
+/// When present, the balance lives here and the [FungibleStore] balance is zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrentFungibleBalance {
+	pub balance: AggregatorV2<u64>,
+}
+
+impl ConcurrentFungibleBalance {
+	/// The struct tag of `0x1::fungible_asset::ConcurrentFungibleBalance`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("fungible_asset", "ConcurrentFungibleBalance", vec![])
+	}
+}
+
+/// Mirror of `0x1::fungible_asset::Metadata`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+	pub name: String,
+	pub symbol: String,
+	pub decimals: u8,
+	pub icon_uri: String,
+	pub project_uri: String,
+}
+
+impl Metadata {
+	/// The struct tag of `0x1::fungible_asset::Metadata`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("fungible_asset", "Metadata", vec![])
+	}
+}
+
+/// Mirror of `0x1::fungible_asset::Supply`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Supply {
+	pub current: u128,
+	pub maximum: Option<u128>,
+}
+
+impl Supply {
+	/// The struct tag of `0x1::fungible_asset::Supply`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("fungible_asset", "Supply", vec![])
+	}
+}
+
+/// Mirror of `0x1::fungible_asset::ConcurrentSupply`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrentSupply {
+	pub current: AggregatorV2<u128>,
+}
+
+impl ConcurrentSupply {
+	/// The struct tag of `0x1::fungible_asset::ConcurrentSupply`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("fungible_asset", "ConcurrentSupply", vec![])
+	}
+}
+
+/// Mirror of `0x1::stake::StakePool`, which holds coins outside of any coin store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakePool {
+	pub active: Coin,
+	pub inactive: Coin,
+	pub pending_active: Coin,
+	pub pending_inactive: Coin,
+	pub locked_until_secs: u64,
+	pub operator_address: AccountAddress,
+	pub delegated_voter: AccountAddress,
+	pub initialize_validator_events: EventHandle,
+	pub set_operator_events: EventHandle,
+	pub add_stake_events: EventHandle,
+	pub reactivate_stake_events: EventHandle,
+	pub rotate_consensus_key_events: EventHandle,
+	pub update_network_and_fullnode_addresses_events: EventHandle,
+	pub increase_lockup_events: EventHandle,
+	pub join_validator_set_events: EventHandle,
+	pub distribute_rewards_events: EventHandle,
+	pub unlock_stake_events: EventHandle,
+	pub withdraw_stake_events: EventHandle,
+	pub leave_validator_set_events: EventHandle,
+}
+
+impl StakePool {
+	/// The struct tag of `0x1::stake::StakePool`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("stake", "StakePool", vec![])
+	}
+
+	/// The total value of the coins held by the pool.
+	pub fn total(&self) -> u128 {
+		[&self.active, &self.inactive, &self.pending_active, &self.pending_inactive]
+			.iter()
+			.map(|coin| u128::from(coin.value))
+			.sum()
+	}
+}
+
+/// Mirror of `0x1::table::Table`, whose items live under the handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table {
+	pub handle: AccountAddress,
+}
+
+impl Table {
+	/// The state key of the item stored under a key.
+	pub fn item_state_key<K: Serialize>(&self, key: &K) -> Result<StateKey, anyhow::Error> {
+		Ok(StateKey::table_item(&TableHandle(self.handle), &bcs::to_bytes(key)?))
+	}
+}
+
+/// Mirror of `0x1::type_info::TypeInfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeInfo {
+	pub account_address: AccountAddress,
+	pub module_name: Vec<u8>,
+	pub struct_name: Vec<u8>,
+}
+
+impl TypeInfo {
+	/// The type info of a struct type, as `0x1::type_info::type_of` would report it.
+	pub fn of(type_tag: &TypeTag) -> Option<Self> {
+		let TypeTag::Struct(struct_tag) = type_tag else {
+			return None;
+		};
+
+		let mut struct_name = struct_tag.name.to_string();
+		if !struct_tag.type_args.is_empty() {
+			let type_args: Vec<String> = struct_tag
+				.type_args
+				.iter()
+				.map(|type_arg| type_arg.to_canonical_string())
+				.collect();
+			struct_name = format!("{}<{}>", struct_name, type_args.join(", "));
+		}
+
+		Some(Self {
+			account_address: struct_tag.address,
+			module_name: struct_tag.module.as_bytes().to_vec(),
+			struct_name: struct_name.into_bytes(),
+		})
+	}
+}
+
+/// Mirror of `0x1::coin::CoinConversionMap`, which pairs coin types with fungible asset metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoinConversionMap {
+	pub coin_to_fungible_asset_map: Table,
+}
+
+impl CoinConversionMap {
+	/// The struct tag of `0x1::coin::CoinConversionMap`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("coin", "CoinConversionMap", vec![])
+	}
+
+	/// The state key of the map, which is published under `0x1`.
+	pub fn state_key() -> Result<StateKey, anyhow::Error> {
+		StateKey::resource(&CORE_CODE_ADDRESS, &Self::struct_tag())
+	}
+}
+
+/// The address of the primary fungible store of an owner for a metadata object.
+///
+/// Primary stores are objects derived from the owner and the metadata address,
+/// see `0x1::object::create_user_derived_object_address` as used by `0x1::primary_fungible_store`.
+pub fn primary_store_address(owner: AccountAddress, metadata: AccountAddress) -> AccountAddress {
+	const OBJECT_DERIVED_SCOPE: u8 = 0xFC;
+	let mut bytes = owner.to_vec();
+	bytes.extend(metadata.to_vec());
+	bytes.push(OBJECT_DERIVED_SCOPE);
+	AccountAddress::new(*HashValue::sha3_256_of(&bytes))
+}
+
+/// Mirror of `0x1::account::CapabilityOffer`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityOffer {
+	pub for_address: Option<AccountAddress>,
+}
+
+/// Mirror of `0x1::account::Account`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+	pub authentication_key: Vec<u8>,
+	pub sequence_number: u64,
+	pub guid_creation_num: u64,
+	pub coin_register_events: EventHandle,
+	pub key_rotation_events: EventHandle,
+	pub rotation_capability_offer: CapabilityOffer,
+	pub signer_capability_offer: CapabilityOffer,
+}
+
+impl Account {
+	/// The struct tag of `0x1::account::Account`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("account", "Account", vec![])
+	}
+}
+
+/// Mirror of `0x1::account::OriginatingAddress`, which maps rotated authentication keys back to accounts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OriginatingAddress {
+	pub address_map: Table,
+}
+
+impl OriginatingAddress {
+	/// The struct tag of `0x1::account::OriginatingAddress`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("account", "OriginatingAddress", vec![])
+	}
+
+	/// The state key of the map, which is published under `0x1`.
+	pub fn state_key() -> Result<StateKey, anyhow::Error> {
+		StateKey::resource(&CORE_CODE_ADDRESS, &Self::struct_tag())
+	}
+}
+
+/// Mirror of `0x1::block::BlockResource`, which holds the block height and the epoch interval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockResource {
+	pub height: u64,
+	pub epoch_interval: u64,
+	pub new_block_events: EventHandle,
+	pub update_epoch_interval_events: EventHandle,
+}
+
+impl BlockResource {
+	/// The struct tag of `0x1::block::BlockResource`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("block", "BlockResource", vec![])
+	}
+
+	/// The state key of the resource, which is published under `0x1`.
+	pub fn state_key() -> Result<StateKey, anyhow::Error> {
+		StateKey::resource(&CORE_CODE_ADDRESS, &Self::struct_tag())
+	}
+}
+
+/// Mirror of `0x1::reconfiguration::Configuration`, which holds the current epoch and when it started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Configuration {
+	pub epoch: u64,
+	pub last_reconfiguration_time: u64,
+	pub events: EventHandle,
+}
+
+impl Configuration {
+	/// The struct tag of `0x1::reconfiguration::Configuration`.
+	pub fn struct_tag() -> StructTag {
+		framework_struct_tag("reconfiguration", "Configuration", vec![])
+	}
+
+	/// The state key of the resource, which is published under `0x1`.
+	pub fn state_key() -> Result<StateKey, anyhow::Error> {
+		StateKey::resource(&CORE_CODE_ADDRESS, &Self::struct_tag())
+	}
+}
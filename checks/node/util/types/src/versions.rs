@@ -0,0 +1,110 @@
+use crate::criterion::MovementNode;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Errors thrown when working with [Versions].
+#[derive(Debug, thiserror::Error)]
+pub enum VersionsError {
+	#[error("invalid version selection: {0}")]
+	Invalid(#[source] Box<dyn std::error::Error + Send + Sync>),
+	#[error("failed to resolve versions: {0}")]
+	Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// A selection of ledger versions at which a criterion compares state.
+///
+/// By default only the latest ledger version is selected.
+/// Migrations which preserve history, such as null and replay, should also agree at older versions,
+/// so criteria can be asked to check a stride of versions and each epoch boundary as well.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Versions {
+	/// Whether to include the latest ledger version.
+	pub latest: bool,
+	/// Explicit versions to include.
+	pub versions: BTreeSet<u64>,
+	/// Include every `stride`-th version starting from genesis.
+	pub stride: Option<u64>,
+	/// Whether to include the last version of each epoch which has ended.
+	pub epoch_boundaries: bool,
+}
+
+impl Default for Versions {
+	fn default() -> Self {
+		Self::latest()
+	}
+}
+
+impl Versions {
+	/// Selects only the latest ledger version.
+	pub fn latest() -> Self {
+		Self { latest: true, versions: BTreeSet::new(), stride: None, epoch_boundaries: false }
+	}
+
+	/// Builder API: sets whether the latest ledger version is included.
+	pub fn with_latest(mut self, latest: bool) -> Self {
+		self.latest = latest;
+		self
+	}
+
+	/// Builder API: adds explicit versions.
+	pub fn with_versions(mut self, versions: impl IntoIterator<Item = u64>) -> Self {
+		self.versions.extend(versions);
+		self
+	}
+
+	/// Builder API: sets the stride.
+	pub fn with_stride(mut self, stride: u64) -> Self {
+		self.stride = Some(stride);
+		self
+	}
+
+	/// Builder API: sets whether epoch boundaries are included.
+	pub fn with_epoch_boundaries(mut self, epoch_boundaries: bool) -> Self {
+		self.epoch_boundaries = epoch_boundaries;
+		self
+	}
+
+	/// Resolves the selection against the Movement node.
+	///
+	/// Versions beyond the latest ledger version are dropped, so a selection can be reused across nodes of different heights.
+	pub fn resolve(&self, movement_node: &MovementNode) -> Result<BTreeSet<u64>, VersionsError> {
+		let latest_ledger_version = movement_node
+			.latest_ledger_version()
+			.context("failed to get latest ledger version")
+			.map_err(|e| VersionsError::Internal(e.into()))?;
+
+		let mut versions: BTreeSet<u64> = self
+			.versions
+			.iter()
+			.copied()
+			.filter(|version| *version <= latest_ledger_version)
+			.collect();
+
+		if self.latest {
+			versions.insert(latest_ledger_version);
+		}
+
+		if let Some(stride) = self.stride {
+			if stride == 0 {
+				return Err(VersionsError::Invalid("stride must be greater than 0".into()));
+			}
+			versions.extend((0..=latest_ledger_version).step_by(stride as usize));
+		}
+
+		if self.epoch_boundaries {
+			let epoch_ending_versions = movement_node
+				.epoch_ending_versions()
+				.context("failed to get epoch ending versions")
+				.map_err(|e| VersionsError::Internal(e.into()))?;
+			versions.extend(
+				epoch_ending_versions
+					.into_iter()
+					.filter(|version| *version <= latest_ledger_version),
+			);
+		}
+
+		Ok(versions)
+	}
+}
@@ -3,9 +3,9 @@ use clap_markdown_ext::Markdown;
 
 pub mod all;
 pub mod all_of;
-pub mod global_storage_includes;
-pub mod global_storage_injective;
-pub mod global_storage_not_empty;
+pub mod list;
+pub mod matching_feature_flags;
+pub mod snapshot;
 
 /// The `mtma-check-dev` CLI.
 #[derive(Parser)]
@@ -28,15 +28,14 @@ pub enum MtmaCheckDevSubcommand {
 	/// Check all of the specified criteria.
 	#[clap(subcommand)]
 	AllOf(all_of::or_file::AllOfCheck),
-	/// Check the Global Storage Includes criterion.
+	/// List the criteria which can be checked by name.
+	List(list::List),
+	/// Check the Matching Feature Flags criterion.
 	#[clap(subcommand)]
-	GlobalStorageIncludes(global_storage_includes::or_file::GlobalStorageIncludesCheck),
-	/// Check the Global Storage Injective criterion.
+	MatchingFeatureFlags(matching_feature_flags::or_file::MatchingFeatureFlagsCheck),
+	/// Check criteria against existing Movement and Movement Aptos DBs, without migrating.
 	#[clap(subcommand)]
-	GlobalStorageInjective(global_storage_injective::or_file::GlobalStorageInjectiveCheck),
-	/// Check the Global Storage Not Empty criterion.
-	#[clap(subcommand)]
-	GlobalStorageNotEmpty(global_storage_not_empty::or_file::GlobalStorageNotEmptyCheck),
+	Snapshot(snapshot::or_file::SnapshotCheck),
 }
 
 /// Implement the `From` trait for `MtmaCheckDev` to convert it into a `MtmaCheckDevSubcommand`.
@@ -64,9 +63,9 @@ impl MtmaCheckDevSubcommand {
 			}
 			MtmaCheckDevSubcommand::All(check) => check.execute().await?,
 			MtmaCheckDevSubcommand::AllOf(check) => check.execute().await?,
-			MtmaCheckDevSubcommand::GlobalStorageIncludes(check) => check.execute().await?,
-			MtmaCheckDevSubcommand::GlobalStorageInjective(check) => check.execute().await?,
-			MtmaCheckDevSubcommand::GlobalStorageNotEmpty(check) => check.execute().await?,
+			MtmaCheckDevSubcommand::List(list) => list.execute().await?,
+			MtmaCheckDevSubcommand::MatchingFeatureFlags(check) => check.execute().await?,
+			MtmaCheckDevSubcommand::Snapshot(check) => check.execute().await?,
 		}
 		Ok(())
 	}
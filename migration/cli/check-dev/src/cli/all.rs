@@ -1,15 +1,50 @@
+use crate::cli::all_of::{check_all_of, shared_parameters};
 use clap::Parser;
+use mtma_migrator_test_registry::registry;
+use mtma_migrator_test_types::registry::Parameter;
+use mtma_node_test_types::prelude::Prelude;
 use orfile::Orfile;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Checks all supported criteria.
 #[derive(Parser, Serialize, Deserialize, Debug, Clone, Orfile)]
 #[clap(help_expected = true)]
-pub struct AllCheck {}
+pub struct AllCheck {
+	/// A prelude to run before migrating, as saved BCS (`.bcs`) or JSON lines. Defaults to an empty prelude.
+	#[clap(long)]
+	#[serde(default)]
+	pub prelude: Option<PathBuf>,
+	/// Parameters passed to the criteria, as `<criterion>.<name>=<value>`.
+	#[clap(long = "parameter")]
+	#[serde(default)]
+	pub parameters: Vec<Parameter>,
+	/// A YAML or JSON file of divergences the migration is expected to introduce, passed to every criterion which accepts them.
+	#[clap(long)]
+	#[serde(default)]
+	pub divergences: Option<PathBuf>,
+	/// Writes the report of the check as JSON to this path.
+	#[clap(long)]
+	#[serde(default)]
+	pub report_json: Option<PathBuf>,
+	/// Writes the report of the check as JUnit XML to this path.
+	#[clap(long)]
+	#[serde(default)]
+	pub report_junit: Option<PathBuf>,
+}
 
 impl AllCheck {
 	pub async fn execute(&self) -> Result<(), anyhow::Error> {
-		unimplemented!()
+		let parameters = shared_parameters(&self.parameters, self.divergences.as_deref());
+		let criteria = registry().build_all(&parameters)?;
+		let prelude = self
+			.prelude
+			.as_deref()
+			.map(Prelude::load)
+			.transpose()?
+			.unwrap_or_else(Prelude::new_empty);
+		check_all_of(criteria, &prelude, self.report_json.as_deref(), self.report_junit.as_deref())
+			.await
 	}
 }
 
@@ -1,15 +1,58 @@
 use clap::Parser;
+use mtma_migrator_test_registry::registry;
+use mtma_migrator_test_types::check::{checked_migration, CheckError, CheckReport};
+use mtma_migrator_test_types::combinator::{BoxedEvaluatish, Named};
+use mtma_migrator_test_types::divergence::DIVERGENCES;
+use mtma_migrator_test_types::registry::Parameter;
+use mtma_migrator_types::migrator::MovementMigrator;
+use mtma_node_null_core::config::Config as MtmaNullConfig;
+use mtma_node_test_types::prelude::Prelude;
 use orfile::Orfile;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
 
 /// Checks all of the criteria provided.
 #[derive(Parser, Serialize, Deserialize, Debug, Clone, Orfile)]
 #[clap(help_expected = true)]
-pub struct AllOfCheck {}
+pub struct AllOfCheck {
+	/// The names of the criteria to check, see `list`.
+	#[clap(long, value_delimiter = ',', required = true)]
+	pub criteria: Vec<String>,
+	/// A prelude to run before migrating, as saved BCS (`.bcs`) or JSON lines. Defaults to an empty prelude.
+	#[clap(long)]
+	#[serde(default)]
+	pub prelude: Option<PathBuf>,
+	/// Parameters passed to the criteria, as `<criterion>.<name>=<value>`.
+	#[clap(long = "parameter")]
+	#[serde(default)]
+	pub parameters: Vec<Parameter>,
+	/// A YAML or JSON file of divergences the migration is expected to introduce, passed to every criterion which accepts them.
+	#[clap(long)]
+	#[serde(default)]
+	pub divergences: Option<PathBuf>,
+	/// Writes the report of the check as JSON to this path.
+	#[clap(long)]
+	#[serde(default)]
+	pub report_json: Option<PathBuf>,
+	/// Writes the report of the check as JUnit XML to this path.
+	#[clap(long)]
+	#[serde(default)]
+	pub report_junit: Option<PathBuf>,
+}
 
 impl AllOfCheck {
 	pub async fn execute(&self) -> Result<(), anyhow::Error> {
-		unimplemented!()
+		let parameters = shared_parameters(&self.parameters, self.divergences.as_deref());
+		let criteria = registry().build(&self.criteria, &parameters)?;
+		let prelude = self
+			.prelude
+			.as_deref()
+			.map(Prelude::load)
+			.transpose()?
+			.unwrap_or_else(Prelude::new_empty);
+		check_all_of(criteria, &prelude, self.report_json.as_deref(), self.report_junit.as_deref())
+			.await
 	}
 }
 
@@ -19,3 +62,62 @@ impl or_file::AllOfCheck {
 		inner.execute().await
 	}
 }
+
+/// Checks the criteria against the local Movement state, after running the prelude.
+///
+/// The report is written to the given paths whether or not the criteria are satisfied, and when the prelude or the migration could not complete.
+pub async fn check_all_of(
+	criteria: Vec<BoxedEvaluatish>,
+	prelude: &Prelude,
+	report_json: Option<&Path>,
+	report_junit: Option<&Path>,
+) -> Result<(), anyhow::Error> {
+	// form the criteria, each reported as its own test case
+	let criteria: Vec<_> = criteria
+		.into_iter()
+		.map(|criterion| Named::new(criterion.name(), criterion))
+		.collect();
+
+	// form the migrator
+	let mut movement_migrator = MovementMigrator::try_debug_home()?;
+
+	// form the migration
+	let migration = MtmaNullConfig::default().build()?;
+
+	// run the checked migration
+	let result = checked_migration(&mut movement_migrator, prelude, &migration, criteria).await;
+
+	// write the report, including when the criteria were not satisfied or a stage could not complete
+	if let Some(report) = result.as_ref().map_or_else(CheckError::report, Some) {
+		write_report(report, report_json, report_junit)?;
+	}
+	result?;
+
+	Ok(())
+}
+
+/// Adds the divergences file, if any, to the parameters as shared by every criterion.
+pub fn shared_parameters(parameters: &[Parameter], divergences: Option<&Path>) -> Vec<Parameter> {
+	let mut parameters = parameters.to_vec();
+	if let Some(path) = divergences {
+		parameters.push(Parameter::shared(DIVERGENCES, path.display().to_string()));
+	}
+	parameters
+}
+
+/// Writes the report of a check to the given paths.
+pub fn write_report(
+	report: &CheckReport,
+	json: Option<&Path>,
+	junit: Option<&Path>,
+) -> Result<(), anyhow::Error> {
+	if let Some(path) = json {
+		info!("Writing JSON report to {}", path.display());
+		std::fs::write(path, report.to_json()?)?;
+	}
+	if let Some(path) = junit {
+		info!("Writing JUnit report to {}", path.display());
+		std::fs::write(path, report.to_junit())?;
+	}
+	Ok(())
+}
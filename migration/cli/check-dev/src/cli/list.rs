@@ -0,0 +1,25 @@
+use clap::Parser;
+use mtma_migrator_test_registry::registry;
+
+/// Lists the criteria which can be checked by name, along with their parameters.
+#[derive(Parser, Debug, Clone)]
+#[clap(help_expected = true)]
+pub struct List {}
+
+impl List {
+	pub async fn execute(&self) -> Result<(), anyhow::Error> {
+		for registration in registry().registrations() {
+			println!("{}: {}", registration.name(), registration.description());
+			for parameter in registration.parameters() {
+				println!(
+					"  --parameter {}.{}=<value> (default: {}): {}",
+					registration.name(),
+					parameter.name,
+					parameter.default,
+					parameter.description
+				);
+			}
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,58 @@
+use clap::Parser;
+use mtma_migrator_test_matching_feature_flags_criterion::Config;
+use mtma_migrator_test_types::check::checked_migration;
+use mtma_migrator_test_types::combinator::Leaf;
+use mtma_migrator_types::migrator::MovementMigrator;
+use mtma_node_null_core::config::Config as MtmaNullConfig;
+use mtma_node_test_types::prelude::Prelude;
+use orfile::Orfile;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Checks the Matching Feature Flags criterion against the local Movement state.
+#[derive(Parser, Serialize, Deserialize, Debug, Clone, Orfile)]
+#[clap(help_expected = true)]
+pub struct MatchingFeatureFlagsCheck {
+	/// The feature-flag contract to check.
+	#[orfile(config)]
+	#[clap(flatten)]
+	pub config: Config,
+	/// A prelude to run before migrating, as saved BCS (`.bcs`) or JSON lines. Defaults to an empty prelude.
+	#[clap(long)]
+	#[serde(default)]
+	pub prelude: Option<PathBuf>,
+}
+
+impl MatchingFeatureFlagsCheck {
+	pub async fn execute(&self) -> Result<(), anyhow::Error> {
+		// form the criterion
+		let criterion = self.config.build()?;
+
+		// form the migrator
+		let mut movement_migrator = MovementMigrator::try_debug_home()?;
+
+		// form the prelude
+		let prelude = self
+			.prelude
+			.as_deref()
+			.map(Prelude::load)
+			.transpose()?
+			.unwrap_or_else(Prelude::new_empty);
+
+		// form the migration
+		let migration = MtmaNullConfig::default().build()?;
+
+		// run the checked migration
+		checked_migration(&mut movement_migrator, &prelude, &migration, vec![Leaf::new(criterion)])
+			.await?;
+
+		Ok(())
+	}
+}
+
+impl or_file::MatchingFeatureFlagsCheck {
+	pub async fn execute(&self) -> Result<(), anyhow::Error> {
+		let inner = self.clone().resolve().await?;
+		inner.execute().await
+	}
+}
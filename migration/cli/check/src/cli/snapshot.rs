@@ -0,0 +1,91 @@
+use crate::cli::all_of::{shared_parameters, write_report};
+use anyhow::Context;
+use clap::Parser;
+use mtma_migrator_test_registry::node_registry;
+use mtma_migrator_test_types::registry::Parameter;
+use mtma_node_test_types::check::{checked_snapshot, CheckError};
+use mtma_node_test_types::combinator::Named;
+use mtma_node_test_types::criterion::{AsyncCriterionish, MovementAptosNode, MovementNode};
+use orfile::Orfile;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Checks criteria against an existing Movement DB and an already migrated Movement Aptos DB.
+#[derive(Parser, Serialize, Deserialize, Debug, Clone, Orfile)]
+#[clap(help_expected = true)]
+pub struct SnapshotCheck {
+	/// The path to the Movement state, i.e. the directory containing `.movement`.
+	///
+	/// The state is opened read-only, so it is neither copied nor modified.
+	#[clap(long)]
+	pub movement_path: PathBuf,
+	/// The path to the migrated Movement Aptos DB, which is opened read-only.
+	#[clap(long)]
+	pub movement_aptos_db_path: PathBuf,
+	/// The names of the node-level criteria to check, see `list`. Defaults to all of them.
+	#[clap(long, value_delimiter = ',')]
+	#[serde(default)]
+	pub criteria: Vec<String>,
+	/// Parameters passed to the criteria, as `<criterion>.<name>=<value>`.
+	#[clap(long = "parameter")]
+	#[serde(default)]
+	pub parameters: Vec<Parameter>,
+	/// A YAML or JSON file of divergences the migration is expected to introduce, passed to every criterion which accepts them.
+	#[clap(long)]
+	#[serde(default)]
+	pub divergences: Option<PathBuf>,
+	/// Writes the report of the check as JSON to this path.
+	#[clap(long)]
+	#[serde(default)]
+	pub report_json: Option<PathBuf>,
+	/// Writes the report of the check as JUnit XML to this path.
+	#[clap(long)]
+	#[serde(default)]
+	pub report_junit: Option<PathBuf>,
+}
+
+impl SnapshotCheck {
+	pub async fn execute(&self) -> Result<(), anyhow::Error> {
+		// form the criteria
+		let registry = node_registry();
+		let parameters = shared_parameters(&self.parameters, self.divergences.as_deref());
+		let criteria = if self.criteria.is_empty() {
+			registry.build_all(&parameters)?
+		} else {
+			registry.build(&self.criteria, &parameters)?
+		};
+		let criteria: Vec<Box<dyn AsyncCriterionish + Send + Sync>> = criteria
+			.into_iter()
+			.map(|criterion| {
+				Box::new(Named::new(criterion.name(), criterion))
+					as Box<dyn AsyncCriterionish + Send + Sync>
+			})
+			.collect();
+
+		// open the nodes
+		let movement_node = MovementNode::try_read_only(self.movement_path.clone())
+			.await
+			.context("failed to open the movement node")?;
+		let movement_aptos_node =
+			MovementAptosNode::try_read_only(self.movement_aptos_db_path.clone())
+				.context("failed to open the movement aptos node")?;
+
+		// check the snapshot
+		let result = checked_snapshot(&movement_node, &movement_aptos_node, criteria).await;
+
+		// write the report, including when the criteria were not satisfied
+		if let Some(report) = result.as_ref().map_or_else(CheckError::report, Some) {
+			write_report(report, self.report_json.as_deref(), self.report_junit.as_deref())?;
+		}
+		result?;
+
+		Ok(())
+	}
+}
+
+impl or_file::SnapshotCheck {
+	pub async fn execute(&self) -> Result<(), anyhow::Error> {
+		let inner = self.clone().resolve().await?;
+		inner.execute().await
+	}
+}
@@ -53,6 +53,9 @@ pub struct MigrateChecked {
 	/// The string identifying the download source if necessary
 	#[clap(long)]
 	pub state_source: Option<String>,
+	/// A prelude to run before migrating, as saved BCS (`.bcs`) or JSON lines. Defaults to an empty prelude.
+	#[clap(long)]
+	pub prelude: Option<PathBuf>,
 }
 
 impl MigrateChecked {
@@ -94,8 +97,13 @@ impl MigrateChecked {
 		let movement_opt_executor = MovementOptExecutor::try_from_config(config, sender).await?;
 		let mut movement_executor = MovementNode::new(movement_opt_executor);
 
-		// empty prelude
-		let prelude = Prelude::new_empty();
+		// form the prelude, empty unless one was given
+		let prelude = self
+			.prelude
+			.as_deref()
+			.map(Prelude::load)
+			.transpose()?
+			.unwrap_or_else(Prelude::new_empty);
 
 		// form the migration
 		let migration_config = MtmaNullConfig::default();
@@ -48,8 +48,10 @@ impl Migrationish for Migrate {
 		movement_executor: &MovementNode,
 	) -> Result<MovementAptosNode, MigrationError> {
 		// Get the db path from the opt executor.
-		let old_db_dir = movement_executor
+		let opt_executor = movement_executor
 			.opt_executor()
+			.map_err(|e| MigrationError::Internal(e.into()))?;
+		let old_db_dir = opt_executor
 			.config
 			.chain
 			.maptos_db_path
@@ -80,12 +82,8 @@ impl Migrationish for Migrate {
 			Default::default(),
 			Default::default(),
 			false,
-			movement_executor.opt_executor().node_config.storage.buffered_state_target_items,
-			movement_executor
-				.opt_executor()
-				.node_config
-				.storage
-				.max_num_nodes_per_lru_cache_shard,
+			opt_executor.node_config.storage.buffered_state_target_items,
+			opt_executor.node_config.storage.max_num_nodes_per_lru_cache_shard,
 			None,
 		)
 		.context("failed to open aptos db")
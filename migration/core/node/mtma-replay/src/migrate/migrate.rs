@@ -75,7 +75,9 @@ impl Migrationish for Migrate {
 		&self,
 		movement_node: &MovementNode,
 	) -> Result<MovementAptosNode, MigrationError> {
-		// open up a new db
+		// open up a new db, sized as the movement node's
+		let opt_executor =
+			movement_node.opt_executor().map_err(|e| MigrationError::Internal(e.into()))?;
 		let unique_id = uuid::Uuid::new_v4();
 		let timestamp = chrono::Utc::now().timestamp_millis();
 		let db_dir = Path::new(".debug").join(format!(
@@ -89,12 +91,8 @@ impl Migrationish for Migrate {
 			Default::default(),
 			Default::default(),
 			false,
-			movement_node.opt_executor().node_config.storage.buffered_state_target_items,
-			movement_node
-				.opt_executor()
-				.node_config
-				.storage
-				.max_num_nodes_per_lru_cache_shard,
+			opt_executor.node_config.storage.buffered_state_target_items,
+			opt_executor.node_config.storage.max_num_nodes_per_lru_cache_shard,
 			None,
 		)
 		.context("failed to open aptos db")
@@ -115,10 +115,16 @@ impl MovementAptosMigrator {
 	}
 
 	/// Gets a [MovementAptosNode] from the runner.
+	///
+	/// The db is opened read-only, so this can be called while the runner is running.
 	pub async fn node(&self) -> Result<MovementAptosNode, anyhow::Error> {
-		todo!()
+		match &self.runner {
+			Runner::MovementAptos(movement_aptos) => {
+				let db_dir = movement_aptos.node_config().storage.dir();
+				MovementAptosNode::try_read_only(db_dir)
+			}
+		}
 	}
-
 }
 
 impl TryFrom<NodeConfig> for MovementAptosMigrator {
@@ -88,6 +88,16 @@ impl MovementMigrator {
 		Ok(rest_client)
 	}
 
+	/// Loads the raw private key of the root account, e.g. to sign a prelude which funds accounts.
+	pub fn try_root_private_key(&self) -> Result<Vec<u8>, anyhow::Error> {
+		match &self.runner {
+			Runner::Movement(movement) => movement
+				.movement_signer_identifier()
+				.try_raw_private_key()
+				.context("failed to load raw root private key"),
+		}
+	}
+
 	/// Produces the [MovementNode] from the runner.
 	pub async fn node(&self) -> Result<MovementNode, anyhow::Error> {
 		match &self.runner {
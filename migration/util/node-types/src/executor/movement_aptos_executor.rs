@@ -1,8 +1,11 @@
+use anyhow::Context;
 use either::Either;
 pub use mtma_types::movement_aptos::aptos_executor::block_executor::BlockExecutor as MovementAptosBlockExecutor;
+use mtma_types::movement_aptos::aptos_config::config::{StorageConfig, StorageDirPaths};
+use mtma_types::movement_aptos::aptos_db::AptosDB;
 use mtma_types::movement_aptos::aptos_storage_interface::{
 	state_store::state_view::db_state_view::{DbStateView, DbStateViewAtVersion},
-	DbReader,
+	DbReader, DbReaderWriter,
 };
 use mtma_types::movement_aptos::aptos_types::state_store::state_key::StateKey;
 pub use mtma_types::movement_aptos::aptos_vm::aptos_vm::AptosVMBlockExecutor;
@@ -35,6 +38,26 @@ impl MovementAptosNode {
 		Self { block_executor: Arc::new(block_executor), db_dir_path }
 	}
 
+	/// Opens the Movement Aptos db in a dir read-only.
+	///
+	/// This can be used while a node is running on the same db, e.g. to check criteria against a migrator.
+	pub fn try_read_only(db_dir_path: PathBuf) -> Result<Self, anyhow::Error> {
+		let storage_config = StorageConfig::default();
+		let aptos_db = AptosDB::open(
+			StorageDirPaths::from_path(db_dir_path.clone()),
+			true,
+			Default::default(),
+			Default::default(),
+			false,
+			storage_config.buffered_state_target_items,
+			storage_config.max_num_nodes_per_lru_cache_shard,
+			None,
+		)
+		.context("failed to open movement aptos db read-only")?;
+		let block_executor = MovementAptosBlockExecutor::new(DbReaderWriter::new(aptos_db));
+		Ok(Self::new(block_executor, db_dir_path))
+	}
+
 	/// Borrows the block executor.
 	pub fn block_executor(&self) -> &MovementAptosBlockExecutor<AptosVMBlockExecutor> {
 		&self.block_executor
@@ -50,6 +73,16 @@ impl MovementAptosNode {
 		&self.db_dir_path
 	}
 
+	/// Gets the latest ledger version.
+	pub fn latest_ledger_version(&self) -> Result<u64, anyhow::Error> {
+		let latest_ledger_info = self
+			.db_reader()
+			.get_latest_ledger_info()
+			.context("failed to get latest ledger info")?;
+
+		Ok(latest_ledger_info.ledger_info().version())
+	}
+
 	/// Gets the state view at a given version.
 	pub fn state_view_at_version(
 		&self,
@@ -2,8 +2,12 @@ use anyhow::Context;
 use either::Either;
 pub use maptos_opt_executor::Executor as MovementOptExecutor;
 use movement_util::common_args::MovementArgs;
+use mtma_types::movement::aptos_config::config::{StorageConfig, StorageDirPaths};
 use mtma_types::movement::aptos_crypto::HashValue;
-use mtma_types::movement::aptos_storage_interface::state_view::DbStateView;
+use mtma_types::movement::aptos_db::AptosDB;
+use mtma_types::movement::aptos_storage_interface::state_view::{
+	DbStateView, DbStateViewAtVersion,
+};
 use mtma_types::movement::aptos_storage_interface::DbReader;
 use mtma_types::movement::aptos_types::state_store::state_key::StateKey;
 use mtma_types::movement::aptos_types::transaction::Version;
@@ -28,10 +32,16 @@ use walkdir::WalkDir;
 
 /// The Movement executor as would be presented in the criterion.
 pub struct MovementNode {
-	/// The opt executor.
+	/// The opt executor, which a node opened read-only does not have.
 	///
 	/// We will have this remain private because I don't think we want people mutating it in the criterion.
-	opt_executor: MovementOptExecutor,
+	opt_executor: Option<MovementOptExecutor>,
+
+	/// The db reader, through which the criteria read the node.
+	db_reader: Arc<dyn DbReader>,
+
+	/// The chain id.
+	chain_id: ChainId,
 }
 
 /// Copies a directory recursively while ignoring specified paths
@@ -118,7 +128,57 @@ fn set_permissions_recursive(path: &Path, permissions: Permissions) -> std::io::
 
 impl MovementNode {
 	pub fn new(opt_executor: MovementOptExecutor) -> Self {
-		Self { opt_executor }
+		let db_reader = opt_executor.db_reader();
+		let chain_id = opt_executor.config().chain.maptos_chain_id.clone();
+		Self { opt_executor: Some(opt_executor), db_reader, chain_id }
+	}
+
+	/// Opens the Movement db in a dir read-only, i.e. the dir containing `.movement`.
+	///
+	/// Unlike [MovementNode::try_from_dir], the dir is neither copied nor modified, so this can be used against a production node.
+	/// The node has no executor, so it can be read, e.g. by the criteria, but not executed on.
+	pub async fn try_read_only(dir: PathBuf) -> Result<Self, anyhow::Error> {
+		let movement_dir = dir.join(".movement");
+		let movement_args =
+			MovementArgs { movement_path: Some(movement_dir.display().to_string()) };
+
+		let config = movement_args.config().await.context("failed to get movement config")?;
+		let maptos_config = config.execution_config.maptos_config;
+		let old_db_path = maptos_config
+			.chain
+			.maptos_db_path
+			.clone()
+			.context("failed to get old db path")?;
+
+		// the db path is based on a docker volume, so it is found relative to the movement dir as in [MovementNode::try_from_dir]
+		let rel_path = old_db_path.strip_prefix("/.movement").context(
+			"old db path must begin with /.movement since it is based on a docker volume",
+		)?;
+		let db_path = movement_dir.join(".movement").join(rel_path);
+
+		Self::try_read_only_db(db_path, maptos_config.chain.maptos_chain_id.clone())
+	}
+
+	/// Opens a Movement db read-only at its path, for the given chain.
+	///
+	/// This can be used while a node is running on the same db, e.g. to check criteria against a generated node.
+	pub fn try_read_only_db(db_path: PathBuf, chain_id: ChainId) -> Result<Self, anyhow::Error> {
+		let storage_config = StorageConfig::default();
+		let aptos_db = AptosDB::open(
+			StorageDirPaths::from_path(db_path.clone()),
+			true,
+			Default::default(),
+			Default::default(),
+			false,
+			storage_config.buffered_state_target_items,
+			storage_config.max_num_nodes_per_lru_cache_shard,
+			None,
+		)
+		.with_context(|| {
+			format!("failed to open movement db read-only at {}", db_path.display())
+		})?;
+
+		Ok(Self { opt_executor: None, db_reader: Arc::new(aptos_db), chain_id })
 	}
 
 	pub async fn try_from_dir(dir: PathBuf) -> Result<Self, anyhow::Error> {
@@ -183,24 +243,74 @@ impl MovementNode {
 		Ok(Self::new(opt_executor))
 	}
 
-	/// Borrows the opt executor.
-	pub fn opt_executor(&self) -> &MovementOptExecutor {
-		&self.opt_executor
+	/// Shuts down an opt executor and opens two nodes with its config, one on its db and one on a copy of it in the dir.
+	///
+	/// This is useful to check that running the same blocks from the same state gives the same ledger.
+	pub async fn try_duplicate(
+		opt_executor: MovementOptExecutor,
+		dir: &Path,
+	) -> Result<(Self, Self), anyhow::Error> {
+		let maptos_config = opt_executor.config().clone();
+		let db_path = maptos_config.chain.maptos_db_path.clone().context("failed to get db path")?;
+
+		// the db must be closed before it is copied
+		drop(opt_executor);
+		copy_dir_recursive_with_ignore(&db_path, Vec::<PathBuf>::new(), dir)
+			.context("failed to copy db")?;
+
+		let mut duplicate_config = maptos_config.clone();
+		duplicate_config.chain.maptos_db_path = Some(dir.to_path_buf());
+
+		let (sender, _receiver) = futures_channel::mpsc::channel(1024);
+		let original = MovementOptExecutor::try_from_config(maptos_config, sender)
+			.await
+			.context("failed to create movement opt executor")?;
+		let (sender, _receiver) = futures_channel::mpsc::channel(1024);
+		let duplicate = MovementOptExecutor::try_from_config(duplicate_config, sender)
+			.await
+			.context("failed to create duplicate movement opt executor")?;
+
+		Ok((Self::new(original), Self::new(duplicate)))
 	}
 
-	/// Borrows the opt executor mutably.
-	pub fn opt_executor_mut(&mut self) -> &mut MovementOptExecutor {
-		&mut self.opt_executor
+	/// Borrows the opt executor, which a node opened read-only does not have.
+	pub fn opt_executor(&self) -> Result<&MovementOptExecutor, anyhow::Error> {
+		self.opt_executor
+			.as_ref()
+			.context("movement node was opened read-only, so it has no executor")
+	}
+
+	/// Borrows the opt executor mutably, which a node opened read-only does not have.
+	pub fn opt_executor_mut(&mut self) -> Result<&mut MovementOptExecutor, anyhow::Error> {
+		self.opt_executor
+			.as_mut()
+			.context("movement node was opened read-only, so it has no executor")
+	}
+
+	/// Forms a read-only node which reads through the same db reader, without the executor.
+	///
+	/// This is cheap, so that the node can be shared, e.g. with criteria which outlive a borrow of it.
+	pub fn reader(&self) -> Self {
+		Self {
+			opt_executor: None,
+			db_reader: self.db_reader.clone(),
+			chain_id: self.chain_id.clone(),
+		}
+	}
+
+	/// Gets an [Arc] to the db reader.
+	pub fn db_reader(&self) -> Arc<dyn DbReader> {
+		self.db_reader.clone()
 	}
 
 	/// Gets a clone of the chain id.
 	pub fn chain_id(&self) -> ChainId {
-		self.opt_executor().config().chain.maptos_chain_id.clone()
+		self.chain_id.clone()
 	}
 
 	/// Gets the latest version of the ledger.
 	pub fn latest_ledger_version(&self) -> Result<u64, anyhow::Error> {
-		let db_reader = self.opt_executor().db_reader();
+		let db_reader = self.db_reader();
 
 		let latest_ledger_info =
 			db_reader.get_latest_ledger_info().context("failed to get latest ledger info")?;
@@ -208,25 +318,71 @@ impl MovementNode {
 		Ok(latest_ledger_info.ledger_info().version())
 	}
 
+	/// Gets the latest epoch of the ledger.
+	pub fn latest_epoch(&self) -> Result<u64, anyhow::Error> {
+		let db_reader = self.db_reader();
+
+		let latest_ledger_info =
+			db_reader.get_latest_ledger_info().context("failed to get latest ledger info")?;
+
+		Ok(latest_ledger_info.ledger_info().epoch())
+	}
+
+	/// Gets the timestamp of the latest ledger info, in microseconds.
+	pub fn latest_timestamp_micros(&self) -> Result<u64, anyhow::Error> {
+		let db_reader = self.db_reader();
+
+		let latest_ledger_info =
+			db_reader.get_latest_ledger_info().context("failed to get latest ledger info")?;
+
+		Ok(latest_ledger_info.ledger_info().timestamp_usecs())
+	}
+
+	/// Gets the last version of each epoch which has ended, in ascending order.
+	pub fn epoch_ending_versions(&self) -> Result<Vec<Version>, anyhow::Error> {
+		let db_reader = self.db_reader();
+		let latest_epoch = self.latest_epoch()?;
+
+		// The db reader pages epoch ending ledger infos, so we keep asking until it says there are no more.
+		let mut versions = Vec::new();
+		let mut start_epoch = 0;
+		while start_epoch < latest_epoch {
+			let epoch_change_proof = db_reader
+				.get_epoch_ending_ledger_infos(start_epoch, latest_epoch)
+				.context("failed to get epoch ending ledger infos")?;
+
+			for ledger_info_with_sigs in &epoch_change_proof.ledger_info_with_sigs {
+				versions.push(ledger_info_with_sigs.ledger_info().version());
+				start_epoch = ledger_info_with_sigs.ledger_info().epoch() + 1;
+			}
+
+			if !epoch_change_proof.more || epoch_change_proof.ledger_info_with_sigs.is_empty() {
+				break;
+			}
+		}
+
+		Ok(versions)
+	}
+
 	/// Constructs a [DbStateView] at a given version.
 	pub fn state_view_at_version(
 		&self,
 		version: Option<u64>,
 	) -> Result<DbStateView, anyhow::Error> {
-		self.opt_executor().state_view_at_version(version)
+		Ok(self.db_reader().state_view_at_version(version)?)
 	}
 
 	/// Gets the all [StateKey]s in the global storage dating back to an original version. None is treated as 0 or all versions.
 	pub fn global_state_keys_from_version(&self, version: Option<u64>) -> GlobalStateKeyIterable {
 		GlobalStateKeyIterable {
-			db_reader: self.opt_executor().db_reader(),
+			db_reader: self.db_reader(),
 			version: version.unwrap_or(0),
 		}
 	}
 
 	/// Gets the genesis block hash.
 	pub fn genesis_block_hash(&self) -> Result<HashValue, anyhow::Error> {
-		let db_reader = self.opt_executor().db_reader();
+		let db_reader = self.db_reader();
 		let (_start, _end, block_event) = db_reader.get_block_info_by_version(0)?;
 		Ok(block_event.hash()?)
 	}
@@ -240,7 +396,7 @@ impl MovementNode {
 	/// Gets the genesis transaction.
 	pub fn genesis_transaction(&self) -> Result<Transaction, anyhow::Error> {
 		// get genesis transaction from db
-		let db_reader = self.opt_executor().db_reader();
+		let db_reader = self.db_reader();
 		let genesis_transaction =
 			db_reader.get_transaction_by_version(0, self.latest_ledger_version()?, true)?;
 		Ok(genesis_transaction.transaction)
@@ -278,7 +434,7 @@ impl<'a> Iterator for BlockIterator<'a> {
 			return None;
 		}
 
-		let db_reader = self.executor.opt_executor().db_reader();
+		let db_reader = self.executor.db_reader();
 		let (start_version, end_version, new_block_event) =
 			match db_reader.get_block_info_by_version(self.version) {
 				Ok(info) => info,
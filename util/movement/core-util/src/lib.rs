@@ -1,3 +1,5 @@
+pub use maptos_aptos_config as aptos_config;
+pub use maptos_aptos_db as aptos_db;
 pub use maptos_opt_executor::{self, *};
 pub use movement_client::{self, *};
 pub use movement_config;